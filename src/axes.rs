@@ -1,8 +1,23 @@
 //! Axes functionality for plots
 
+use std::sync::Arc;
+
+use crate::IntoVec;
+use crate::category::{self, Categories};
+use crate::colorbar::Colorbar;
 use crate::colors::Color;
-use crate::plot::Plot;
-use crate::utils::{calculate_range, format_number, generate_ticks, map_range};
+use crate::hist::{Bins, HistOptions, Histogram};
+use crate::layout::{EDGE_PAD, LABEL_GAP, Margins};
+use crate::plot::{BarAlign, Orientation, Plot, PlotType};
+use crate::render::{Anchor, Group, PathData, Renderer, Style, SvgRenderer, TextStyle};
+use crate::scale::{PlotArea, Scale};
+use crate::secondary::SecondaryAxis;
+use crate::text::{ascent, descent, text_width};
+use crate::ticker::{
+    Formatter, GridStyle, Locator, TickDirection, TickLabelStyle, Ticker, auto_minor_ticks,
+};
+use crate::utils::{calculate_range_sticky, generate_ticks};
+use crate::violin::ViolinSide;
 
 /// Represents a set of axes for plotting
 #[derive(Debug)]
@@ -14,15 +29,294 @@ pub struct Axes {
     pub title: Option<String>,
     pub x_limits: Option<(f64, f64)>,
     pub y_limits: Option<(f64, f64)>,
+    pub x_scale: Scale,
+    pub y_scale: Scale,
+    /// String categories along the x axis, shared by all plots
+    pub x_categories: Categories,
+    /// String categories along the y axis, shared by all plots
+    pub y_categories: Categories,
+    pub x_ticker: Ticker,
+    pub y_ticker: Ticker,
     pub grid: bool,
     pub legend: bool,
     pub background_color: Color,
     pub grid_color: Color,
+    /// Style of the grid lines at major ticks
+    pub major_grid: GridStyle,
+    /// Style of the grid lines at minor ticks; no minor grid if unset
+    pub minor_grid: Option<GridStyle>,
+    /// Draw the grid over the data instead of behind it
+    pub grid_above: bool,
     pub text_color: Color,
     pub font_size: f64,
     pub show_x_axis: bool,
     pub show_y_axis: bool,
     pub equal_aspect: bool,
+    pub colorbar: Option<Colorbar>,
+    /// Draw the x axis along the top instead of the bottom
+    pub x_axis_top: bool,
+    /// Draw the y axis along the right instead of the left
+    pub y_axis_right: bool,
+    /// Axes overlaid on these ones, sharing one of their axes
+    pub twins: Vec<(SharedAxis, Axes)>,
+    /// Converted x axis along the top
+    pub secondary_x: Option<SecondaryAxis>,
+    /// Converted y axis along the right
+    pub secondary_y: Option<SecondaryAxis>,
+}
+
+/// Settings a figure imposes on axes drawn as one of its subplots
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct SubplotContext {
+    /// Limits shared with other subplots, replacing these axes' own
+    pub x_limits: Option<(f64, f64)>,
+    pub y_limits: Option<(f64, f64)>,
+    /// Tick labels left to a neighbouring subplot on the same shared axis
+    pub hide_x_ticklabels: bool,
+    pub hide_y_ticklabels: bool,
+    /// Margins around the plotting area; [`DEFAULT_MARGIN`](crate::layout::DEFAULT_MARGIN) if unset
+    pub margins: Option<Margins>,
+    /// Leave the plotting area background unpainted
+    pub transparent: bool,
+}
+
+/// The axis a twin shares with the axes it was created from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedAxis {
+    X,
+    Y,
+}
+
+/// Side of the plotting area an axis is drawn along
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Side {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// An axis line with its ticks and tick labels along one side of the plotting area
+///
+/// Tick positions are in pixels from the start of the plotting area.
+#[derive(Debug)]
+struct SideAxis<'a> {
+    side: Side,
+    ticker: &'a Ticker,
+    ticks: Vec<(f64, String)>,
+    minor: Vec<f64>,
+    /// Shared offset or power of ten of the tick labels
+    offset_text: Option<String>,
+    /// Length of the axis in pixels
+    length: f64,
+    font_size: f64,
+    /// Style of the tick labels
+    text_style: TextStyle,
+}
+
+impl SideAxis<'_> {
+    fn has_labels(&self) -> bool {
+        self.ticks.iter().any(|(_, label)| !label.is_empty())
+    }
+
+    fn widest_label(&self) -> f64 {
+        self.ticks
+            .iter()
+            .map(|(_, label)| text_width(label, self.font_size))
+            .fold(0.0, f64::max)
+    }
+
+    /// Rotation of the tick labels in degrees
+    fn rotation(&self) -> f64 {
+        let style = &self.ticker.label_style;
+        match self.side {
+            Side::Bottom | Side::Top => {
+                // Labels wider than the gap between ticks are rotated so they don't overlap
+                let spacing = self
+                    .ticks
+                    .windows(2)
+                    .map(|pair| (pair[1].0 - pair[0].0).abs())
+                    .fold(self.length, f64::min);
+                style
+                    .rotation
+                    .unwrap_or(if self.widest_label() > spacing * 0.9 {
+                        45.0
+                    } else {
+                        0.0
+                    })
+            }
+            Side::Left | Side::Right => style.rotation.unwrap_or(0.0),
+        }
+    }
+
+    /// Distance from the axis line to the far edge of the tick labels
+    fn band(&self) -> f64 {
+        let (_, outside) = self.ticker.direction.extents(self.ticker.major_length);
+        if !self.has_labels() {
+            return outside;
+        }
+        let rotation = self.rotation().to_radians();
+        let (sin, cos) = (rotation.sin().abs(), rotation.cos().abs());
+        let widest = self.widest_label();
+        let labels = match self.side {
+            Side::Bottom | Side::Top if rotation != 0.0 => {
+                7.0 + widest * sin + self.font_size * 0.5 * cos
+            }
+            Side::Bottom | Side::Top => 3.0 + self.font_size,
+            Side::Left | Side::Right => 5.0 + widest * cos + self.font_size * sin,
+        };
+        let offset_text = match self.side {
+            Side::Bottom | Side::Top if self.offset_text.is_some() => self.font_size * 1.2,
+            _ => 0.0,
+        };
+        outside + labels + offset_text
+    }
+
+    /// How far the tick labels stick out past the start and the end of the axis
+    fn overhang(&self) -> (f64, f64) {
+        let rotation = self.rotation().to_radians();
+        let (mut before, mut after) = (0.0f64, 0.0f64);
+        for (position, label) in self.ticks.iter().filter(|(_, label)| !label.is_empty()) {
+            let width = text_width(label, self.font_size);
+            let (start, end) = match self.side {
+                // Rotated labels hang off one end of their tick
+                Side::Bottom if rotation != 0.0 => (position - width * rotation.cos(), *position),
+                Side::Top if rotation != 0.0 => (*position, position + width * rotation.cos()),
+                Side::Bottom | Side::Top => (position - width / 2.0, position + width / 2.0),
+                Side::Left | Side::Right => (
+                    position - self.font_size / 2.0,
+                    position + self.font_size / 2.0,
+                ),
+            };
+            before = before.max(-start);
+            after = after.max(end - self.length);
+        }
+        // Offset text of a vertical axis sits above its top end
+        if self.offset_text.is_some() && matches!(self.side, Side::Left | Side::Right) {
+            before = before.max(8.0 + ascent(self.font_size));
+        }
+        (before, after)
+    }
+
+    /// Draw the axis around `area`, whose top left corner is at `(left, top)`
+    fn draw(&self, renderer: &mut dyn Renderer, left: f64, top: f64, area: &PlotArea) {
+        let ticker = self.ticker;
+        let (right, bottom) = (left + area.width, top + area.height);
+
+        // Axis line
+        let (x1, y1, x2, y2) = match self.side {
+            Side::Bottom => (left, bottom, right, bottom),
+            Side::Top => (left, top, right, top),
+            Side::Left => (left, top, left, bottom),
+            Side::Right => (right, top, right, bottom),
+        };
+        renderer.line((x1, y1), (x2, y2), &Style::stroked(Color::AXIS_COLOR, 0.8));
+
+        // Tick mark at `position` along the side
+        let mut tick_mark = |position: f64, length: f64, width: f64| {
+            let (inside, outside) = ticker.direction.extents(length);
+            let (x1, y1, x2, y2) = match self.side {
+                Side::Bottom => (
+                    left + position,
+                    bottom - inside,
+                    left + position,
+                    bottom + outside,
+                ),
+                Side::Top => (
+                    left + position,
+                    top + inside,
+                    left + position,
+                    top - outside,
+                ),
+                Side::Left => (
+                    left + inside,
+                    top + position,
+                    left - outside,
+                    top + position,
+                ),
+                Side::Right => (
+                    right - inside,
+                    top + position,
+                    right + outside,
+                    top + position,
+                ),
+            };
+            renderer.line(
+                (x1, y1),
+                (x2, y2),
+                &Style::stroked(Color::AXIS_COLOR, width),
+            );
+        };
+        for &position in &self.minor {
+            tick_mark(position, ticker.minor_length, 0.6);
+        }
+        for (position, _) in &self.ticks {
+            tick_mark(*position, ticker.major_length, 0.8);
+        }
+
+        let (_, outside) = ticker.direction.extents(ticker.major_length);
+        let rotation = self.rotation();
+        let (ascent, descent) = (ascent(self.font_size), descent(self.font_size));
+        for (position, label) in self.ticks.iter().filter(|(_, label)| !label.is_empty()) {
+            let (x, y, anchor, dy) = match self.side {
+                Side::Bottom if rotation != 0.0 => {
+                    (left + position, bottom + outside + 7.0, Anchor::End, 0.35)
+                }
+                Side::Bottom => (
+                    left + position,
+                    bottom + outside + 3.0 + ascent,
+                    Anchor::Middle,
+                    0.0,
+                ),
+                Side::Top if rotation != 0.0 => {
+                    (left + position, top - outside - 7.0, Anchor::Start, 0.35)
+                }
+                Side::Top => (
+                    left + position,
+                    top - outside - 3.0 - descent,
+                    Anchor::Middle,
+                    0.0,
+                ),
+                Side::Left => (left - outside - 5.0, top + position, Anchor::End, 0.35),
+                Side::Right => (right + outside + 5.0, top + position, Anchor::Start, 0.35),
+            };
+            let style = self
+                .text_style
+                .clone()
+                .anchor(anchor)
+                .dy(dy)
+                .rotation(rotation);
+            renderer.text(x, y, label, &style);
+        }
+
+        // Offset text at the end of the axis
+        if let Some(ref text) = self.offset_text {
+            let band = self.band();
+            let (x, y, anchor) = match self.side {
+                Side::Bottom => (right, bottom + band - descent, Anchor::End),
+                Side::Top => (right, top - band + ascent, Anchor::End),
+                Side::Left => (left, top - 8.0, Anchor::Start),
+                Side::Right => (right, top - 8.0, Anchor::End),
+            };
+            renderer.text(x, y, text, &self.text_style.clone().anchor(anchor));
+        }
+    }
+}
+
+/// Thickness of the ticks and tick labels along each side of the plotting area
+fn tick_bands(axes: &[SideAxis]) -> Margins {
+    let mut bands = Margins::uniform(0.0);
+    for axis in axes {
+        let band = axis.band();
+        let side = match axis.side {
+            Side::Left => &mut bands.left,
+            Side::Right => &mut bands.right,
+            Side::Top => &mut bands.top,
+            Side::Bottom => &mut bands.bottom,
+        };
+        *side = side.max(band);
+    }
+    bands
 }
 
 impl Axes {
@@ -36,19 +330,57 @@ impl Axes {
             title: None,
             x_limits: None,
             y_limits: None,
+            x_scale: Scale::Linear,
+            y_scale: Scale::Linear,
+            x_categories: Categories::new(),
+            y_categories: Categories::new(),
+            x_ticker: Ticker::default(),
+            y_ticker: Ticker::default(),
             grid: true,
             legend: false,
             background_color: Color::WHITE,
             grid_color: Color::GRID_COLOR,
+            major_grid: GridStyle::new(),
+            minor_grid: None,
+            grid_above: false,
             text_color: Color::TEXT_COLOR,
             font_size: 16.0,
             show_x_axis: true,
             show_y_axis: true,
             equal_aspect: false,
+            colorbar: None,
+            x_axis_top: false,
+            y_axis_right: false,
+            twins: Vec::new(),
+            secondary_x: None,
+            secondary_y: None,
         }
     }
 
     pub fn add_plot(&mut self, mut plot: Plot) -> &mut Self {
+        match plot.plot_type {
+            PlotType::Pie(_) => {
+                // Pies are round and have no meaningful axes
+                self.grid = false;
+                self.show_x_axis = false;
+                self.show_y_axis = false;
+                self.equal_aspect = true;
+            }
+            PlotType::Image(_) => {
+                // Grid lines would be drawn over the cells
+                self.grid = false;
+            }
+            _ => {}
+        }
+
+        // Categories keep the positions they were first given by any plot
+        if let Some(ref names) = plot.x_categories {
+            register_categories(&mut self.x_categories, names, &mut plot.x_data);
+        }
+        if let Some(ref names) = plot.y_categories {
+            register_categories(&mut self.y_categories, names, &mut plot.y_data);
+        }
+
         if plot.color.is_none() {
             plot.color = Some(crate::colors::get_cycle_color(self.plots.len()));
         }
@@ -57,6 +389,208 @@ impl Axes {
         self
     }
 
+    /// Add a vertical bar plot with bars at `x` of the given `height`
+    pub fn bar<X, H>(&mut self, x: X, height: H) -> &mut Self
+    where
+        X: IntoVec<f64>,
+        H: IntoVec<f64>,
+    {
+        self.add_plot(Plot::bar(x, height))
+    }
+
+    /// Add a horizontal bar plot with bars at `y` of the given `width`
+    pub fn barh<Y, W>(&mut self, y: Y, width: W) -> &mut Self
+    where
+        Y: IntoVec<f64>,
+        W: IntoVec<f64>,
+    {
+        self.add_plot(Plot::barh(y, width))
+    }
+
+    /// Add several bar series side by side at each position
+    ///
+    /// Each series keeps its own label and color, while widths and offsets are
+    /// computed so that the group of bars fills 80% of the unit spacing.
+    pub fn grouped_bar(&mut self, series: Vec<Plot>) -> &mut Self {
+        self.grouped_bar_with_width(series, 0.8)
+    }
+
+    /// Add several bar series side by side, with the whole group spanning `group_width`
+    pub fn grouped_bar_with_width(&mut self, series: Vec<Plot>, group_width: f64) -> &mut Self {
+        let count = series.len();
+        let width = group_width / count.max(1) as f64;
+
+        for (k, mut plot) in series.into_iter().enumerate() {
+            if let Some((style, positions, _)) = plot.bar_parts_mut() {
+                let offset = -group_width / 2.0 + width * (k as f64 + 0.5);
+                for position in positions.iter_mut() {
+                    *position += offset;
+                }
+                style.widths = vec![width];
+                style.align = BarAlign::Center;
+            }
+            self.add_plot(plot);
+        }
+        self
+    }
+
+    /// Add several bar series stacked on top of each other
+    ///
+    /// Bars are stacked by position (or category), starting from the baseline
+    /// of the first bar at each position. Positive and negative values are
+    /// stacked separately. Series that are not bar plots, or whose bars run in
+    /// the other orientation than the first bar series, are added unstacked.
+    pub fn stacked_bar(&mut self, series: Vec<Plot>) -> &mut Self {
+        let mut orientation = None;
+        // Position with the top of its positive and the bottom of its negative stack
+        let mut stacks: Vec<(f64, f64, f64)> = Vec::new();
+
+        for mut plot in series {
+            // Stack categorical bars at the positions the axes will give their categories
+            let mut shared_positions = match plot.plot_type {
+                PlotType::Bar(ref style) if style.orientation == Orientation::Vertical => {
+                    plot.x_categories.as_ref().map(|names| {
+                        let mut positions = plot.x_data.clone();
+                        register_categories(&mut self.x_categories, names, &mut positions);
+                        positions
+                    })
+                }
+                PlotType::Bar(_) => plot.y_categories.as_ref().map(|names| {
+                    let mut positions = plot.y_data.clone();
+                    register_categories(&mut self.y_categories, names, &mut positions);
+                    positions
+                }),
+                _ => None,
+            };
+
+            if let Some((style, positions, lengths)) = plot.bar_parts_mut()
+                && *orientation.get_or_insert(style.orientation) == style.orientation
+            {
+                let positions = shared_positions.get_or_insert_with(|| positions.clone());
+                let mut bottoms = Vec::with_capacity(lengths.len());
+                for (i, (&position, &length)) in positions.iter().zip(lengths.iter()).enumerate() {
+                    let same = |s: &(f64, f64, f64)| {
+                        (s.0 - position).abs() <= 1e-9 * position.abs().max(1.0)
+                    };
+                    let index = match stacks.iter().position(same) {
+                        Some(index) => index,
+                        None => {
+                            let bottom = style.bottom_at(i);
+                            stacks.push((position, bottom, bottom));
+                            stacks.len() - 1
+                        }
+                    };
+                    let stack = if length < 0.0 {
+                        &mut stacks[index].2
+                    } else {
+                        &mut stacks[index].1
+                    };
+                    bottoms.push(*stack);
+                    *stack += length;
+                }
+                style.bottoms = bottoms;
+            }
+            self.add_plot(plot);
+        }
+        self
+    }
+
+    /// Add a histogram of `data` and return the computed counts and bin edges
+    pub fn hist(&mut self, data: &[f64], options: &HistOptions) -> Histogram {
+        let histogram = Histogram::compute(data, options);
+        self.add_plot(histogram.to_plot(options.histtype));
+        histogram
+    }
+
+    /// Add a histogram of `data` with `bins` equal-width bins
+    pub fn histogram<D: IntoVec<f64>>(&mut self, data: D, bins: usize) -> &mut Self {
+        let options = HistOptions::new().bins(Bins::Count(bins));
+        self.hist(&data.into_vec(), &options);
+        self
+    }
+
+    /// Add a pie chart of `values` with optional wedge labels
+    pub fn pie<V: IntoVec<f64>>(&mut self, values: V, labels: Option<&[String]>) -> &mut Self {
+        let mut plot = Plot::pie(values);
+        if let Some(labels) = labels {
+            plot = plot.wedge_labels(labels);
+        }
+        self.add_plot(plot)
+    }
+
+    /// Add a box plot of `data` at position 1
+    pub fn boxplot<D: IntoVec<f64>>(&mut self, data: D) -> &mut Self {
+        self.add_plot(Plot::boxplot(data))
+    }
+
+    /// Add one box plot per dataset at positions 1, 2, 3, ... sharing one color
+    pub fn boxplots(&mut self, datasets: &[Vec<f64>]) -> &mut Self {
+        let color = crate::colors::get_cycle_color(self.plots.len());
+        for (i, data) in datasets.iter().enumerate() {
+            self.add_plot(
+                Plot::boxplot(data.as_slice())
+                    .position((i + 1) as f64)
+                    .color(color),
+            );
+        }
+        self
+    }
+
+    /// Add a violin plot of `data` at position 1
+    pub fn violin<D: IntoVec<f64>>(&mut self, data: D) -> &mut Self {
+        self.add_plot(Plot::violin(data))
+    }
+
+    /// Add one violin plot per dataset at positions 1, 2, 3, ... sharing one color
+    pub fn violins(&mut self, datasets: &[Vec<f64>]) -> &mut Self {
+        let color = crate::colors::get_cycle_color(self.plots.len());
+        for (i, data) in datasets.iter().enumerate() {
+            self.add_plot(
+                Plot::violin(data.as_slice())
+                    .position((i + 1) as f64)
+                    .color(color),
+            );
+        }
+        self
+    }
+
+    /// Add a split violin comparing two distributions at `position`
+    ///
+    /// `low` is drawn on the left half and `high` on the right half, each with
+    /// its own color from the color cycle.
+    pub fn split_violin(&mut self, low: &[f64], high: &[f64], position: f64) -> &mut Self {
+        self.add_plot(Plot::violin(low).position(position).side(ViolinSide::Low));
+        self.add_plot(Plot::violin(high).position(position).side(ViolinSide::High))
+    }
+
+    /// Add contour lines of `z` over the `x`/`y` grid, where `z[j][i]` is the value at `(x[i], y[j])`
+    pub fn contour<X, Y>(&mut self, x: X, y: Y, z: &[Vec<f64>]) -> &mut Self
+    where
+        X: IntoVec<f64>,
+        Y: IntoVec<f64>,
+    {
+        self.add_plot(Plot::contour(x, y, z.to_vec()))
+    }
+
+    /// Add filled contours of `z` over the `x`/`y` grid
+    pub fn contourf<X, Y>(&mut self, x: X, y: Y, z: &[Vec<f64>]) -> &mut Self
+    where
+        X: IntoVec<f64>,
+        Y: IntoVec<f64>,
+    {
+        self.add_plot(Plot::contourf(x, y, z.to_vec()))
+    }
+
+    /// Add an image of the matrix `z`, with row 0 at the top
+    pub fn imshow(&mut self, z: &[Vec<f64>]) -> &mut Self {
+        self.add_plot(Plot::imshow(z.to_vec()))
+    }
+
+    /// Add a heatmap of the matrix `z` with every cell annotated with its value
+    pub fn heatmap(&mut self, z: &[Vec<f64>], decimals: usize) -> &mut Self {
+        self.add_plot(Plot::imshow(z.to_vec()).annotate(decimals))
+    }
+
     /// Add custom SVG element
     pub fn add_svg_element(&mut self, svg_element: String) {
         self.custom_svg_elements.push(svg_element);
@@ -92,12 +626,162 @@ impl Axes {
         self
     }
 
+    /// Set the X-axis scale, e.g. `Scale::log()`, `"symlog".parse()?` or `Scale::custom(..)`
+    pub fn set_xscale(&mut self, scale: Scale) -> &mut Self {
+        self.x_scale = scale;
+        self
+    }
+
+    /// Set the Y-axis scale, e.g. `Scale::log()`, `"symlog".parse()?` or `Scale::custom(..)`
+    pub fn set_yscale(&mut self, scale: Scale) -> &mut Self {
+        self.y_scale = scale;
+        self
+    }
+
+    /// Place x ticks with a locator, e.g. `MultipleLocator::new(0.5)`
+    ///
+    /// Replaces ticks and tick labels given with `set_xticks`/`set_xticklabels`.
+    pub fn set_xlocator<L: Locator + 'static>(&mut self, locator: L) -> &mut Self {
+        self.x_ticker.set_locator(Arc::new(locator));
+        self
+    }
+
+    /// Place y ticks with a locator
+    ///
+    /// Replaces ticks and tick labels given with `set_yticks`/`set_yticklabels`.
+    pub fn set_ylocator<L: Locator + 'static>(&mut self, locator: L) -> &mut Self {
+        self.y_ticker.set_locator(Arc::new(locator));
+        self
+    }
+
+    /// Write x tick labels with a formatter, e.g. `PercentFormatter::new(1.0)`
+    pub fn set_xformatter<F: Formatter + 'static>(&mut self, formatter: F) -> &mut Self {
+        self.x_ticker.formatter = Some(Arc::new(formatter));
+        self.x_ticker.fixed_labels = None;
+        self
+    }
+
+    /// Write y tick labels with a formatter
+    pub fn set_yformatter<F: Formatter + 'static>(&mut self, formatter: F) -> &mut Self {
+        self.y_ticker.formatter = Some(Arc::new(formatter));
+        self.y_ticker.fixed_labels = None;
+        self
+    }
+
+    /// Place x ticks at exactly these values
+    pub fn set_xticks<T: IntoVec<f64>>(&mut self, ticks: T) -> &mut Self {
+        self.x_ticker.set_fixed_values(ticks.into_vec());
+        self
+    }
+
+    /// Place y ticks at exactly these values
+    pub fn set_yticks<T: IntoVec<f64>>(&mut self, ticks: T) -> &mut Self {
+        self.y_ticker.set_fixed_values(ticks.into_vec());
+        self
+    }
+
+    /// Label the x ticks with these texts, paired with the values given to `set_xticks` if any
+    pub fn set_xticklabels<L, S>(&mut self, labels: L) -> &mut Self
+    where
+        L: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.x_ticker.set_fixed_labels(category::names(labels));
+        self
+    }
+
+    /// Label the y ticks with these texts, paired with the values given to `set_yticks` if any
+    pub fn set_yticklabels<L, S>(&mut self, labels: L) -> &mut Self
+    where
+        L: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.y_ticker.set_fixed_labels(category::names(labels));
+        self
+    }
+
+    /// Set the rotation, font and color of the x tick labels
+    pub fn set_xticklabel_style(&mut self, style: TickLabelStyle) -> &mut Self {
+        self.x_ticker.label_style = style;
+        self
+    }
+
+    /// Set the rotation, font and color of the y tick labels
+    pub fn set_yticklabel_style(&mut self, style: TickLabelStyle) -> &mut Self {
+        self.y_ticker.label_style = style;
+        self
+    }
+
     /// Enable or disable grid
     pub fn grid(&mut self, enable: bool) -> &mut Self {
         self.grid = enable;
         self
     }
 
+    /// Set the style of the grid lines at major ticks and enable the grid
+    pub fn set_grid_style(&mut self, style: GridStyle) -> &mut Self {
+        self.major_grid = style;
+        self.grid = true;
+        self
+    }
+
+    /// Add grid lines at the minor ticks, e.g. with `GridStyle::minor()`
+    pub fn set_minor_grid(&mut self, style: GridStyle) -> &mut Self {
+        self.minor_grid = Some(style);
+        self.grid = true;
+        self
+    }
+
+    /// Draw the grid over the data instead of behind it
+    pub fn grid_above(&mut self, above: bool) -> &mut Self {
+        self.grid_above = above;
+        self
+    }
+
+    /// Show minor ticks on both axes
+    pub fn minorticks_on(&mut self) -> &mut Self {
+        self.x_ticker.minor = Some(true);
+        self.y_ticker.minor = Some(true);
+        self
+    }
+
+    /// Hide minor ticks on both axes, including logarithmic ones
+    pub fn minorticks_off(&mut self) -> &mut Self {
+        self.x_ticker.minor = Some(false);
+        self.y_ticker.minor = Some(false);
+        self
+    }
+
+    /// Place and show x minor ticks with a locator
+    pub fn set_xminor_locator<L: Locator + 'static>(&mut self, locator: L) -> &mut Self {
+        self.x_ticker.minor_locator = Some(Arc::new(locator));
+        self.x_ticker.minor = Some(true);
+        self
+    }
+
+    /// Place and show y minor ticks with a locator
+    pub fn set_yminor_locator<L: Locator + 'static>(&mut self, locator: L) -> &mut Self {
+        self.y_ticker.minor_locator = Some(Arc::new(locator));
+        self.y_ticker.minor = Some(true);
+        self
+    }
+
+    /// Draw tick marks on both axes inside, outside or across the axis line
+    pub fn set_tick_direction(&mut self, direction: TickDirection) -> &mut Self {
+        self.x_ticker.direction = direction;
+        self.y_ticker.direction = direction;
+        self
+    }
+
+    /// Set the length of major and minor tick marks on both axes in pixels
+    pub fn set_tick_length(&mut self, major: f64, minor: f64) -> &mut Self {
+        for ticker in [&mut self.x_ticker, &mut self.y_ticker] {
+            ticker.major_length = major;
+            ticker.minor_length = minor;
+        }
+        self
+    }
+
     /// Enable or disable legend
     pub fn legend(&mut self, enable: bool) -> &mut Self {
         self.legend = enable;
@@ -122,17 +806,148 @@ impl Axes {
         self
     }
 
+    /// Attach a colorbar for the last color-mapped plot (or the colorbar's mappable)
+    pub fn colorbar(&mut self, colorbar: Colorbar) -> &mut Self {
+        self.colorbar = Some(colorbar);
+        self
+    }
+
+    /// Overlay axes sharing the x axis, with their own y axis on the right
+    ///
+    /// The twin has its own range, ticks, label and color cycle. The shared x
+    /// axis is widened to fit the data of both.
+    pub fn twinx(&mut self) -> &mut Axes {
+        let mut twin = self.twin();
+        twin.show_x_axis = false;
+        twin.y_axis_right = true;
+        self.twins.push((SharedAxis::X, twin));
+        &mut self.twins.last_mut().unwrap().1
+    }
+
+    /// Overlay axes sharing the y axis, with their own x axis along the top
+    pub fn twiny(&mut self) -> &mut Axes {
+        let mut twin = self.twin();
+        twin.show_y_axis = false;
+        twin.x_axis_top = true;
+        self.twins.push((SharedAxis::Y, twin));
+        &mut self.twins.last_mut().unwrap().1
+    }
+
+    /// Empty axes styled like these ones, for overlaying
+    fn twin(&self) -> Axes {
+        let mut twin = Axes::new();
+        twin.grid = false;
+        twin.x_scale = self.x_scale.clone();
+        twin.y_scale = self.y_scale.clone();
+        twin.font_size = self.font_size;
+        twin.text_color = self.text_color;
+        twin
+    }
+
+    /// Add an x axis along the top showing the x values converted by `forward`
+    ///
+    /// `inverse` converts back, e.g. `secondary_xaxis(|c| c * 1.8 + 32.0, |f| (f - 32.0) / 1.8)`
+    /// shows Celsius data in Fahrenheit.
+    pub fn secondary_xaxis<F, I>(&mut self, forward: F, inverse: I) -> &mut SecondaryAxis
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+        I: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        self.secondary_x
+            .insert(SecondaryAxis::new(forward, inverse))
+    }
+
+    /// Add a y axis along the right showing the y values converted by `forward`
+    pub fn secondary_yaxis<F, I>(&mut self, forward: F, inverse: I) -> &mut SecondaryAxis
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+        I: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        self.secondary_y
+            .insert(SecondaryAxis::new(forward, inverse))
+    }
+
     /// Calculate the data ranges for all plots
-    fn calculate_data_ranges(&self) -> ((f64, f64), (f64, f64)) {
-        if self.plots.is_empty() {
+    pub(crate) fn calculate_data_ranges(&self) -> ((f64, f64), (f64, f64)) {
+        if self.plots.is_empty() && self.twins.iter().all(|(_, t)| t.plots.is_empty()) {
             return ((0.0, 1.0), (0.0, 1.0));
         }
 
         let mut all_x: Vec<f64> = Vec::new();
         let mut all_y: Vec<f64> = Vec::new();
+        let mut sticky_x: Vec<f64> = Vec::new();
+        let mut sticky_y: Vec<f64> = Vec::new();
+        let mut invert_y = false;
 
         for plot in &self.plots {
-            match plot.plot_type {
+            match &plot.plot_type {
+                PlotType::Bar(style) => {
+                    // Bars cover their full extent and rest on a baseline that gets no padding
+                    for (x0, x1, y0, y1) in plot.bar_rects() {
+                        all_x.extend([x0, x1]);
+                        all_y.extend([y0, y1]);
+                        match style.orientation {
+                            Orientation::Vertical => sticky_y.push(y0),
+                            Orientation::Horizontal => sticky_x.push(x0),
+                        }
+                    }
+                }
+                PlotType::Box(style) => {
+                    if let Some(stats) = plot.box_stats() {
+                        let (low, high) = stats.value_range();
+                        // Keep half a unit around each box like categorical positions
+                        let positions = [style.position - 0.5, style.position + 0.5];
+                        match style.orientation {
+                            Orientation::Vertical => {
+                                all_x.extend(positions);
+                                all_y.extend([low, high]);
+                            }
+                            Orientation::Horizontal => {
+                                all_x.extend([low, high]);
+                                all_y.extend(positions);
+                            }
+                        }
+                    }
+                }
+                PlotType::Violin(style) => {
+                    if let Some(outline) = plot.violin_outline() {
+                        let values = [outline.values[0], outline.values[outline.values.len() - 1]];
+                        let positions = [style.position - 0.5, style.position + 0.5];
+                        match style.orientation {
+                            Orientation::Vertical => {
+                                all_x.extend(positions);
+                                all_y.extend(values);
+                            }
+                            Orientation::Horizontal => {
+                                all_x.extend(values);
+                                all_y.extend(positions);
+                            }
+                        }
+                    }
+                }
+                PlotType::Contour(_) => {
+                    // The grid is drawn edge to edge without padding
+                    all_x.extend(&plot.x_data);
+                    all_y.extend(&plot.y_data);
+                    sticky_x.extend(&plot.x_data);
+                    sticky_y.extend(&plot.y_data);
+                }
+                PlotType::Image(_) => {
+                    if let Some((left, right, bottom, top)) = plot.image_extent() {
+                        all_x.extend([left, right]);
+                        all_y.extend([bottom, top]);
+                        sticky_x.extend([left, right]);
+                        sticky_y.extend([bottom, top]);
+                        // Images with the first row on top are shown with a flipped y axis
+                        invert_y |= bottom > top;
+                    }
+                }
+                PlotType::Pie(style) => {
+                    // Pies are centered on the origin with unit radius
+                    let extent = style.extent();
+                    all_x.extend([-extent, extent]);
+                    all_y.extend([-extent, extent]);
+                }
                 _ => {
                     // Regular plots use both x and y data
                     all_x.extend(&plot.x_data);
@@ -141,12 +956,96 @@ impl Axes {
             }
         }
 
-        let x_range = self.x_limits.unwrap_or_else(|| calculate_range(&all_x));
-        let y_range = self.y_limits.unwrap_or_else(|| calculate_range(&all_y));
+        // Categories keep half a unit around them like box positions
+        if !self.x_categories.is_empty() {
+            all_x.extend([-0.5, self.x_categories.len() as f64 - 0.5]);
+        }
+        if !self.y_categories.is_empty() {
+            all_y.extend([-0.5, self.y_categories.len() as f64 - 0.5]);
+        }
+
+        let mut x_range = self
+            .x_limits
+            .unwrap_or_else(|| scaled_range(&self.x_scale, &all_x, &sticky_x));
+        let mut y_range = self.y_limits.unwrap_or_else(|| {
+            let (min, max) = scaled_range(&self.y_scale, &all_y, &sticky_y);
+            if invert_y { (max, min) } else { (min, max) }
+        });
+
+        // A shared axis also fits the data of the twins
+        let widen = |range: (f64, f64), other: (f64, f64)| {
+            if self.plots.is_empty() {
+                other
+            } else {
+                union(range, other)
+            }
+        };
+        for (shared, twin) in self.twins.iter().filter(|(_, t)| !t.plots.is_empty()) {
+            let (twin_x, twin_y) = twin.calculate_data_ranges();
+            match shared {
+                SharedAxis::X if self.x_limits.is_none() => x_range = widen(x_range, twin_x),
+                SharedAxis::Y if self.y_limits.is_none() => y_range = widen(y_range, twin_y),
+                _ => {}
+            }
+        }
 
         (x_range, y_range)
     }
 
+    /// Major tick values of the x axis; the limits may be inverted
+    fn x_ticks(&self, x_min: f64, x_max: f64) -> Vec<f64> {
+        let (low, high) = (x_min.min(x_max), x_min.max(x_max));
+        if let Some(ref locator) = self.x_ticker.locator {
+            return locator.ticks(low, high);
+        }
+        if !self.x_categories.is_empty() {
+            return self.x_categories.ticks(low, high);
+        }
+        match self.x_scale {
+            Scale::Linear => generate_ticks(low, high, 12),
+            ref scale => scale.ticks(low, high),
+        }
+    }
+
+    /// Major tick values of the y axis; the limits may be inverted
+    fn y_ticks(&self, y_min: f64, y_max: f64) -> Vec<f64> {
+        let (low, high) = (y_min.min(y_max), y_min.max(y_max));
+        if let Some(ref locator) = self.y_ticker.locator {
+            return locator.ticks(low, high);
+        }
+        if !self.y_categories.is_empty() {
+            return self.y_categories.ticks(low, high);
+        }
+        match self.y_scale {
+            Scale::Linear => self.generate_adaptive_ticks(low, high, 9),
+            ref scale => scale.ticks(low, high),
+        }
+    }
+
+    /// Minor tick values of the x axis between the major `ticks`
+    fn x_minor_ticks(&self, x_min: f64, x_max: f64, ticks: &[f64]) -> Vec<f64> {
+        minor_ticks(
+            &self.x_ticker,
+            &self.x_scale,
+            &self.x_categories,
+            ticks,
+            x_min,
+            x_max,
+        )
+    }
+
+    /// Minor tick values of the y axis between the major `ticks`
+    fn y_minor_ticks(&self, y_min: f64, y_max: f64, ticks: &[f64]) -> Vec<f64> {
+        minor_ticks(
+            &self.y_ticker,
+            &self.y_scale,
+            &self.y_categories,
+            ticks,
+            y_min,
+            y_max,
+        )
+    }
+
     /// Generate adaptive ticks that tries to produce the target count
     fn generate_adaptive_ticks(&self, min: f64, max: f64, target_count: usize) -> Vec<f64> {
         if min >= max || target_count == 0 {
@@ -214,248 +1113,549 @@ impl Axes {
 
     /// Generate SVG for the axes
     pub fn to_svg(&self, width: f64, height: f64) -> String {
-        let margin = 60.0;
-        let plot_width = width - 2.0 * margin;
-        let plot_height = height - 2.0 * margin;
-
-        let ((mut x_min, mut x_max), (mut y_min, mut y_max)) = self.calculate_data_ranges();
-
-        // Apply equal aspect ratio if enabled
-        if self.equal_aspect {
-            let x_range = x_max - x_min;
-            let y_range = y_max - y_min;
-            let x_scale = plot_width / x_range;
-            let y_scale = plot_height / y_range;
-
-            // Use the smaller scale to ensure both axes fit
-            let scale = x_scale.min(y_scale);
-
-            // Adjust ranges to maintain equal scaling
-            let new_x_range = plot_width / scale;
-            let new_y_range = plot_height / scale;
-
-            let x_center = (x_min + x_max) / 2.0;
-            let y_center = (y_min + y_max) / 2.0;
+        let mut svg = SvgRenderer::new();
+        self.draw(&mut svg, width, height);
+        svg.finish()
+    }
 
-            x_min = x_center - new_x_range / 2.0;
-            x_max = x_center + new_x_range / 2.0;
-            y_min = y_center - new_y_range / 2.0;
-            y_max = y_center + new_y_range / 2.0;
-        }
+    /// Draw axes `width` by `height` pixels with their top left corner at the origin
+    pub fn draw(&self, renderer: &mut dyn Renderer, width: f64, height: f64) {
+        self.draw_in_context(renderer, width, height, &SubplotContext::default());
+    }
 
-        let mut svg = String::new();
+    /// Draw the axes as a subplot of a figure
+    pub(crate) fn draw_in_context(
+        &self,
+        renderer: &mut dyn Renderer,
+        width: f64,
+        height: f64,
+        context: &SubplotContext,
+    ) {
+        let margins = context.margins.unwrap_or_default();
+        let (left, top) = (margins.left, margins.top);
+        let area = self.plot_area(width, height, &margins, context);
+        let (plot_width, plot_height) = (area.width, area.height);
+        let twin_areas = self.twin_areas(&area);
+        let side_axes = self.side_axes(&area, &twin_areas, context);
+        let bands = tick_bands(&side_axes);
 
         // Background
-        svg.push_str(&format!(
-            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" />",
-            margin,
-            margin,
-            plot_width,
-            plot_height,
-            self.background_color.to_svg_string()
-        ));
-
-        // Grid (skip for pie charts)
-        if self.grid {
-            svg.push_str(&self.generate_grid_svg(
-                x_min,
-                x_max,
-                y_min,
-                y_max,
-                margin,
+        if !context.transparent {
+            renderer.rect(
+                left,
+                top,
                 plot_width,
                 plot_height,
-            ));
+                &Style::filled(self.background_color),
+            );
         }
 
-        // Plot data
-        for plot in &self.plots {
-            svg.push_str(&format!(
-                "<g transform=\"translate({},{})\">\n",
-                margin, margin
-            ));
-            svg.push_str(&plot.to_svg(x_min, x_max, y_min, y_max, plot_width, plot_height));
-            svg.push_str("</g>\n");
+        // Grid (disabled for pie charts), behind the data unless asked otherwise
+        if self.grid && !self.grid_above {
+            self.draw_grid(renderer, &area, left, top);
         }
 
-        // Axes (skip for pie charts)
-        if self.show_x_axis || self.show_y_axis {
-            svg.push_str(&self.generate_axes_svg(
-                x_min,
-                x_max,
-                y_min,
-                y_max,
-                margin,
-                width,
-                height,
-                plot_width,
-                plot_height,
-            ));
+        // Plot data, clipped to the plotting area except for pies whose labels may stick
+        // out; the plots of twins are drawn over those of these axes
+        let plots =
+            self.plots
+                .iter()
+                .map(|plot| (plot, &area))
+                .chain(twin_areas.iter().flat_map(|(twin, twin_area)| {
+                    twin.plots.iter().map(move |plot| (plot, twin_area))
+                }));
+        renderer.begin_group(&Group::translate(left, top));
+        for (plot, plot_area) in plots {
+            let clip = !matches!(plot.plot_type, PlotType::Pie(_));
+            if clip {
+                renderer.begin_clip(0.0, 0.0, plot_width, plot_height);
+            }
+            plot.draw(renderer, plot_area);
+            if clip {
+                renderer.end_clip();
+            }
+        }
+        renderer.end_group();
+
+        if self.grid && self.grid_above {
+            self.draw_grid(renderer, &area, left, top);
+        }
+
+        // Axes (hidden for pie charts)
+        for axis in &side_axes {
+            axis.draw(renderer, left, top, &area);
         }
 
         // Labels and title
-        svg.push_str(&self.generate_labels_svg(width, height, margin));
+        self.draw_labels(renderer, left, top, &area, &bands);
+
+        // Colorbar beside (vertical) or below (horizontal) the plotting area
+        if let Some(ref colorbar) = self.colorbar {
+            let (x, y, length) = match colorbar.orientation {
+                // Past the tick labels and axis label of twin and secondary y axes
+                Orientation::Vertical => (
+                    left + plot_width + bands.right + self.label_space().right + colorbar.pad,
+                    top,
+                    plot_height,
+                ),
+                // Below the x tick labels and axis label
+                Orientation::Horizontal => (
+                    left,
+                    top + plot_height + bands.bottom + self.label_space().bottom + colorbar.pad,
+                    plot_width,
+                ),
+            };
+            let text = TextStyle::new(self.font_size, self.text_color);
+            colorbar.draw(renderer, &self.plots, x, y, length, &text);
+        }
 
         // Custom SVG elements
         for element in &self.custom_svg_elements {
-            svg.push_str(element);
-            svg.push_str("\n");
+            renderer.svg_fragment(element);
         }
 
         // Legend
         if self.legend {
-            svg.push_str(&self.generate_legend_svg(width, height));
+            self.draw_legend(renderer, left, top, plot_width);
         }
 
-        // Outer border (matplotlib style)
-        let border_color = Color::AXIS_COLOR.to_svg_string();
-        svg.push_str(&format!(
-                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"0.8\" />\n",
-                margin, margin, plot_width, plot_height, border_color
-            ));
-
-        svg
+        // Outer border (matplotlib style), hidden together with both axes
+        if self.show_x_axis || self.show_y_axis {
+            renderer.rect(
+                left,
+                top,
+                plot_width,
+                plot_height,
+                &Style::stroked(Color::AXIS_COLOR, 0.8),
+            );
+        }
     }
 
-    fn generate_grid_svg(
+    /// Smallest margins of axes `width` by `height` pixels that fit all of their text
+    ///
+    /// Tick labels, axis labels and the title are measured so that nothing is
+    /// clipped at the edges of the axes.
+    pub(crate) fn tight_margins(
         &self,
-        x_min: f64,
-        x_max: f64,
-        y_min: f64,
-        y_max: f64,
-        margin: f64,
-        plot_width: f64,
-        plot_height: f64,
-    ) -> String {
-        let mut svg = String::new();
-        let grid_color = self.grid_color.to_svg_string();
-
-        // Vertical grid lines
-        let x_ticks = generate_ticks(x_min, x_max, 12);
-        for &tick in &x_ticks {
-            let x = map_range(tick, x_min, x_max, 0.0, plot_width) + margin;
-            svg.push_str(&format!(
-                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"0.3\" />\n",
-                x, margin, x, margin + plot_height, grid_color
-            ));
+        width: f64,
+        height: f64,
+        context: &SubplotContext,
+    ) -> Margins {
+        let area = self.plot_area(width, height, &Margins::default(), context);
+        let twin_areas = self.twin_areas(&area);
+        let side_axes = self.side_axes(&area, &twin_areas, context);
+        let bands = tick_bands(&side_axes);
+        let labels = self.label_space();
+
+        let mut margins = Margins {
+            left: bands.left + labels.left + EDGE_PAD,
+            right: bands.right + labels.right + EDGE_PAD,
+            top: bands.top + labels.top + EDGE_PAD,
+            bottom: bands.bottom + labels.bottom + EDGE_PAD,
+        };
+
+        // Tick labels may also stick out past the ends of their axis
+        for axis in &side_axes {
+            let (before, after) = axis.overhang();
+            match axis.side {
+                Side::Bottom | Side::Top => {
+                    margins.left = margins.left.max(before + EDGE_PAD);
+                    margins.right = margins.right.max(after + EDGE_PAD);
+                }
+                Side::Left | Side::Right => {
+                    margins.top = margins.top.max(before + EDGE_PAD);
+                    margins.bottom = margins.bottom.max(after + EDGE_PAD);
+                }
+            }
         }
+        margins
+    }
 
-        // Horizontal grid lines
-        let y_ticks = self.generate_adaptive_ticks(y_min, y_max, 9);
-        for &tick in &y_ticks {
-            let y = map_range(tick, y_min, y_max, plot_height, 0.0) + margin;
-            svg.push_str(&format!(
-                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"0.3\" />\n",
-                margin, y, margin + plot_width, y, grid_color
-            ));
+    /// The plotting area inside `margins` of axes `width` by `height` pixels
+    fn plot_area(
+        &self,
+        width: f64,
+        height: f64,
+        margins: &Margins,
+        context: &SubplotContext,
+    ) -> PlotArea {
+        // A colorbar takes its space from the plotting area
+        let colorbar_space = self.colorbar.as_ref().map_or(0.0, |colorbar| {
+            colorbar.reserved_space(&self.plots, self.font_size)
+        });
+        let (right_space, bottom_space) = match self.colorbar {
+            Some(ref colorbar) if colorbar.orientation == Orientation::Horizontal => {
+                (0.0, colorbar_space)
+            }
+            Some(_) => (colorbar_space, 0.0),
+            None => (0.0, 0.0),
+        };
+        let plot_width = (width - margins.left - margins.right - right_space).max(1.0);
+        let plot_height = (height - margins.top - margins.bottom - bottom_space).max(1.0);
+
+        let (x_range, y_range) = self.calculate_data_ranges();
+        let (mut x_min, mut x_max) = context.x_limits.unwrap_or(x_range);
+        let (mut y_min, mut y_max) = context.y_limits.unwrap_or(y_range);
+
+        // Apply equal aspect ratio if enabled, in the space of the axis scales
+        if self.equal_aspect {
+            let (xs, ys) = (&self.x_scale, &self.y_scale);
+            let (x0, x1) = (xs.forward(x_min), xs.forward(x_max));
+            let (y0, y1) = (ys.forward(y_min), ys.forward(y_max));
+            let x_range = x1 - x0;
+            let y_range = y1 - y0;
+            let x_scale = plot_width / x_range.abs();
+            let y_scale = plot_height / y_range.abs();
+
+            // Use the smaller scale to ensure both axes fit
+            let scale = x_scale.min(y_scale);
+
+            // Adjust ranges to maintain equal scaling, keeping inverted axes inverted
+            let new_x_range = (plot_width / scale).copysign(x_range);
+            let new_y_range = (plot_height / scale).copysign(y_range);
+
+            let x_center = (x0 + x1) / 2.0;
+            let y_center = (y0 + y1) / 2.0;
+
+            x_min = xs.inverse(x_center - new_x_range / 2.0);
+            x_max = xs.inverse(x_center + new_x_range / 2.0);
+            y_min = ys.inverse(y_center - new_y_range / 2.0);
+            y_max = ys.inverse(y_center + new_y_range / 2.0);
         }
 
-        svg
+        PlotArea {
+            x_scale: self.x_scale.clone(),
+            y_scale: self.y_scale.clone(),
+            x_limits: (x_min, x_max),
+            y_limits: (y_min, y_max),
+            width: plot_width,
+            height: plot_height,
+        }
     }
 
-    fn generate_axes_svg(
-        &self,
-        x_min: f64,
-        x_max: f64,
-        y_min: f64,
-        y_max: f64,
-        margin: f64,
-        _width: f64,
-        _height: f64,
-        plot_width: f64,
-        plot_height: f64,
-    ) -> String {
-        let mut svg = String::new();
-        let text_color = self.text_color.to_svg_string();
-        let axis_color = Color::AXIS_COLOR.to_svg_string();
+    /// Areas of the twins, sharing one axis with `area` and using their own range for the other
+    fn twin_areas(&self, area: &PlotArea) -> Vec<(&Axes, PlotArea)> {
+        self.twins
+            .iter()
+            .map(|(shared, twin)| {
+                let (twin_x, twin_y) = twin.calculate_data_ranges();
+                let twin_area = match shared {
+                    SharedAxis::X => PlotArea {
+                        y_scale: twin.y_scale.clone(),
+                        y_limits: twin_y,
+                        ..area.clone()
+                    },
+                    SharedAxis::Y => PlotArea {
+                        x_scale: twin.x_scale.clone(),
+                        x_limits: twin_x,
+                        ..area.clone()
+                    },
+                };
+                (twin, twin_area)
+            })
+            .collect()
+    }
 
-        if self.show_x_axis {
-            // X-axis
-            svg.push_str(&format!(
-                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"0.8\" />\n",
-                margin, margin + plot_height, margin + plot_width, margin + plot_height, axis_color
+    fn draw_grid(&self, renderer: &mut dyn Renderer, area: &PlotArea, left: f64, top: f64) {
+        let (x_min, x_max) = area.x_limits;
+        let (y_min, y_max) = area.y_limits;
+        let x_ticks = self.x_ticks(x_min, x_max);
+        let y_ticks = self.y_ticks(y_min, y_max);
+
+        // Minor lines go first so that major lines are drawn over them
+        let mut levels = Vec::new();
+        if let Some(ref style) = self.minor_grid {
+            levels.push((
+                style,
+                self.x_minor_ticks(x_min, x_max, &x_ticks),
+                self.y_minor_ticks(y_min, y_max, &y_ticks),
             ));
-
-            // X-axis ticks and labels
-            let x_ticks = generate_ticks(x_min, x_max, 12);
-            for &tick in &x_ticks {
-                let x = map_range(tick, x_min, x_max, 0.0, plot_width) + margin;
-                svg.push_str(&format!(
-                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"0.8\" />\n",
-                    x, margin + plot_height, x, margin + plot_height + 5.0, axis_color
-                ));
-                svg.push_str(&format!(
-                    "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"{}\" fill=\"{}\">{}</text>\n",
-                    x, margin + plot_height + 20.0, self.font_size, text_color, format_number(tick)
-                ));
-            }
         }
+        levels.push((&self.major_grid, x_ticks, y_ticks));
 
-        if self.show_y_axis {
-            // Y-axis
-            svg.push_str(&format!(
-                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"0.8\" />\n",
-                margin, margin, margin, margin + plot_height, axis_color
-            ));
+        for (style, x_ticks, y_ticks) in levels {
+            let stroke = style.line_style(self.grid_color);
 
-            // Y-axis ticks and labels
-            let y_ticks = self.generate_adaptive_ticks(y_min, y_max, 9);
-            for &tick in &y_ticks {
-                let y = map_range(tick, y_min, y_max, plot_height, 0.0) + margin;
-                svg.push_str(&format!(
-                    "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"0.8\" />\n",
-                    margin - 5.0, y, margin, y, axis_color
-                ));
-                svg.push_str(&format!(
-                    "<text x=\"{}\" y=\"{}\" text-anchor=\"end\" font-size=\"{}\" fill=\"{}\" dy=\"0.35em\">{}</text>\n",
-                    margin - 10.0, y, self.font_size, text_color, format_number(tick)
-                ));
+            // Vertical grid lines
+            for tick in x_ticks {
+                let x = area.x(tick) + left;
+                renderer.line((x, top), (x, top + area.height), &stroke);
+            }
+
+            // Horizontal grid lines
+            for tick in y_ticks {
+                let y = area.y(tick) + top;
+                renderer.line((left, y), (left + area.width, y), &stroke);
             }
         }
+    }
 
-        svg
+    /// Axis lines with their ticks drawn around `area`, including those of the twins
+    fn side_axes<'a>(
+        &'a self,
+        area: &PlotArea,
+        twin_areas: &[(&'a Axes, PlotArea)],
+        context: &SubplotContext,
+    ) -> Vec<SideAxis<'a>> {
+        let mut axes = self.own_side_axes(area, context);
+        for (twin, twin_area) in twin_areas {
+            axes.extend(twin.own_side_axes(twin_area, &SubplotContext::default()));
+        }
+        axes
     }
 
-    fn generate_labels_svg(&self, width: f64, height: f64, _margin: f64) -> String {
-        let mut svg = String::new();
-        let text_color = self.text_color.to_svg_string();
+    /// Axis lines with their ticks drawn around `area`, leaving out the twins
+    fn own_side_axes(&self, area: &PlotArea, context: &SubplotContext) -> Vec<SideAxis<'_>> {
+        let mut axes = Vec::new();
+        let (x_min, x_max) = area.x_limits;
+        let (y_min, y_max) = area.y_limits;
 
-        // Title
-        if let Some(ref title) = self.title {
-            svg.push_str(&format!(
-                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"{}\" font-weight=\"bold\" fill=\"{}\">{}</text>\n",
-                width / 2.0, 30.0, self.font_size + 4.0, text_color, title
-            ));
+        if self.show_x_axis {
+            // X-axis ticks and labels (limits may be inverted)
+            let values = self.x_ticks(x_min, x_max);
+            let labels = if context.hide_x_ticklabels {
+                vec![String::new(); values.len()]
+            } else {
+                tick_labels(&self.x_ticker, &self.x_categories, &self.x_scale, &values)
+            };
+            let minor: Vec<f64> = if shows_minor(&self.x_ticker, &self.x_scale) {
+                self.x_minor_ticks(x_min, x_max, &values)
+                    .into_iter()
+                    .map(|t| area.x(t))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let offset_text = offset_text(&self.x_ticker, &self.x_scale, &values)
+                .filter(|_| !context.hide_x_ticklabels);
+            let side = if self.x_axis_top {
+                Side::Top
+            } else {
+                Side::Bottom
+            };
+            let ticks = values.iter().map(|&t| area.x(t)).zip(labels).collect();
+            axes.push(self.side_axis(side, &self.x_ticker, ticks, minor, offset_text, area.width));
         }
 
-        // X-axis label
-        if let Some(ref xlabel) = self.x_label {
-            svg.push_str(&format!(
-                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"{}\" fill=\"{}\">{}</text>\n",
-                width / 2.0, height - 10.0, self.font_size, text_color, xlabel
+        if self.show_y_axis {
+            // Y-axis ticks and labels (limits may be inverted)
+            let values = self.y_ticks(y_min, y_max);
+            let labels = if context.hide_y_ticklabels {
+                vec![String::new(); values.len()]
+            } else {
+                tick_labels(&self.y_ticker, &self.y_categories, &self.y_scale, &values)
+            };
+            let minor: Vec<f64> = if shows_minor(&self.y_ticker, &self.y_scale) {
+                self.y_minor_ticks(y_min, y_max, &values)
+                    .into_iter()
+                    .map(|t| area.y(t))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            let offset_text = offset_text(&self.y_ticker, &self.y_scale, &values)
+                .filter(|_| !context.hide_y_ticklabels);
+            let side = if self.y_axis_right {
+                Side::Right
+            } else {
+                Side::Left
+            };
+            let ticks = values.iter().map(|&t| area.y(t)).zip(labels).collect();
+            axes.push(self.side_axis(side, &self.y_ticker, ticks, minor, offset_text, area.height));
+        }
+
+        // Converted axes on the opposite sides
+        if let Some(ref secondary) = self.secondary_x {
+            let ticks = secondary
+                .ticks(area.x_limits)
+                .into_iter()
+                .map(|(t, label)| (area.x(t), label))
+                .collect();
+            axes.push(self.side_axis(
+                Side::Top,
+                &secondary.ticker,
+                ticks,
+                Vec::new(),
+                None,
+                area.width,
             ));
         }
+        if let Some(ref secondary) = self.secondary_y {
+            let ticks = secondary
+                .ticks(area.y_limits)
+                .into_iter()
+                .map(|(t, label)| (area.y(t), label))
+                .collect();
+            axes.push(self.side_axis(
+                Side::Right,
+                &secondary.ticker,
+                ticks,
+                Vec::new(),
+                None,
+                area.height,
+            ));
+        }
+
+        axes
+    }
 
-        // Y-axis label
+    /// An axis along `side` styled by `ticker` and the text settings of these axes
+    fn side_axis<'a>(
+        &self,
+        side: Side,
+        ticker: &'a Ticker,
+        ticks: Vec<(f64, String)>,
+        minor: Vec<f64>,
+        offset_text: Option<String>,
+        length: f64,
+    ) -> SideAxis<'a> {
+        let style = &ticker.label_style;
+        SideAxis {
+            side,
+            ticker,
+            ticks,
+            minor,
+            offset_text,
+            length,
+            font_size: style.font_size.unwrap_or(self.font_size),
+            text_style: style.text_style(self.font_size, self.text_color),
+        }
+    }
+
+    /// Axis labels by the side they are drawn along, including those of the twins
+    fn axis_labels(&self) -> Vec<(Side, &str)> {
+        let mut labels = Vec::new();
+        if let Some(ref xlabel) = self.x_label {
+            let side = if self.x_axis_top {
+                Side::Top
+            } else {
+                Side::Bottom
+            };
+            labels.push((side, xlabel.as_str()));
+        }
         if let Some(ref ylabel) = self.y_label {
-            svg.push_str(&format!(
-                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"{}\" fill=\"{}\" transform=\"rotate(-90, {}, {})\">{}</text>\n",
-                20.0, height / 2.0, self.font_size, text_color, 20.0, height / 2.0, ylabel
-            ));
+            let side = if self.y_axis_right {
+                Side::Right
+            } else {
+                Side::Left
+            };
+            labels.push((side, ylabel.as_str()));
+        }
+        if let Some(label) = self.secondary_x.as_ref().and_then(|s| s.label.as_ref()) {
+            labels.push((Side::Top, label.as_str()));
+        }
+        if let Some(label) = self.secondary_y.as_ref().and_then(|s| s.label.as_ref()) {
+            labels.push((Side::Right, label.as_str()));
         }
 
-        svg
+        // Twins only label the axis they don't share
+        for (shared, twin) in &self.twins {
+            labels.extend(
+                twin.axis_labels()
+                    .into_iter()
+                    .filter(|(side, _)| match shared {
+                        SharedAxis::X => matches!(side, Side::Left | Side::Right),
+                        SharedAxis::Y => matches!(side, Side::Top | Side::Bottom),
+                    }),
+            );
+        }
+        labels
     }
 
-    fn generate_legend_svg(&self, width: f64, _height: f64) -> String {
-        let mut svg = String::new();
-        let margin = 60.0;
-        let plot_width = width - 2.0 * margin;
+    /// Space the axis labels and title take beyond the tick labels on each side
+    fn label_space(&self) -> Margins {
+        let mut space = Margins::uniform(0.0);
+        for (side, _) in self.axis_labels() {
+            let size = LABEL_GAP + self.font_size;
+            match side {
+                Side::Left => space.left = size,
+                Side::Right => space.right = size,
+                Side::Top => space.top = size,
+                Side::Bottom => space.bottom = size,
+            }
+        }
+        if self.title.is_some() {
+            space.top += LABEL_GAP + self.font_size + 4.0;
+        }
+        space
+    }
+
+    /// Draw an axis label centered along one side of the plotting area
+    ///
+    /// The plotting area has its top left corner at `(left, top)`. The label sits
+    /// just beyond the tick labels, which take up `bands` on each side.
+    fn draw_axis_label(
+        &self,
+        renderer: &mut dyn Renderer,
+        side: Side,
+        label: &str,
+        (left, top): (f64, f64),
+        area: &PlotArea,
+        bands: &Margins,
+    ) {
+        let (center_x, center_y) = (left + area.width / 2.0, top + area.height / 2.0);
+        let (ascent, descent) = (ascent(self.font_size), descent(self.font_size));
+        // Vertical labels are rotated so that the tops of their letters face left
+        let (x, y, rotation) = match side {
+            Side::Bottom => (
+                center_x,
+                top + area.height + bands.bottom + LABEL_GAP + ascent,
+                0.0,
+            ),
+            Side::Top => (center_x, top - bands.top - LABEL_GAP - descent, 0.0),
+            Side::Left => (left - bands.left - LABEL_GAP - descent, center_y, 90.0),
+            Side::Right => (
+                left + area.width + bands.right + LABEL_GAP + ascent,
+                center_y,
+                90.0,
+            ),
+        };
+        let style = TextStyle::new(self.font_size, self.text_color)
+            .anchor(Anchor::Middle)
+            .rotation(rotation);
+        renderer.text(x, y, label, &style);
+    }
+
+    fn draw_labels(
+        &self,
+        renderer: &mut dyn Renderer,
+        left: f64,
+        top: f64,
+        area: &PlotArea,
+        bands: &Margins,
+    ) {
+        // Title, above everything along the top
+        if let Some(ref title) = self.title {
+            let font_size = self.font_size + 4.0;
+            let above = self.label_space().top - (LABEL_GAP + font_size);
+            let style = TextStyle::new(font_size, self.text_color)
+                .anchor(Anchor::Middle)
+                .bold(true);
+            renderer.text(
+                left + area.width / 2.0,
+                top - bands.top - above - LABEL_GAP - descent(font_size),
+                title,
+                &style,
+            );
+        }
+
+        // Axis labels
+        for (side, label) in self.axis_labels() {
+            self.draw_axis_label(renderer, side, label, (left, top), area, bands);
+        }
+    }
 
+    fn draw_legend(&self, renderer: &mut dyn Renderer, left: f64, top: f64, plot_width: f64) {
         // Calculate legend dimensions
-        let legend_entries: Vec<_> = self.plots.iter().filter(|p| p.label.is_some()).collect();
+        // Twins add their entries after those of these axes
+        let legend_entries: Vec<&Plot> = self
+            .plots
+            .iter()
+            .chain(self.twins.iter().flat_map(|(_, twin)| &twin.plots))
+            .filter(|p| p.label.is_some())
+            .collect();
         if legend_entries.is_empty() {
-            return svg;
+            return;
         }
 
         // Simple matplotlib-style legend parameters
@@ -472,73 +1672,169 @@ impl Axes {
         let mut max_text_width = 0.0f64;
         for plot in &legend_entries {
             if let Some(ref label) = plot.label {
-                // Estimate text width: approximately 0.6 * actual_font_size per character
                 let actual_font_size = self.font_size * 0.9;
-                let estimated_width = label.len() as f64 * actual_font_size * 0.6;
-                max_text_width = max_text_width.max(estimated_width);
+                max_text_width = max_text_width.max(text_width(label, actual_font_size));
             }
         }
 
-        // Calculate total legend width: padding + handle + gap + text (no right padding)
-        let legend_width = legend_padding + handle_length + handle_text_gap + max_text_width;
+        // Calculate total legend width: padding + handle + gap + text + padding
+        let legend_width = 2.0 * legend_padding + handle_length + handle_text_gap + max_text_width;
 
-        let legend_x = margin + plot_width - legend_width - 10.0; // Position legend within plot area (standard margin)
-        let legend_y = margin + 20.0; // Start legend below the top margin
+        let legend_x = left + plot_width - legend_width - 10.0; // Position legend within plot area (standard margin)
+        let legend_y = top + 20.0; // Start legend below the top margin
 
         // Simple legend background with subtle border and rounded corners
-        svg.push_str(&format!(
-            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"#cccccc\" stroke-width=\"{}\" rx=\"3\" />\n",
-            legend_x - legend_padding,
+        let frame = PathData::rounded_rect(
+            legend_x,
             legend_y - legend_padding,
             legend_width,
             legend_height,
-            legend_border_width
-        ));
+            3.0,
+        );
+        let frame_style =
+            Style::filled(Color::WHITE).stroke(Color::rgb(204, 204, 204), legend_border_width);
+        renderer.path(&frame, &frame_style);
 
         let mut current_y = legend_y + line_height * 0.7; // Adjust for text baseline
 
-        for plot in &self.plots {
+        for plot in legend_entries {
             if let Some(ref label) = plot.label {
                 // Legend handle (line for line plots, rect for others)
                 match plot.plot_type {
-                    crate::plot::PlotType::Line => {
+                    PlotType::Line => {
                         // Draw a line handle like matplotlib
-                        svg.push_str(&format!(
-                            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"2\" />\n",
-                            legend_x + legend_padding,
-                            current_y - 3.0,
-                            legend_x + legend_padding + handle_length,
-                            current_y - 3.0,
-                            plot.plot_color().to_svg_string()
-                        ));
+                        renderer.line(
+                            (legend_x + legend_padding, current_y - 3.0),
+                            (legend_x + legend_padding + handle_length, current_y - 3.0),
+                            &Style::stroked(plot.plot_color(), 2.0),
+                        );
                     }
-                    crate::plot::PlotType::Scatter => {
+                    PlotType::Scatter(_) => {
                         // Draw a circle marker for scatter plots
-                        svg.push_str(&format!(
-                            "<circle cx=\"{}\" cy=\"{}\" r=\"4\" fill=\"{}\" />\n",
+                        renderer.circle(
                             legend_x + legend_padding + handle_length / 2.0,
                             current_y - 3.0,
-                            plot.plot_color().to_svg_string()
-                        ));
+                            4.0,
+                            &Style::filled(plot.plot_color()),
+                        );
+                    }
+                    PlotType::Bar(_)
+                    | PlotType::Fill
+                    | PlotType::Pie(_)
+                    | PlotType::Box(_)
+                    | PlotType::Violin(_)
+                    | PlotType::Contour(_)
+                    | PlotType::Image(_) => {
+                        // Draw a filled box for bar plots
+                        renderer.rect(
+                            legend_x + legend_padding + handle_length / 2.0 - 7.0,
+                            current_y - 10.0,
+                            14.0,
+                            14.0,
+                            &Style::filled(plot.plot_color()).opacity(plot.alpha),
+                        );
                     }
                 }
 
-                // Legend text
-                svg.push_str(&format!(
-                    "<text x=\"{}\" y=\"{}\" font-size=\"{}\" font-family=\"Arial, sans-serif\" fill=\"{}\">{}</text>\n",
+                // Legend text, slightly smaller and at the size its width was measured at
+                let style = TextStyle::new(self.font_size * 0.9, self.text_color);
+                renderer.text(
                     legend_x + legend_padding + handle_length + handle_text_gap,
                     current_y,
-                    (self.font_size * 0.9) as i32,  // Slightly smaller font size
-                    self.text_color.to_svg_string(),
-                    label
-                ));
+                    label,
+                    &style,
+                );
 
                 current_y += line_height;
             }
         }
+    }
+}
 
-        svg
+/// Labels of an axis' ticks: from its formatter if set, else category names or the scale
+fn tick_labels(
+    ticker: &Ticker,
+    categories: &Categories,
+    scale: &Scale,
+    ticks: &[f64],
+) -> Vec<String> {
+    if let Some(ref formatter) = ticker.formatter {
+        return formatter.format_ticks(ticks);
     }
+    ticks
+        .iter()
+        .map(|&tick| match categories.name_at(tick) {
+            Some(name) => name.to_string(),
+            None => scale.format_tick(tick),
+        })
+        .collect()
+}
+
+/// Text at the end of an axis that applies to all of its tick labels
+fn offset_text(ticker: &Ticker, scale: &Scale, ticks: &[f64]) -> Option<String> {
+    match ticker.formatter {
+        Some(ref formatter) => formatter.offset_text(ticks),
+        None => scale.offset_text(ticks),
+    }
+}
+
+/// Whether an axis draws minor tick marks; by default only logarithmic ones do
+fn shows_minor(ticker: &Ticker, scale: &Scale) -> bool {
+    ticker.minor.unwrap_or(matches!(scale, Scale::Log { .. }))
+}
+
+/// Minor tick values of an axis, leaving out the major `ticks`
+fn minor_ticks(
+    ticker: &Ticker,
+    scale: &Scale,
+    categories: &Categories,
+    ticks: &[f64],
+    min: f64,
+    max: f64,
+) -> Vec<f64> {
+    let (low, high) = (min.min(max), min.max(max));
+    if !categories.is_empty() {
+        return Vec::new();
+    }
+    match ticker.minor_locator {
+        Some(ref locator) => locator
+            .ticks(low, high)
+            .into_iter()
+            .filter(|v| !ticks.contains(v))
+            .collect(),
+        None => auto_minor_ticks(scale, ticks, low, high),
+    }
+}
+
+/// Move values from their plot's own category positions to the shared ones
+fn register_categories(categories: &mut Categories, names: &[String], values: &mut [f64]) {
+    // Offsets from the category positions, like those of grouped bars, are kept
+    let local = category::positions(names);
+    for ((value, name), local) in values.iter_mut().zip(names).zip(local) {
+        *value += categories.position(name) - local;
+    }
+}
+
+/// Smallest range covering both `a` and `b`, in the direction of `a`
+pub(crate) fn union(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    let low = a.0.min(a.1).min(b.0.min(b.1));
+    let high = a.0.max(a.1).max(b.0.max(b.1));
+    if a.0 > a.1 { (high, low) } else { (low, high) }
+}
+
+/// Padded range of `data` in the space of `scale`, ignoring values it cannot show
+fn scaled_range(scale: &Scale, data: &[f64], sticky: &[f64]) -> (f64, f64) {
+    let forward = |values: &[f64]| -> Vec<f64> {
+        values
+            .iter()
+            .filter(|&&v| scale.is_valid(v))
+            .map(|&v| scale.forward(v))
+            .collect()
+    };
+    let (min, max) = calculate_range_sticky(&forward(data), &forward(sticky));
+    // Decreasing transforms (e.g. reciprocal) swap the ends back into data order
+    let (low, high) = (scale.inverse(min), scale.inverse(max));
+    (low.min(high), low.max(high))
 }
 
 impl Default for Axes {
@@ -546,3 +1842,313 @@ impl Default for Axes {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grouped_bar_offsets() {
+        let mut axes = Axes::new();
+        axes.grouped_bar_with_width(
+            vec![
+                Plot::bar(vec![0.0], vec![1.0]),
+                Plot::bar(vec![0.0], vec![2.0]),
+            ],
+            1.0,
+        );
+        assert_eq!(axes.plots[0].bar_rects(), vec![(-0.5, 0.0, 0.0, 1.0)]);
+        assert_eq!(axes.plots[1].bar_rects(), vec![(0.0, 0.5, 0.0, 2.0)]);
+        assert_ne!(axes.plots[0].color, axes.plots[1].color);
+    }
+
+    #[test]
+    fn test_stacked_bar_bottoms() {
+        let mut axes = Axes::new();
+        axes.stacked_bar(vec![
+            Plot::bar(vec![0.0, 1.0], vec![1.0, -1.0]),
+            Plot::bar(vec![0.0, 1.0], vec![2.0, 3.0]),
+            Plot::bar(vec![0.0, 1.0], vec![-1.0, -2.0]),
+        ]);
+        let bottoms: Vec<Vec<f64>> = axes
+            .plots
+            .iter()
+            .map(|p| p.bar_rects().iter().map(|r| r.2).collect())
+            .collect();
+        assert_eq!(
+            bottoms,
+            vec![vec![0.0, 0.0], vec![1.0, 0.0], vec![0.0, -1.0]]
+        );
+    }
+
+    #[test]
+    fn test_stacked_bar_categories() {
+        let mut axes = Axes::new();
+        axes.stacked_bar(vec![
+            Plot::bar_categories(["a", "b"], vec![1.0, 2.0]),
+            Plot::bar_categories(["b", "a"], vec![10.0, 20.0]),
+            Plot::bar(vec![0.1 + 0.2 - 0.3, 1.0], vec![5.0, 5.0]),
+        ]);
+
+        // The second series stacks on the bars of the same category, not index
+        let rects = axes.plots[1].bar_rects();
+        assert_eq!((rects[0].0 + rects[0].1) / 2.0, 1.0);
+        assert_eq!((rects[0].2, rects[1].2), (2.0, 1.0));
+        // Positions equal up to rounding share a stack
+        let rects = axes.plots[2].bar_rects();
+        assert_eq!((rects[0].2, rects[1].2), (21.0, 12.0));
+    }
+
+    #[test]
+    fn test_stacked_bar_skips_other_series() {
+        let mut axes = Axes::new();
+        axes.stacked_bar(vec![
+            Plot::line(vec![0.0, 1.0], vec![5.0, 5.0]),
+            Plot::bar(vec![0.0, 1.0], vec![1.0, 2.0]).bottom(vec![0.5]),
+            Plot::barh(vec![0.0], vec![4.0]),
+            Plot::bar(vec![1.0, 2.0], vec![3.0, 1.0]),
+            Plot::bar(vec![2.0, 1.0, 0.0], vec![1.0, 1.0, 1.0]),
+        ]);
+
+        // The line and the horizontal bars are left alone
+        let bar_bottoms = |index: usize| match axes.plots[index].plot_type {
+            PlotType::Bar(ref style) => style.bottoms.clone(),
+            _ => panic!("not a bar plot"),
+        };
+        assert_eq!(bar_bottoms(2), vec![0.0]);
+        // Bars stack on those at the same position, not the same index
+        assert_eq!(bar_bottoms(3), vec![2.5, 0.0]);
+        assert_eq!(bar_bottoms(4), vec![1.0, 5.5, 1.5]);
+    }
+
+    #[test]
+    fn test_pie_hides_axes() {
+        let labels = vec!["a".to_string(), "b".to_string()];
+        let mut axes = Axes::new();
+        axes.pie(vec![1.0, 2.0], Some(&labels));
+        assert!(!axes.grid && !axes.show_x_axis && !axes.show_y_axis);
+        assert!(axes.equal_aspect);
+        assert!(axes.to_svg(400.0, 400.0).contains(">b</text>"));
+    }
+
+    #[test]
+    fn test_shared_categories() {
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::bar_categories(["apple", "pear"], vec![1.0, 2.0]));
+        axes.grouped_bar_with_width(
+            vec![
+                Plot::bar_categories(["kiwi", "apple"], vec![3.0, 4.0]),
+                Plot::bar_categories(["kiwi", "apple"], vec![5.0, 6.0]),
+            ],
+            1.0,
+        );
+        assert_eq!(axes.x_categories.names(), ["apple", "pear", "kiwi"]);
+        assert_eq!(axes.plots[0].x_data, vec![0.0, 1.0]);
+        assert_eq!(axes.plots[1].x_data, vec![1.75, -0.25]);
+
+        let svg = axes.to_svg(600.0, 400.0);
+        assert!(svg.contains(">kiwi</text>"));
+        assert!(!svg.contains("rotate(-45"));
+    }
+
+    #[test]
+    fn test_custom_ticks() {
+        use crate::ticker::PercentFormatter;
+
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::line(vec![0.0, 10.0], vec![0.0, 1.0]))
+            .set_xticks(vec![0.0, 5.0, 20.0])
+            .set_xticklabels(["start", "middle"])
+            .set_yformatter(PercentFormatter::new(1.0))
+            .set_yticklabel_style(TickLabelStyle::new().font_size(9.0).rotation(30.0));
+        let svg = axes.to_svg(600.0, 400.0);
+        assert!(svg.contains(">start</text>") && svg.contains(">middle</text>"));
+        assert!(svg.contains(">100%</text>"));
+        assert!(svg.contains("font-size=\"9\"") && svg.contains("rotate(-30"));
+    }
+
+    #[test]
+    fn test_locator_replaces_fixed_ticks() {
+        use crate::ticker::MultipleLocator;
+
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::line(vec![0.0, 10.0], vec![0.0, 1.0]))
+            .set_xticks(vec![0.0, 5.0])
+            .set_xticklabels(["start", "middle"])
+            .set_xlocator(MultipleLocator::new(2.5));
+        let svg = axes.to_svg(600.0, 400.0);
+        // The new ticks get numeric labels, not the blanks of the old fixed labels
+        assert!(!svg.contains(">start</text>") && !svg.contains(">middle</text>"));
+        assert!(svg.contains(">2.5</text>") && svg.contains(">7.5</text>"));
+    }
+
+    #[test]
+    fn test_fixed_tick_labels() {
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::line(vec![0.0, 10.0], vec![0.0, 1.0]))
+            .set_xticks(vec![-5.0, 0.0, 5.0])
+            .set_xticklabels(["neg", "zero", "five"])
+            .set_yticklabels(["low", "high"])
+            .set_yticks(vec![1.0, 0.0]);
+        let svg = axes.to_svg(600.0, 400.0);
+        // -5 is outside the view, so its label must not shift onto the others
+        assert!(!svg.contains(">neg</text>"));
+        assert!(svg.contains(">zero</text>") && svg.contains(">five</text>"));
+        assert!(svg.contains(">low</text>") && svg.contains(">high</text>"));
+        let texts: Vec<&str> = svg.split("</text>").collect();
+        let low = texts.iter().position(|t| t.ends_with(">low")).unwrap();
+        let high = texts.iter().position(|t| t.ends_with(">high")).unwrap();
+        // The y labels are drawn from the bottom tick up: 0 then 1
+        assert!(high < low);
+    }
+
+    #[test]
+    fn test_twin_axes() {
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::line(vec![0.0, 10.0], vec![0.0, 1.0]).label("latency"));
+        axes.twinx()
+            .add_plot(Plot::line(vec![5.0, 20.0], vec![100.0, 500.0]).label("throughput"))
+            .set_ylabel("req/s");
+        axes.secondary_xaxis(|c| c * 1.8 + 32.0, |f| (f - 32.0) / 1.8)
+            .set_label("°F");
+        axes.legend(true);
+
+        // The shared x axis fits both, the twin keeps its own y range and colors
+        let ((x_min, x_max), (_, y_max)) = axes.calculate_data_ranges();
+        assert!(x_min <= 0.0 && x_max >= 20.0 && y_max < 2.0);
+        assert_eq!(axes.twins[0].1.plots[0].color, axes.plots[0].color);
+
+        let svg = axes.to_svg(600.0, 400.0);
+        assert!(svg.contains(">500</text>") && svg.contains(">req/s</text>"));
+        assert!(svg.contains(">°F</text>") && svg.contains(">throughput</text>"));
+    }
+
+    #[test]
+    fn test_legend_text_fits_its_frame() {
+        use crate::render::{Command, RecordingRenderer, Segment};
+
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::line(vec![0.0, 1.0], vec![0.0, 1.0]).label("a rather long label"))
+            .legend(true);
+        let mut recording = RecordingRenderer::new();
+        axes.draw(&mut recording, 400.0, 300.0);
+
+        let Some((x, style)) = recording.commands.iter().find_map(|c| match c {
+            Command::Text { x, text, style, .. } if text == "a rather long label" => {
+                Some((*x, style))
+            }
+            _ => None,
+        }) else {
+            panic!("no legend label drawn");
+        };
+        // The label is drawn at the size it was measured at
+        assert_eq!(style.font_size, axes.font_size * 0.9);
+
+        let frame_right = recording
+            .commands
+            .iter()
+            .find_map(|c| match c {
+                Command::Path { path, .. } if path.segments.len() > 4 => path
+                    .segments
+                    .iter()
+                    .filter_map(|s| match s {
+                        Segment::LineTo(x, _) | Segment::ArcTo { x, .. } => Some(*x),
+                        _ => None,
+                    })
+                    .reduce(f64::max),
+                _ => None,
+            })
+            .unwrap();
+        // and ends inside the frame, just short of its right edge
+        let text_right = x + text_width("a rather long label", style.font_size);
+        assert!(text_right < frame_right && frame_right - text_right < 5.0);
+    }
+
+    #[test]
+    fn test_colorbar_clears_twin_axis() {
+        use crate::render::{Command, RecordingRenderer};
+
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::imshow(vec![vec![0.0, 1.0], vec![2.0, 3.0]]))
+            .colorbar(Colorbar::new());
+        axes.twinx()
+            .add_plot(Plot::line(vec![0.0, 1.0], vec![100.0, 50000.0]))
+            .set_ylabel("count");
+        let mut recording = RecordingRenderer::new();
+        axes.draw(&mut recording, 600.0, 400.0);
+
+        // The twin's tick labels and axis label end before the color strip starts
+        let twin_text_end = recording
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                Command::Text { x, text, style, .. }
+                    if text == "count" || text.parse::<f64>().is_ok_and(|v| v >= 100.0) =>
+                {
+                    // The axis label runs along the axis, a line of text thick
+                    let extent = if style.rotation == 0.0 {
+                        text_width(text, style.font_size)
+                    } else {
+                        style.font_size
+                    };
+                    Some(x + extent)
+                }
+                _ => None,
+            })
+            .fold(0.0, f64::max);
+        let strip_x = recording
+            .commands
+            .iter()
+            .find_map(|c| match c {
+                Command::Rect { x, width, .. } if *width == Colorbar::new().thickness => Some(*x),
+                _ => None,
+            })
+            .unwrap();
+        assert!(strip_x > twin_text_end, "{} <= {}", strip_x, twin_text_end);
+    }
+
+    #[test]
+    fn test_draw_through_renderer() {
+        use crate::render::{Command, RecordingRenderer};
+
+        let mut axes = Axes::new();
+        axes.add_plot(Plot::scatter(vec![0.0, 1.0], vec![0.0, 1.0]).alpha(0.5))
+            .set_title("Title");
+        let mut recording = RecordingRenderer::new();
+        axes.draw(&mut recording, 400.0, 300.0);
+
+        assert!(recording.texts().contains(&"Title"));
+        // Both markers are faded on their own inside the clipped plotting area
+        let commands = &recording.commands;
+        let clip = commands
+            .iter()
+            .position(|c| matches!(c, Command::BeginClip { .. }))
+            .unwrap();
+        let circles = commands[clip..]
+            .iter()
+            .take_while(|c| !matches!(c, Command::EndClip))
+            .filter(|c| matches!(c, Command::Circle { .. }))
+            .count();
+        assert_eq!(circles, 2);
+        assert!(commands.contains(&Command::BeginGroup(Group::opacity(0.5))));
+    }
+
+    #[test]
+    fn test_tight_margins() {
+        let context = SubplotContext::default();
+        let mut small = Axes::new();
+        small.add_plot(Plot::line(vec![0.0, 1.0], vec![0.0, 1.0]));
+        let mut large = Axes::new();
+        large
+            .add_plot(Plot::line(vec![0.0, 1.0], vec![0.0, 1e7]))
+            .set_title("Title")
+            .set_ylabel("bytes");
+
+        let small = small.tight_margins(600.0, 400.0, &context);
+        let large = large.tight_margins(600.0, 400.0, &context);
+        // Wider tick labels plus an axis label push the plotting area right
+        assert!(large.left > small.left + 2.0 * LABEL_GAP);
+        assert!(large.top > small.top + LABEL_GAP);
+        assert_eq!(large.bottom, small.bottom);
+    }
+}
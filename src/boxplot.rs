@@ -0,0 +1,336 @@
+//! Box-and-whisker plots
+
+use crate::IntoVec;
+use crate::colors::Color;
+use crate::markers::Marker;
+use crate::plot::{Orientation, Plot, PlotType};
+use crate::render::{PathData, Renderer, Style};
+use crate::scale::PlotArea;
+use crate::stats::{mean, percentile, sorted_finite};
+
+/// Where the whiskers of a box plot end
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Whisker {
+    /// At the most extreme data point within `factor * IQR` of the box
+    Iqr(f64),
+    /// At the given low and high percentiles (0-100)
+    Percentiles(f64, f64),
+}
+
+/// Summary statistics drawn by a box plot
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxStats {
+    pub median: f64,
+    pub q1: f64,
+    pub q3: f64,
+    pub iqr: f64,
+    pub mean: f64,
+    pub whisker_low: f64,
+    pub whisker_high: f64,
+    /// Confidence interval around the median drawn as notches
+    pub notch_low: f64,
+    pub notch_high: f64,
+    /// Values beyond the whiskers
+    pub fliers: Vec<f64>,
+}
+
+impl BoxStats {
+    /// Compute box plot statistics of the finite values in `data`
+    ///
+    /// Returns `None` if `data` has no finite values.
+    pub fn compute(data: &[f64], whis: Whisker) -> Option<Self> {
+        let sorted = sorted_finite(data);
+        if sorted.is_empty() {
+            return None;
+        }
+
+        let median = percentile(&sorted, 50.0);
+        let q1 = percentile(&sorted, 25.0);
+        let q3 = percentile(&sorted, 75.0);
+        let iqr = q3 - q1;
+
+        let (whisker_low, whisker_high) = match whis {
+            Whisker::Iqr(factor) => {
+                let low_limit = q1 - factor * iqr;
+                let high_limit = q3 + factor * iqr;
+                // Whiskers stop at real data points, but never inside the box
+                let low = sorted
+                    .iter()
+                    .copied()
+                    .find(|&v| v >= low_limit)
+                    .map_or(q1, |v| v.min(q1));
+                let high = sorted
+                    .iter()
+                    .rev()
+                    .copied()
+                    .find(|&v| v <= high_limit)
+                    .map_or(q3, |v| v.max(q3));
+                (low, high)
+            }
+            Whisker::Percentiles(low, high) => {
+                (percentile(&sorted, low), percentile(&sorted, high))
+            }
+        };
+
+        let fliers = sorted
+            .iter()
+            .copied()
+            .filter(|&v| v < whisker_low || v > whisker_high)
+            .collect();
+
+        let notch = 1.57 * iqr / (sorted.len() as f64).sqrt();
+
+        Some(BoxStats {
+            median,
+            q1,
+            q3,
+            iqr,
+            mean: mean(&sorted),
+            whisker_low,
+            whisker_high,
+            notch_low: median - notch,
+            notch_high: median + notch,
+            fliers,
+        })
+    }
+
+    /// Lowest and highest value drawn, including fliers
+    pub fn value_range(&self) -> (f64, f64) {
+        let low = self.fliers.iter().copied().fold(self.whisker_low, f64::min);
+        let high = self
+            .fliers
+            .iter()
+            .copied()
+            .fold(self.whisker_high, f64::max);
+        (low, high)
+    }
+}
+
+/// Box plot specific settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoxStyle {
+    pub position: f64,
+    pub width: f64,
+    pub orientation: Orientation,
+    pub whis: Whisker,
+    pub notch: bool,
+    pub show_mean: bool,
+    pub show_fliers: bool,
+    pub flier_marker: Marker,
+    pub mean_marker: Marker,
+    pub median_color: Color,
+}
+
+impl BoxStyle {
+    fn new() -> Self {
+        BoxStyle {
+            position: 1.0,
+            width: 0.5,
+            orientation: Orientation::Vertical,
+            whis: Whisker::Iqr(1.5),
+            notch: false,
+            show_mean: false,
+            show_fliers: true,
+            flier_marker: Marker::Circle,
+            mean_marker: Marker::TriangleUp,
+            median_color: Color::BLACK,
+        }
+    }
+}
+
+impl Plot {
+    /// Create a new box plot summarizing `data`
+    pub fn boxplot<D: IntoVec<f64>>(data: D) -> Self {
+        Plot {
+            x_data: Vec::new(),
+            y_data: data.into_vec(),
+            z_data: None,
+            plot_type: PlotType::Box(BoxStyle::new()),
+            color: None,
+            marker: Marker::None,
+            marker_size: 5.0,
+            line_width: 1.0,
+            label: None,
+            alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    fn box_style_mut(&mut self) -> Option<&mut BoxStyle> {
+        match self.plot_type {
+            PlotType::Box(ref mut style) => Some(style),
+            _ => None,
+        }
+    }
+
+    /// Set the width of the box
+    pub fn box_width(mut self, width: f64) -> Self {
+        if let Some(style) = self.box_style_mut() {
+            style.width = width;
+        }
+        self
+    }
+
+    /// Set where the whiskers end
+    pub fn whis(mut self, whis: Whisker) -> Self {
+        if let Some(style) = self.box_style_mut() {
+            style.whis = whis;
+        }
+        self
+    }
+
+    /// Draw notches showing the confidence interval of the median
+    pub fn notch(mut self, enable: bool) -> Self {
+        if let Some(style) = self.box_style_mut() {
+            style.notch = enable;
+        }
+        self
+    }
+
+    /// Draw a marker at the mean
+    pub fn show_mean(mut self, enable: bool) -> Self {
+        if let Some(style) = self.box_style_mut() {
+            style.show_mean = enable;
+        }
+        self
+    }
+
+    /// Draw the values beyond the whiskers
+    pub fn show_fliers(mut self, enable: bool) -> Self {
+        if let Some(style) = self.box_style_mut() {
+            style.show_fliers = enable;
+        }
+        self
+    }
+
+    /// Set the marker used for fliers
+    pub fn flier_marker(mut self, marker: Marker) -> Self {
+        if let Some(style) = self.box_style_mut() {
+            style.flier_marker = marker;
+        }
+        self
+    }
+
+    /// Compute the statistics of a box plot, `None` for other plots
+    pub fn box_stats(&self) -> Option<BoxStats> {
+        match self.plot_type {
+            PlotType::Box(ref style) => BoxStats::compute(&self.y_data, style.whis),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn draw_box(&self, renderer: &mut dyn Renderer, style: &BoxStyle, area: &PlotArea) {
+        let Some(stats) = self.box_stats() else {
+            return;
+        };
+
+        // Map (position, value) pairs to SVG coordinates based on orientation
+        let point = |pos: f64, value: f64| {
+            let (x, y) = match style.orientation {
+                Orientation::Vertical => (pos, value),
+                Orientation::Horizontal => (value, pos),
+            };
+            area.point(x, y)
+        };
+
+        let p = style.position;
+        let half = style.width / 2.0;
+        let edge = Style::stroked(Color::BLACK, self.line_width);
+
+        // Whiskers and caps
+        for (from, to) in [
+            (stats.q1, stats.whisker_low),
+            (stats.q3, stats.whisker_high),
+        ] {
+            renderer.line(point(p, from), point(p, to), &edge);
+            renderer.line(point(p - half / 2.0, to), point(p + half / 2.0, to), &edge);
+        }
+
+        // Box, optionally notched around the median
+        let outline = if style.notch {
+            vec![
+                (p - half, stats.q1),
+                (p + half, stats.q1),
+                (p + half, stats.notch_low),
+                (p + half / 2.0, stats.median),
+                (p + half, stats.notch_high),
+                (p + half, stats.q3),
+                (p - half, stats.q3),
+                (p - half, stats.notch_high),
+                (p - half / 2.0, stats.median),
+                (p - half, stats.notch_low),
+            ]
+        } else {
+            vec![
+                (p - half, stats.q1),
+                (p + half, stats.q1),
+                (p + half, stats.q3),
+                (p - half, stats.q3),
+            ]
+        };
+        let points: Vec<(f64, f64)> = outline
+            .iter()
+            .map(|&(pos, value)| point(pos, value))
+            .collect();
+        renderer.path(
+            &PathData::polygon(&points),
+            &edge
+                .clone()
+                .fill(self.plot_color().multiply_alpha(self.alpha)),
+        );
+
+        // Median
+        let median_half = if style.notch { half / 2.0 } else { half };
+        renderer.line(
+            point(p - median_half, stats.median),
+            point(p + median_half, stats.median),
+            &Style::stroked(style.median_color, self.line_width * 2.0),
+        );
+
+        if style.show_mean {
+            let (x, y) = point(p, stats.mean);
+            style
+                .mean_marker
+                .draw(renderer, x, y, self.marker_size * 1.5, Color::GREEN);
+        }
+
+        if style.show_fliers {
+            for &flier in &stats.fliers {
+                let (x, y) = point(p, flier);
+                style
+                    .flier_marker
+                    .draw(renderer, x, y, self.marker_size, self.plot_color());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_stats() {
+        let mut data: Vec<f64> = (1..=9).map(|i| i as f64).collect();
+        data.push(30.0);
+        let stats = BoxStats::compute(&data, Whisker::Iqr(1.5)).unwrap();
+        assert_eq!(stats.median, 5.5);
+        assert_eq!(stats.q1, 3.25);
+        assert_eq!(stats.q3, 7.75);
+        assert_eq!(stats.whisker_low, 1.0);
+        assert_eq!(stats.whisker_high, 9.0);
+        assert_eq!(stats.fliers, vec![30.0]);
+        assert_eq!(stats.mean, 7.5);
+    }
+
+    #[test]
+    fn test_box_stats_percentile_whiskers() {
+        let data: Vec<f64> = (0..=100).map(|i| i as f64).collect();
+        let stats = BoxStats::compute(&data, Whisker::Percentiles(5.0, 95.0)).unwrap();
+        assert_eq!(stats.whisker_low, 5.0);
+        assert_eq!(stats.whisker_high, 95.0);
+        assert_eq!(stats.fliers.len(), 10);
+        assert!(BoxStats::compute(&[], Whisker::Iqr(1.5)).is_none());
+    }
+}
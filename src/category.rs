@@ -0,0 +1,96 @@
+//! String categories placed along an axis
+
+/// Category names of an axis, in the order they were first seen
+///
+/// Each category sits at the integer position of its index, so the first one
+/// is at 0, the next at 1 and so on.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Categories {
+    names: Vec<String>,
+}
+
+impl Categories {
+    /// Create an empty set of categories
+    pub fn new() -> Self {
+        Categories { names: Vec::new() }
+    }
+
+    /// Category names in position order
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Position of `name`, appending it as a new category if unseen
+    pub fn position(&mut self, name: &str) -> f64 {
+        let index = match self.names.iter().position(|n| n == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.names.len() - 1
+            }
+        };
+        index as f64
+    }
+
+    /// Name of the category at `position`, if it is one
+    pub fn name_at(&self, position: f64) -> Option<&str> {
+        let index = position.round();
+        if (position - index).abs() > 1e-9 || index < 0.0 {
+            return None;
+        }
+        self.names.get(index as usize).map(|s| s.as_str())
+    }
+
+    /// Positions of the categories between `min` and `max`
+    pub fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        (0..self.names.len())
+            .map(|i| i as f64)
+            .filter(|&p| p >= min && p <= max)
+            .collect()
+    }
+}
+
+/// Collect category names from strings
+pub(crate) fn names<C, S>(categories: C) -> Vec<String>
+where
+    C: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    categories
+        .into_iter()
+        .map(|s| s.as_ref().to_string())
+        .collect()
+}
+
+/// Positions of each name in first-seen order among `names`
+pub(crate) fn positions(names: &[String]) -> Vec<f64> {
+    let mut categories = Categories::new();
+    names.iter().map(|name| categories.position(name)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categories() {
+        let names = names(["b", "a", "b", "c"]);
+        assert_eq!(positions(&names), vec![0.0, 1.0, 0.0, 2.0]);
+
+        let mut categories = Categories::new();
+        assert_eq!(categories.position("x"), 0.0);
+        assert_eq!(categories.position("y"), 1.0);
+        assert_eq!(categories.position("x"), 0.0);
+        assert_eq!(categories.name_at(1.0), Some("y"));
+        assert_eq!(categories.name_at(0.5), None);
+        assert_eq!(categories.ticks(-0.5, 0.5), vec![0.0]);
+    }
+}
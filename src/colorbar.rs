@@ -0,0 +1,293 @@
+//! Colorbars showing the color scale of a color-mapped plot
+
+use crate::colors::{Color, Colormap, NormKind, Normalize};
+use crate::plot::{Orientation, Plot, PlotType};
+use crate::render::{Anchor, Renderer, Style, TextStyle};
+use crate::text::text_width;
+use crate::utils::{format_number, generate_ticks};
+
+/// Length of the tick marks on the colorbar in pixels
+const TICK_LENGTH: f64 = 4.0;
+/// Gap between tick marks and their labels in pixels
+const TICK_GAP: f64 = 3.0;
+/// Number of flat slices a continuous color strip is drawn with
+const SLICES: usize = 100;
+
+/// A color scale drawn beside an axes
+///
+/// Vertical colorbars sit to the right of the plotting area, past the ticks
+/// of any twin or secondary y axis there, horizontal ones below it. The space
+/// they need is taken from the plotting area.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colorbar {
+    pub orientation: Orientation,
+    pub label: Option<String>,
+    /// Index of the plot whose colors are shown; the last color-mapped plot if unset
+    pub mappable: Option<usize>,
+    /// Width of the color strip across its length in pixels
+    pub thickness: f64,
+    /// Gap between the plotting area and the color strip in pixels
+    pub pad: f64,
+    /// Target number of ticks
+    pub tick_count: usize,
+}
+
+impl Colorbar {
+    /// Create a vertical colorbar
+    pub fn new() -> Self {
+        Colorbar {
+            orientation: Orientation::Vertical,
+            label: None,
+            mappable: None,
+            thickness: 15.0,
+            pad: 15.0,
+            tick_count: 6,
+        }
+    }
+
+    /// Place the colorbar to the right (vertical) or below (horizontal)
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        self.orientation = orientation;
+        self
+    }
+
+    /// Set the label written along the colorbar
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Show the colors of the plot at `index` in the axes
+    pub fn mappable(mut self, index: usize) -> Self {
+        self.mappable = Some(index);
+        self
+    }
+
+    /// Set the width of the color strip in pixels
+    pub fn thickness(mut self, thickness: f64) -> Self {
+        self.thickness = thickness;
+        self
+    }
+
+    /// Set the gap between the plotting area and the color strip in pixels
+    pub fn pad(mut self, pad: f64) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    /// Set the target number of ticks
+    pub fn tick_count(mut self, count: usize) -> Self {
+        self.tick_count = count;
+        self
+    }
+
+    /// The plot whose colormap and norm the colorbar shows
+    fn source<'a>(&self, plots: &'a [Plot]) -> Option<&'a Plot> {
+        match self.mappable {
+            Some(index) => plots.get(index).filter(|p| p.color_mapping().is_some()),
+            None => plots.iter().rev().find(|p| p.color_mapping().is_some()),
+        }
+    }
+
+    /// Tick values within the norm limits
+    ///
+    /// Logarithmic norms get ticks at whole decades when the range spans any.
+    pub fn tick_values(&self, norm: &Normalize) -> Vec<f64> {
+        let (vmin, vmax) = norm.limits();
+        let (low, high) = (vmin.min(vmax), vmin.max(vmax));
+        let inside = |v: &f64| *v >= low - (high - low) * 1e-9 && *v <= high + (high - low) * 1e-9;
+
+        if norm.kind == NormKind::Log && low > 0.0 {
+            let decades: Vec<f64> = generate_ticks(low.log10(), high.log10(), self.tick_count)
+                .into_iter()
+                .filter(|e| (e - e.round()).abs() < 1e-9)
+                .map(|e| 10f64.powf(e.round()))
+                .filter(inside)
+                .collect();
+            if decades.len() >= 2 {
+                return decades;
+            }
+        }
+
+        generate_ticks(low, high, self.tick_count)
+            .into_iter()
+            .filter(inside)
+            .collect()
+    }
+
+    /// Space in pixels the colorbar needs beside the plotting area
+    pub(crate) fn reserved_space(&self, plots: &[Plot], font_size: f64) -> f64 {
+        let Some((_, norm)) = self.source(plots).and_then(|p| p.color_mapping()) else {
+            return 0.0;
+        };
+
+        let tick_labels = match self.orientation {
+            Orientation::Vertical => self
+                .tick_values(&norm)
+                .iter()
+                .map(|&v| text_width(&format_number(v), font_size))
+                .fold(0.0, f64::max),
+            Orientation::Horizontal => font_size,
+        };
+        let label = if self.label.is_some() {
+            font_size + TICK_GAP * 2.0
+        } else {
+            0.0
+        };
+
+        self.pad + self.thickness + TICK_LENGTH + TICK_GAP + tick_labels + label
+    }
+
+    /// Draw a colorbar whose strip starts at `(x, y)` and runs `length` pixels
+    ///
+    /// Tick labels and the label are drawn in the size and color of `text`.
+    pub(crate) fn draw(
+        &self,
+        renderer: &mut dyn Renderer,
+        plots: &[Plot],
+        x: f64,
+        y: f64,
+        length: f64,
+        text: &TextStyle,
+    ) {
+        let Some(plot) = self.source(plots) else {
+            return;
+        };
+        let Some((colormap, norm)) = plot.color_mapping() else {
+            return;
+        };
+
+        // Rectangle covering the strip between colormap positions `t0` and `t1`
+        let strip = |t0: f64, t1: f64| match self.orientation {
+            Orientation::Vertical => (
+                x,
+                y + length * (1.0 - t1.max(t0)),
+                self.thickness,
+                length * (t1 - t0).abs(),
+            ),
+            Orientation::Horizontal => (
+                x + length * t0.min(t1),
+                y,
+                length * (t1 - t0).abs(),
+                self.thickness,
+            ),
+        };
+        for (t0, t1, color) in self.segments(plot, colormap, &norm) {
+            let (rx, ry, rw, rh) = strip(t0, t1);
+            renderer.rect(rx, ry, rw, rh, &Style::filled(color).stroke(color, 0.5));
+        }
+
+        let (bx, by, bw, bh) = strip(0.0, 1.0);
+        let axis = Style::stroked(Color::AXIS_COLOR, 0.8);
+        renderer.rect(bx, by, bw, bh, &axis);
+
+        for tick in self.tick_values(&norm) {
+            let t = norm.apply(tick);
+            if !t.is_finite() {
+                continue;
+            }
+            let label = format_number(tick);
+            match self.orientation {
+                Orientation::Vertical => {
+                    let ty = y + length * (1.0 - t);
+                    let x0 = x + self.thickness;
+                    renderer.line((x0, ty), (x0 + TICK_LENGTH, ty), &axis);
+                    renderer.text(
+                        x0 + TICK_LENGTH + TICK_GAP,
+                        ty,
+                        &label,
+                        &text.clone().dy(0.35),
+                    );
+                }
+                Orientation::Horizontal => {
+                    let tx = x + length * t;
+                    let y0 = y + self.thickness;
+                    renderer.line((tx, y0), (tx, y0 + TICK_LENGTH), &axis);
+                    renderer.text(
+                        tx,
+                        y0 + TICK_LENGTH + TICK_GAP,
+                        &label,
+                        &text.clone().anchor(Anchor::Middle).dy(0.8),
+                    );
+                }
+            }
+        }
+
+        if let Some(ref label) = self.label {
+            // The label sits past the tick labels, clear of the reserved space's far edge
+            let offset = self.reserved_space(plots, text.font_size) - self.pad - TICK_GAP;
+            let text = text.clone().anchor(Anchor::Middle);
+            match self.orientation {
+                Orientation::Vertical => {
+                    renderer.text(x + offset, y + length / 2.0, label, &text.rotation(90.0));
+                }
+                Orientation::Horizontal => {
+                    renderer.text(x + length / 2.0, y + offset, label, &text);
+                }
+            }
+        }
+    }
+
+    /// Flat-colored `(t0, t1, color)` pieces of the strip
+    ///
+    /// Filled contours show one band per level interval, everything else a
+    /// continuous ramp.
+    fn segments(
+        &self,
+        plot: &Plot,
+        colormap: &Colormap,
+        norm: &Normalize,
+    ) -> Vec<(f64, f64, Color)> {
+        if let PlotType::Contour(ref style) = plot.plot_type
+            && style.filled
+        {
+            return plot
+                .contour_levels()
+                .windows(2)
+                .map(|band| {
+                    let t0 = norm.apply(band[0]).clamp(0.0, 1.0);
+                    let t1 = norm.apply(band[1]).clamp(0.0, 1.0);
+                    let color = colormap.at(norm.apply((band[0] + band[1]) / 2.0));
+                    (t0, t1, color)
+                })
+                .filter(|(t0, t1, _)| t0.is_finite() && t1.is_finite())
+                .collect();
+        }
+
+        (0..SLICES)
+            .map(|i| {
+                let t0 = i as f64 / SLICES as f64;
+                let t1 = (i + 1) as f64 / SLICES as f64;
+                (t0, t1, colormap.at((t0 + t1) / 2.0))
+            })
+            .collect()
+    }
+}
+
+impl Default for Colorbar {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colorbar_ticks() {
+        let colorbar = Colorbar::new();
+        let ticks = colorbar.tick_values(&Normalize::linear().range(0.0, 10.0));
+        assert_eq!(ticks.first(), Some(&0.0));
+        assert_eq!(ticks.last(), Some(&10.0));
+
+        let ticks = colorbar.tick_values(&Normalize::log().range(1.0, 1000.0));
+        assert_eq!(ticks, vec![1.0, 10.0, 100.0, 1000.0]);
+
+        // Only plots with a colormap can back a colorbar
+        let plots = vec![Plot::line(vec![0.0], vec![0.0])];
+        assert_eq!(colorbar.reserved_space(&plots, 12.0), 0.0);
+        let plots = vec![Plot::imshow(vec![vec![0.0, 1.0]])];
+        assert!(colorbar.reserved_space(&plots, 12.0) > colorbar.thickness);
+    }
+}
@@ -1,6 +1,7 @@
 //! Color definitions and utilities
 
 use std::fmt;
+use std::str::FromStr;
 
 /// RGB color representation
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -22,6 +23,11 @@ impl Color {
         Color { r, g, b, a: a.clamp(0.0, 1.0) }
     }
 
+    /// This color with its alpha multiplied by `alpha`
+    pub fn multiply_alpha(self, alpha: f64) -> Self {
+        Color::rgba(self.r, self.g, self.b, self.a * alpha)
+    }
+
     /// Create color from hex string (e.g., "#FF0000" for red)
     pub fn from_hex(hex: &str) -> Result<Self, &'static str> {
         let hex = hex.trim_start_matches('#');
@@ -114,4 +120,661 @@ pub const DEFAULT_COLOR_CYCLE: [Color; 10] = [
 /// Get color from the default color cycle
 pub fn get_cycle_color(index: usize) -> Color {
     DEFAULT_COLOR_CYCLE[index % DEFAULT_COLOR_CYCLE.len()]
-}
\ No newline at end of file
+}
+
+/// A mapping from the unit interval to colors
+///
+/// Continuous colormaps linearly interpolate between their stops, listed
+/// (qualitative) colormaps split the interval into equal bins of one color.
+/// Positions below 0 or above 1 use the `under`/`over` colors when set and
+/// the end colors otherwise, NaN maps to the `bad` color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colormap {
+    pub name: String,
+    stops: Vec<(f64, Color)>,
+    listed: bool,
+    pub under: Option<Color>,
+    pub over: Option<Color>,
+    pub bad: Color,
+}
+
+/// Color from a `0xRRGGBB` literal
+const fn hex(value: u32) -> Color {
+    Color {
+        r: (value >> 16) as u8,
+        g: (value >> 8) as u8,
+        b: value as u8,
+        a: 1.0,
+    }
+}
+
+const VIRIDIS: [u32; 9] = [
+    0x440154, 0x472d7b, 0x3b528b, 0x2c728e, 0x21918c, 0x28ae80, 0x5ec962, 0xaddc30, 0xfde725,
+];
+const PLASMA: [u32; 9] = [
+    0x0d0887, 0x4c02a1, 0x7e03a8, 0xa92395, 0xcc4778, 0xe56b5d, 0xf89441, 0xfdc328, 0xf0f921,
+];
+const INFERNO: [u32; 9] = [
+    0x000004, 0x1f0c48, 0x550f6d, 0x88226a, 0xba3655, 0xe35933, 0xf98e09, 0xf9cb35, 0xfcffa4,
+];
+const MAGMA: [u32; 9] = [
+    0x000004, 0x1c1044, 0x4f127b, 0x812581, 0xb5367a, 0xe55064, 0xfb8761, 0xfec287, 0xfcfdbf,
+];
+const CIVIDIS: [u32; 9] = [
+    0x00224e, 0x123570, 0x3b496c, 0x575d6d, 0x707173, 0x8a8678, 0xa59c74, 0xc3b369, 0xfee838,
+];
+const GREYS: [u32; 9] = [
+    0xffffff, 0xf0f0f0, 0xd9d9d9, 0xbdbdbd, 0x969696, 0x737373, 0x525252, 0x252525, 0x000000,
+];
+const BLUES: [u32; 9] = [
+    0xf7fbff, 0xdeebf7, 0xc6dbef, 0x9ecae1, 0x6baed6, 0x4292c6, 0x2171b5, 0x08519c, 0x08306b,
+];
+const GREENS: [u32; 9] = [
+    0xf7fcf5, 0xe5f5e0, 0xc7e9c0, 0xa1d99b, 0x74c476, 0x41ab5d, 0x238b45, 0x006d2c, 0x00441b,
+];
+const REDS: [u32; 9] = [
+    0xfff5f0, 0xfee0d2, 0xfcbba1, 0xfc9272, 0xfb6a4a, 0xef3b2c, 0xcb181d, 0xa50f15, 0x67000d,
+];
+const COOLWARM: [u32; 9] = [
+    0x3b4cc0, 0x6282ea, 0x8db0fe, 0xb8d0f9, 0xdddddd, 0xf5c4ad, 0xf49a7b, 0xde604d, 0xb40426,
+];
+const RDBU: [u32; 9] = [
+    0x67001f, 0xb2182b, 0xd6604d, 0xf4a582, 0xf7f7f7, 0x92c5de, 0x4393c3, 0x2166ac, 0x053061,
+];
+const SPECTRAL: [u32; 11] = [
+    0x9e0142, 0xd53e4f, 0xf46d43, 0xfdae61, 0xfee08b, 0xffffbf, 0xe6f598, 0xabdda4, 0x66c2a5,
+    0x3288bd, 0x5e4fa2,
+];
+const SET1: [u32; 9] = [
+    0xe41a1c, 0x377eb8, 0x4daf4a, 0x984ea3, 0xff7f00, 0xffff33, 0xa65628, 0xf781bf, 0x999999,
+];
+
+impl Colormap {
+    /// Create a colormap from `(position, color)` stops with positions in [0, 1]
+    ///
+    /// Positions outside [0, 1] are clamped to it and stops at NaN or
+    /// infinite positions are dropped.
+    pub fn from_stops(name: &str, stops: Vec<(f64, Color)>) -> Self {
+        let mut stops: Vec<(f64, Color)> = stops
+            .into_iter()
+            .filter(|(pos, _)| pos.is_finite())
+            .map(|(pos, color)| (pos.clamp(0.0, 1.0), color))
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Colormap {
+            name: name.to_string(),
+            stops,
+            listed: false,
+            under: None,
+            over: None,
+            bad: Color::rgba(0, 0, 0, 0.0),
+        }
+    }
+
+    /// Create a colormap interpolating between evenly spaced colors
+    pub fn from_colors(name: &str, colors: &[Color]) -> Self {
+        let last = colors.len().saturating_sub(1).max(1) as f64;
+        let stops = colors
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (i as f64 / last, c))
+            .collect();
+        Colormap::from_stops(name, stops)
+    }
+
+    /// Create a qualitative colormap of distinct colors without interpolation
+    pub fn listed(name: &str, colors: &[Color]) -> Self {
+        let mut colormap = Colormap::from_colors(name, colors);
+        colormap.listed = true;
+        colormap
+    }
+
+    fn from_hex(name: &str, values: &[u32]) -> Self {
+        let colors: Vec<Color> = values.iter().map(|&v| hex(v)).collect();
+        Colormap::from_colors(name, &colors)
+    }
+
+    /// The default colormap, blue to yellow through green
+    ///
+    /// This and the other matplotlib perceptually uniform maps ([`plasma`],
+    /// [`inferno`], [`magma`], [`cividis`]) are approximations: linear sRGB
+    /// interpolation between 9 samples of the reference tables, so they are
+    /// close to but not exactly perceptually uniform.
+    ///
+    /// [`plasma`]: Colormap::plasma
+    /// [`inferno`]: Colormap::inferno
+    /// [`magma`]: Colormap::magma
+    /// [`cividis`]: Colormap::cividis
+    pub fn viridis() -> Self {
+        Colormap::from_hex("viridis", &VIRIDIS)
+    }
+
+    /// Approximation of plasma, blue to yellow through magenta
+    pub fn plasma() -> Self {
+        Colormap::from_hex("plasma", &PLASMA)
+    }
+
+    /// Approximation of inferno, black to yellow through red
+    pub fn inferno() -> Self {
+        Colormap::from_hex("inferno", &INFERNO)
+    }
+
+    /// Approximation of magma, black to light pink through purple
+    pub fn magma() -> Self {
+        Colormap::from_hex("magma", &MAGMA)
+    }
+
+    /// Approximation of cividis, a color-vision-deficiency friendly blue to yellow
+    pub fn cividis() -> Self {
+        Colormap::from_hex("cividis", &CIVIDIS)
+    }
+
+    /// Sequential white to black
+    pub fn greys() -> Self {
+        Colormap::from_hex("Greys", &GREYS)
+    }
+
+    /// Sequential white to dark blue
+    pub fn blues() -> Self {
+        Colormap::from_hex("Blues", &BLUES)
+    }
+
+    /// Sequential white to dark green
+    pub fn greens() -> Self {
+        Colormap::from_hex("Greens", &GREENS)
+    }
+
+    /// Sequential white to dark red
+    pub fn reds() -> Self {
+        Colormap::from_hex("Reds", &REDS)
+    }
+
+    /// Diverging blue to red through light gray
+    pub fn coolwarm() -> Self {
+        Colormap::from_hex("coolwarm", &COOLWARM)
+    }
+
+    /// Diverging red to blue through white
+    pub fn rdbu() -> Self {
+        Colormap::from_hex("RdBu", &RDBU)
+    }
+
+    /// Diverging pure blue to pure red through white
+    pub fn bwr() -> Self {
+        Colormap::from_colors("bwr", &[Color::BLUE, Color::WHITE, Color::RED])
+    }
+
+    /// Diverging red to purple through yellow
+    pub fn spectral() -> Self {
+        Colormap::from_hex("Spectral", &SPECTRAL)
+    }
+
+    /// Qualitative map of the ten default cycle colors
+    pub fn tab10() -> Self {
+        Colormap::listed("tab10", &DEFAULT_COLOR_CYCLE)
+    }
+
+    /// Qualitative map of nine saturated colors
+    pub fn set1() -> Self {
+        let colors: Vec<Color> = SET1.iter().map(|&v| hex(v)).collect();
+        Colormap::listed("Set1", &colors)
+    }
+
+    /// Look up a built-in colormap by its matplotlib name
+    ///
+    /// Names ending in `_r` give the reversed colormap.
+    pub fn by_name(name: &str) -> Option<Self> {
+        if let Some(base) = name.strip_suffix("_r") {
+            return Colormap::by_name(base).map(|c| c.reversed());
+        }
+        let colormap = match name.to_lowercase().as_str() {
+            "viridis" => Colormap::viridis(),
+            "plasma" => Colormap::plasma(),
+            "inferno" => Colormap::inferno(),
+            "magma" => Colormap::magma(),
+            "cividis" => Colormap::cividis(),
+            "greys" => Colormap::greys(),
+            "gray" | "grey" => Colormap::greys().reversed().renamed(name),
+            "blues" => Colormap::blues(),
+            "greens" => Colormap::greens(),
+            "reds" => Colormap::reds(),
+            "coolwarm" => Colormap::coolwarm(),
+            "rdbu" => Colormap::rdbu(),
+            "bwr" => Colormap::bwr(),
+            "spectral" => Colormap::spectral(),
+            "tab10" => Colormap::tab10(),
+            "set1" => Colormap::set1(),
+            _ => return None,
+        };
+        Some(colormap)
+    }
+
+    fn renamed(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    /// Whether colors are picked from discrete bins instead of interpolated
+    pub fn is_listed(&self) -> bool {
+        self.listed
+    }
+
+    /// The same colormap running from 1 to 0
+    pub fn reversed(&self) -> Self {
+        let name = match self.name.strip_suffix("_r") {
+            Some(base) => base.to_string(),
+            None => format!("{}_r", self.name),
+        };
+        Colormap {
+            name,
+            stops: self
+                .stops
+                .iter()
+                .rev()
+                .map(|&(p, c)| (1.0 - p, c))
+                .collect(),
+            listed: self.listed,
+            under: self.over,
+            over: self.under,
+            bad: self.bad,
+        }
+    }
+
+    /// `n` colors sampled evenly across the colormap
+    ///
+    /// Listed colormaps are sampled at the bin centers.
+    pub fn colors(&self, n: usize) -> Vec<Color> {
+        match n {
+            0 => Vec::new(),
+            1 => vec![self.at(0.5)],
+            _ if self.listed => (0..n)
+                .map(|i| self.at((i as f64 + 0.5) / n as f64))
+                .collect(),
+            _ => (0..n).map(|i| self.at(i as f64 / (n - 1) as f64)).collect(),
+        }
+    }
+
+    /// The colormap resampled to `n` colors
+    pub fn resampled(&self, n: usize) -> Self {
+        let colors = self.colors(n);
+        let mut colormap = if self.listed {
+            Colormap::listed(&self.name, &colors)
+        } else {
+            Colormap::from_colors(&self.name, &colors)
+        };
+        colormap.under = self.under;
+        colormap.over = self.over;
+        colormap.bad = self.bad;
+        colormap
+    }
+
+    /// Set the color used below the start of the colormap
+    pub fn with_under(mut self, color: Color) -> Self {
+        self.under = Some(color);
+        self
+    }
+
+    /// Set the color used above the end of the colormap
+    pub fn with_over(mut self, color: Color) -> Self {
+        self.over = Some(color);
+        self
+    }
+
+    /// Set the color used for NaN and other unmappable values
+    pub fn with_bad(mut self, color: Color) -> Self {
+        self.bad = color;
+        self
+    }
+
+    /// Color at position `t`, clamped to [0, 1] unless `under`/`over` are set
+    pub fn at(&self, t: f64) -> Color {
+        if t.is_nan() {
+            return self.bad;
+        }
+        let Some(&(_, first)) = self.stops.first() else {
+            return Color::BLACK;
+        };
+        let last = self.stops[self.stops.len() - 1].1;
+        if t < 0.0 {
+            return self.under.unwrap_or(first);
+        }
+        if t > 1.0 {
+            return self.over.unwrap_or(last);
+        }
+
+        if self.listed {
+            let n = self.stops.len();
+            return self.stops[((t * n as f64) as usize).min(n - 1)].1;
+        }
+
+        let index = self.stops.partition_point(|&(pos, _)| pos < t);
+        if index == 0 {
+            return first;
+        }
+        if index >= self.stops.len() {
+            return last;
+        }
+
+        let (p0, c0) = self.stops[index - 1];
+        let (p1, c1) = self.stops[index];
+        let f = if p1 > p0 { (t - p0) / (p1 - p0) } else { 0.0 };
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+        Color::rgba(
+            mix(c0.r, c1.r),
+            mix(c0.g, c1.g),
+            mix(c0.b, c1.b),
+            c0.a + (c1.a - c0.a) * f,
+        )
+    }
+}
+
+impl Default for Colormap {
+    fn default() -> Self {
+        Self::viridis()
+    }
+}
+
+impl FromStr for Colormap {
+    type Err = UnknownColormap;
+
+    /// Colormap from its name, as with [`Colormap::by_name`]
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Colormap::by_name(name).ok_or_else(|| UnknownColormap(name.to_string()))
+    }
+}
+
+/// Error parsing a name that no built-in colormap has
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownColormap(pub String);
+
+impl fmt::Display for UnknownColormap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown colormap: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownColormap {}
+
+/// How values are spread over the colormap by a [`Normalize`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormKind {
+    /// Linear between `vmin` and `vmax`
+    Linear,
+    /// Linear in the logarithm of the value; non-positive values are bad
+    Log,
+    /// Linear within `linthresh` of zero and logarithmic beyond it
+    SymLog { linthresh: f64 },
+    /// Two linear ramps meeting at `center`, which maps to 0.5
+    TwoSlope { center: f64 },
+    /// Linear position raised to `gamma`
+    Power { gamma: f64 },
+}
+
+/// Maps data values to colormap positions, with 0 at `vmin` and 1 at `vmax`
+///
+/// Unset limits are filled from the data by [`Normalize::autoscaled`].
+/// Values outside the limits map outside [0, 1] unless `clip` is set.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normalize {
+    pub kind: NormKind,
+    pub vmin: Option<f64>,
+    pub vmax: Option<f64>,
+    pub clip: bool,
+}
+
+impl Normalize {
+    fn new(kind: NormKind) -> Self {
+        Normalize {
+            kind,
+            vmin: None,
+            vmax: None,
+            clip: false,
+        }
+    }
+
+    /// Linear normalization
+    pub fn linear() -> Self {
+        Normalize::new(NormKind::Linear)
+    }
+
+    /// Logarithmic normalization for positive data
+    pub fn log() -> Self {
+        Normalize::new(NormKind::Log)
+    }
+
+    /// Symmetric logarithmic normalization, linear within `linthresh` of zero
+    pub fn symlog(linthresh: f64) -> Self {
+        Normalize::new(NormKind::SymLog {
+            linthresh: linthresh.abs().max(f64::MIN_POSITIVE),
+        })
+    }
+
+    /// Diverging normalization with `center` in the middle of the colormap
+    pub fn two_slope(center: f64) -> Self {
+        Normalize::new(NormKind::TwoSlope { center })
+    }
+
+    /// Power-law normalization
+    pub fn power(gamma: f64) -> Self {
+        Normalize::new(NormKind::Power { gamma })
+    }
+
+    /// Set the values mapped to 0 and 1
+    pub fn range(mut self, vmin: f64, vmax: f64) -> Self {
+        self.vmin = Some(vmin);
+        self.vmax = Some(vmax);
+        self
+    }
+
+    /// Clamp normalized values to [0, 1]
+    pub fn clip(mut self, clip: bool) -> Self {
+        self.clip = clip;
+        self
+    }
+
+    /// A copy with unset limits taken from the finite (for log, positive) values
+    pub fn autoscaled<'a, I>(&self, values: I) -> Self
+    where
+        I: IntoIterator<Item = &'a f64>,
+    {
+        let mut norm = *self;
+        if norm.vmin.is_some() && norm.vmax.is_some() {
+            return norm;
+        }
+
+        let log = norm.kind == NormKind::Log;
+        let (min, max) = values
+            .into_iter()
+            .filter(|v| v.is_finite() && (!log || **v > 0.0))
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        let (min, max) = match (min.is_finite(), log) {
+            (true, _) => (min, max),
+            (false, true) => (1.0, 10.0),
+            (false, false) => (0.0, 1.0),
+        };
+
+        // A diverging norm always spans its center
+        let (min, max) = match norm.kind {
+            NormKind::TwoSlope { center } => (min.min(center), max.max(center)),
+            _ => (min, max),
+        };
+        norm.vmin = Some(norm.vmin.unwrap_or(min));
+        norm.vmax = Some(norm.vmax.unwrap_or(max));
+        norm
+    }
+
+    /// The `(vmin, vmax)` limits, defaulting to the unit interval when unset
+    pub fn limits(&self) -> (f64, f64) {
+        let default = if self.kind == NormKind::Log {
+            (1.0, 10.0)
+        } else {
+            (0.0, 1.0)
+        };
+        (
+            self.vmin.unwrap_or(default.0),
+            self.vmax.unwrap_or(default.1),
+        )
+    }
+
+    /// Map `value` to a colormap position; NaN if it cannot be mapped
+    pub fn apply(&self, value: f64) -> f64 {
+        let (vmin, vmax) = self.limits();
+        let linear = |v: f64, a: f64, b: f64| if b != a { (v - a) / (b - a) } else { 0.0 };
+
+        let t = match self.kind {
+            NormKind::Linear => linear(value, vmin, vmax),
+            NormKind::Log => {
+                if value <= 0.0 || vmin <= 0.0 || vmax <= 0.0 {
+                    f64::NAN
+                } else {
+                    linear(value.ln(), vmin.ln(), vmax.ln())
+                }
+            }
+            NormKind::SymLog { linthresh } => {
+                let f = |v| symlog_forward(v, linthresh);
+                linear(f(value), f(vmin), f(vmax))
+            }
+            NormKind::TwoSlope { center } => {
+                if value < center {
+                    0.5 * linear(value, vmin, center)
+                } else {
+                    0.5 + 0.5 * linear(value, center, vmax)
+                }
+            }
+            NormKind::Power { gamma } => {
+                let t = linear(value, vmin, vmax);
+                if t > 0.0 { t.powf(gamma) } else { t }
+            }
+        };
+
+        if self.clip { t.clamp(0.0, 1.0) } else { t }
+    }
+
+    /// The value mapped to colormap position `t`
+    pub fn inverse(&self, t: f64) -> f64 {
+        let (vmin, vmax) = self.limits();
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        match self.kind {
+            NormKind::Linear => lerp(vmin, vmax, t),
+            NormKind::Log => lerp(vmin.ln(), vmax.ln(), t).exp(),
+            NormKind::SymLog { linthresh } => {
+                let f = |v| symlog_forward(v, linthresh);
+                symlog_inverse(lerp(f(vmin), f(vmax), t), linthresh)
+            }
+            NormKind::TwoSlope { center } => {
+                if t < 0.5 {
+                    lerp(vmin, center, t * 2.0)
+                } else {
+                    lerp(center, vmax, (t - 0.5) * 2.0)
+                }
+            }
+            NormKind::Power { gamma } => {
+                let t = if t > 0.0 { t.powf(1.0 / gamma) } else { t };
+                lerp(vmin, vmax, t)
+            }
+        }
+    }
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Self::linear()
+    }
+}
+
+fn symlog_forward(value: f64, linthresh: f64) -> f64 {
+    let a = value.abs() / linthresh;
+    let y = if a <= 1.0 { a } else { 1.0 + a.log10() };
+    y.copysign(value)
+}
+
+fn symlog_inverse(y: f64, linthresh: f64) -> f64 {
+    let a = y.abs();
+    let v = if a <= 1.0 {
+        a * linthresh
+    } else {
+        linthresh * 10f64.powf(a - 1.0)
+    };
+    v.copysign(y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colormap_reversed_and_listed() {
+        let viridis = Colormap::viridis();
+        let reversed = Colormap::by_name("viridis_r").unwrap();
+        assert_eq!(reversed.name, "viridis_r");
+        assert_eq!(reversed.at(0.0), viridis.at(1.0));
+        assert_eq!(reversed.at(0.3), viridis.at(0.7));
+        assert_eq!(reversed.reversed(), viridis);
+        assert_eq!("viridis_r".parse::<Colormap>(), Ok(reversed));
+        assert_eq!(
+            "viridus".parse::<Colormap>(),
+            Err(UnknownColormap("viridus".to_string()))
+        );
+
+        // Listed colormaps pick whole bins without interpolating
+        let tab10 = Colormap::tab10();
+        assert_eq!(tab10.at(0.0), DEFAULT_COLOR_CYCLE[0]);
+        assert_eq!(tab10.at(0.19), DEFAULT_COLOR_CYCLE[1]);
+        assert_eq!(tab10.at(1.0), DEFAULT_COLOR_CYCLE[9]);
+        assert_eq!(tab10.colors(10), DEFAULT_COLOR_CYCLE.to_vec());
+
+        let resampled = viridis.resampled(3);
+        assert_eq!(
+            resampled.colors(3),
+            vec![viridis.at(0.0), viridis.at(0.5), viridis.at(1.0)]
+        );
+        assert_eq!(viridis.clone().with_over(Color::RED).at(1.5), Color::RED);
+        assert_eq!(viridis.at(1.5), viridis.at(1.0));
+    }
+
+    #[test]
+    fn test_colormap_from_stops_sanitizes_positions() {
+        let colormap = Colormap::from_stops(
+            "stops",
+            vec![
+                (1.5, Color::RED),
+                (f64::NAN, Color::GREEN),
+                (-0.5, Color::BLUE),
+                (f64::INFINITY, Color::WHITE),
+            ],
+        );
+        assert_eq!(colormap.at(0.0), Color::BLUE);
+        assert_eq!(colormap.at(1.0), Color::RED);
+        assert_eq!(colormap.at(0.5), Color::rgb(128, 0, 128));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let values = [2.0, 4.0, 6.0];
+        let linear = Normalize::linear().autoscaled(&values);
+        assert_eq!(linear.limits(), (2.0, 6.0));
+        assert_eq!(linear.apply(5.0), 0.75);
+        assert_eq!(linear.apply(8.0), 1.5);
+        assert_eq!(linear.clip(true).apply(8.0), 1.0);
+
+        let log = Normalize::log().range(1.0, 100.0);
+        assert!((log.apply(10.0) - 0.5).abs() < 1e-12);
+        assert!(log.apply(-1.0).is_nan());
+        assert!((log.inverse(0.5) - 10.0).abs() < 1e-9);
+
+        // The center sits in the middle no matter how lopsided the data is
+        let two_slope = Normalize::two_slope(0.0).autoscaled(&[-1.0, 4.0]);
+        assert_eq!(two_slope.apply(0.0), 0.5);
+        assert_eq!(two_slope.apply(-1.0), 0.0);
+        assert_eq!(two_slope.apply(2.0), 0.75);
+        assert_eq!(two_slope.inverse(0.75), 2.0);
+
+        let symlog = Normalize::symlog(1.0).range(-100.0, 100.0);
+        assert_eq!(symlog.apply(0.0), 0.5);
+        assert!((symlog.inverse(symlog.apply(30.0)) - 30.0).abs() < 1e-9);
+        assert!((symlog.inverse(symlog.apply(-0.5)) + 0.5).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,517 @@
+//! Contour and filled contour plots of gridded data
+
+use std::collections::HashMap;
+
+use crate::IntoVec;
+use crate::colors::{Color, Colormap, Normalize};
+use crate::markers::Marker;
+use crate::plot::{Plot, PlotType};
+use crate::render::{Anchor, PathData, Renderer, Style, TextStyle};
+use crate::scale::PlotArea;
+use crate::utils::{format_number, generate_ticks};
+
+/// How contour levels are chosen
+#[derive(Debug, Clone, PartialEq)]
+pub enum Levels {
+    /// Roughly this many "nice" levels spanning the data
+    Auto(usize),
+    /// Explicit, increasing level values
+    Values(Vec<f64>),
+}
+
+/// Contour specific settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContourStyle {
+    pub levels: Levels,
+    /// Fill the regions between levels instead of drawing isolines
+    pub filled: bool,
+    pub colormap: Colormap,
+    /// Maps level values to colormap positions; spans the levels if unset
+    pub norm: Normalize,
+    /// Draw every isoline in this color instead of using the colormap
+    pub line_color: Option<Color>,
+    /// Label isolines with their level value
+    pub inline_labels: bool,
+    pub font_size: f64,
+}
+
+impl ContourStyle {
+    fn new(filled: bool) -> Self {
+        ContourStyle {
+            levels: Levels::Auto(8),
+            filled,
+            colormap: Colormap::viridis(),
+            norm: Normalize::linear(),
+            line_color: None,
+            inline_labels: false,
+            font_size: 10.0,
+        }
+    }
+}
+
+/// A connected isoline in data coordinates
+#[derive(Debug, Clone, PartialEq)]
+pub struct Isoline {
+    pub level: f64,
+    pub points: Vec<(f64, f64)>,
+    pub closed: bool,
+}
+
+/// Identifies the grid edge an isoline crosses, so segments can be joined exactly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum EdgeKey {
+    /// Edge from (i, j) to (i + 1, j)
+    Horizontal(usize, usize),
+    /// Edge from (i, j) to (i, j + 1)
+    Vertical(usize, usize),
+}
+
+/// Gridded data: `z[j][i]` is the value at `(x[i], y[j])`
+struct Grid<'a> {
+    x: &'a [f64],
+    y: &'a [f64],
+    z: &'a [Vec<f64>],
+}
+
+impl Grid<'_> {
+    fn value(&self, i: usize, j: usize) -> f64 {
+        self.z[j][i]
+    }
+
+    /// Point where `level` crosses the given edge, by linear interpolation
+    fn crossing(&self, edge: EdgeKey, level: f64) -> (f64, f64) {
+        let ((i0, j0), (i1, j1)) = match edge {
+            EdgeKey::Horizontal(i, j) => ((i, j), (i + 1, j)),
+            EdgeKey::Vertical(i, j) => ((i, j), (i, j + 1)),
+        };
+        let (z0, z1) = (self.value(i0, j0), self.value(i1, j1));
+        let t = if z1 != z0 {
+            (level - z0) / (z1 - z0)
+        } else {
+            0.5
+        };
+        (
+            self.x[i0] + (self.x[i1] - self.x[i0]) * t,
+            self.y[j0] + (self.y[j1] - self.y[j0]) * t,
+        )
+    }
+}
+
+/// Trace the isolines of `z` at `level` with marching squares
+///
+/// `z[j][i]` is the value at `(x[i], y[j])`. Cells containing non-finite values are skipped.
+pub fn isolines(x: &[f64], y: &[f64], z: &[Vec<f64>], level: f64) -> Vec<Isoline> {
+    let (nx, ny) = (x.len(), y.len());
+    if nx < 2 || ny < 2 || z.len() < ny || z.iter().take(ny).any(|row| row.len() < nx) {
+        return Vec::new();
+    }
+    let grid = Grid { x, y, z };
+
+    let mut segments: Vec<(EdgeKey, EdgeKey)> = Vec::new();
+    for j in 0..ny - 1 {
+        for i in 0..nx - 1 {
+            let corners = [
+                grid.value(i, j),
+                grid.value(i + 1, j),
+                grid.value(i + 1, j + 1),
+                grid.value(i, j + 1),
+            ];
+            if corners.iter().any(|v| !v.is_finite()) {
+                continue;
+            }
+
+            let case = corners
+                .iter()
+                .enumerate()
+                .fold(0, |acc, (k, &v)| acc | (((v >= level) as usize) << k));
+
+            let bottom = EdgeKey::Horizontal(i, j);
+            let right = EdgeKey::Vertical(i + 1, j);
+            let top = EdgeKey::Horizontal(i, j + 1);
+            let left = EdgeKey::Vertical(i, j);
+
+            match case {
+                0 | 15 => {}
+                1 | 14 => segments.push((left, bottom)),
+                2 | 13 => segments.push((bottom, right)),
+                3 | 12 => segments.push((left, right)),
+                4 | 11 => segments.push((right, top)),
+                6 | 9 => segments.push((bottom, top)),
+                7 | 8 => segments.push((left, top)),
+                5 | 10 => {
+                    // Saddle: resolve with the average of the corners
+                    let center = corners.iter().sum::<f64>() / 4.0;
+                    let center_above = center >= level;
+                    if (case == 5) == center_above {
+                        segments.push((left, top));
+                        segments.push((bottom, right));
+                    } else {
+                        segments.push((left, bottom));
+                        segments.push((right, top));
+                    }
+                }
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    join_segments(&segments)
+        .into_iter()
+        .map(|(edges, closed)| Isoline {
+            level,
+            points: edges.iter().map(|&e| grid.crossing(e, level)).collect(),
+            closed,
+        })
+        .collect()
+}
+
+/// Chain segments sharing an edge into polylines
+fn join_segments(segments: &[(EdgeKey, EdgeKey)]) -> Vec<(Vec<EdgeKey>, bool)> {
+    let mut by_edge: HashMap<EdgeKey, Vec<usize>> = HashMap::new();
+    for (index, &(a, b)) in segments.iter().enumerate() {
+        by_edge.entry(a).or_default().push(index);
+        by_edge.entry(b).or_default().push(index);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let mut lines = Vec::new();
+
+    // Follow unused segments from `edge` and append the reached edges
+    let walk = |mut edge: EdgeKey, used: &mut Vec<bool>, line: &mut Vec<EdgeKey>| {
+        while let Some(&next) = by_edge[&edge].iter().find(|&&s| !used[s]) {
+            used[next] = true;
+            let (a, b) = segments[next];
+            edge = if a == edge { b } else { a };
+            line.push(edge);
+        }
+    };
+
+    for start in 0..segments.len() {
+        if used[start] {
+            continue;
+        }
+        used[start] = true;
+        let (a, b) = segments[start];
+
+        let mut forward = vec![a, b];
+        walk(b, &mut used, &mut forward);
+        let closed = forward.len() > 2 && forward.first() == forward.last();
+
+        if !closed {
+            let mut backward = Vec::new();
+            walk(a, &mut used, &mut backward);
+            backward.reverse();
+            backward.extend(forward);
+            forward = backward;
+        }
+        lines.push((forward, closed));
+    }
+
+    lines
+}
+
+/// Clip a polygon whose vertices carry values to the part above (or below) `level`
+///
+/// Values are interpolated linearly along the polygon edges.
+fn clip_polygon(polygon: &[(f64, f64, f64)], level: f64, above: bool) -> Vec<(f64, f64, f64)> {
+    let inside = |v: f64| if above { v >= level } else { v <= level };
+    let mut clipped = Vec::with_capacity(polygon.len() + 2);
+
+    for k in 0..polygon.len() {
+        let current = polygon[k];
+        let next = polygon[(k + 1) % polygon.len()];
+        if inside(current.2) {
+            clipped.push(current);
+        }
+        if inside(current.2) != inside(next.2) {
+            let t = (level - current.2) / (next.2 - current.2);
+            clipped.push((
+                current.0 + (next.0 - current.0) * t,
+                current.1 + (next.1 - current.1) * t,
+                level,
+            ));
+        }
+    }
+
+    clipped
+}
+
+/// Polygons covering the region where `low <= z <= high`, one or more per grid cell
+pub fn band_polygons(
+    x: &[f64],
+    y: &[f64],
+    z: &[Vec<f64>],
+    low: f64,
+    high: f64,
+) -> Vec<Vec<(f64, f64)>> {
+    let (nx, ny) = (x.len(), y.len());
+    let mut polygons = Vec::new();
+    if nx < 2 || ny < 2 || z.len() < ny || z.iter().take(ny).any(|row| row.len() < nx) {
+        return polygons;
+    }
+
+    for j in 0..ny - 1 {
+        for i in 0..nx - 1 {
+            let cell = [
+                (x[i], y[j], z[j][i]),
+                (x[i + 1], y[j], z[j][i + 1]),
+                (x[i + 1], y[j + 1], z[j + 1][i + 1]),
+                (x[i], y[j + 1], z[j + 1][i]),
+            ];
+            if cell.iter().any(|c| !c.2.is_finite()) {
+                continue;
+            }
+
+            let clipped = clip_polygon(&clip_polygon(&cell, low, true), high, false);
+            if clipped.len() >= 3 {
+                polygons.push(clipped.iter().map(|&(px, py, _)| (px, py)).collect());
+            }
+        }
+    }
+
+    polygons
+}
+
+impl Plot {
+    /// Create a new contour plot with isolines of `z` over the `x`/`y` grid
+    ///
+    /// `z[j][i]` is the value at `(x[i], y[j])`.
+    pub fn contour<X, Y>(x: X, y: Y, z: Vec<Vec<f64>>) -> Self
+    where
+        X: IntoVec<f64>,
+        Y: IntoVec<f64>,
+    {
+        Plot {
+            x_data: x.into_vec(),
+            y_data: y.into_vec(),
+            z_data: Some(z),
+            plot_type: PlotType::Contour(ContourStyle::new(false)),
+            color: None,
+            marker: Marker::None,
+            marker_size: 0.0,
+            line_width: 1.5,
+            label: None,
+            alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    /// Create a new filled contour plot of `z` over the `x`/`y` grid
+    pub fn contourf<X, Y>(x: X, y: Y, z: Vec<Vec<f64>>) -> Self
+    where
+        X: IntoVec<f64>,
+        Y: IntoVec<f64>,
+    {
+        let mut plot = Plot::contour(x, y, z);
+        plot.plot_type = PlotType::Contour(ContourStyle::new(true));
+        plot.line_width = 0.0;
+        plot
+    }
+
+    fn contour_style_mut(&mut self) -> Option<&mut ContourStyle> {
+        match self.plot_type {
+            PlotType::Contour(ref mut style) => Some(style),
+            _ => None,
+        }
+    }
+
+    /// Set the contour levels
+    pub fn levels(mut self, levels: Levels) -> Self {
+        if let Some(style) = self.contour_style_mut() {
+            style.levels = levels;
+        }
+        self
+    }
+
+    /// Draw all isolines in a single color
+    pub fn line_color(mut self, color: Color) -> Self {
+        if let Some(style) = self.contour_style_mut() {
+            style.line_color = Some(color);
+        }
+        self
+    }
+
+    /// Label isolines with their level value
+    pub fn clabel(mut self, enable: bool) -> Self {
+        if let Some(style) = self.contour_style_mut() {
+            style.inline_labels = enable;
+        }
+        self
+    }
+
+    /// Resolve the contour levels of a contour plot against its data
+    ///
+    /// Filled contours get levels that enclose the whole data range.
+    pub fn contour_levels(&self) -> Vec<f64> {
+        let (PlotType::Contour(style), Some(z)) = (&self.plot_type, &self.z_data) else {
+            return Vec::new();
+        };
+
+        let count = match &style.levels {
+            Levels::Values(values) => return values.clone(),
+            Levels::Auto(count) => *count,
+        };
+
+        let (min, max) = z
+            .iter()
+            .flatten()
+            .filter(|v| v.is_finite())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        if min >= max {
+            return if min.is_finite() {
+                vec![min]
+            } else {
+                Vec::new()
+            };
+        }
+
+        let ticks = generate_ticks(min, max, count.max(2));
+        if !style.filled || ticks.len() < 2 {
+            return ticks;
+        }
+
+        // Extend the nice levels outwards so every value falls in a band
+        let step = ticks[1] - ticks[0];
+        let start = (min / step).floor() * step;
+        let end = (max / step).ceil() * step;
+        let n = ((end - start) / step).round() as usize;
+        (0..=n).map(|k| start + step * k as f64).collect()
+    }
+
+    pub(crate) fn draw_contour(
+        &self,
+        renderer: &mut dyn Renderer,
+        style: &ContourStyle,
+        area: &PlotArea,
+    ) {
+        let Some(ref z) = self.z_data else {
+            return;
+        };
+
+        let to_svg = |(x, y): (f64, f64)| area.point(x, y);
+        let levels = self.contour_levels();
+        if levels.is_empty() {
+            return;
+        }
+        let norm = style.norm.autoscaled(&levels);
+        let color_of = |level: f64| style.colormap.at(norm.apply(level));
+
+        if style.filled {
+            for band in levels.windows(2) {
+                let polygons = band_polygons(&self.x_data, &self.y_data, z, band[0], band[1]);
+                if polygons.is_empty() {
+                    continue;
+                }
+
+                let mut path = PathData::new();
+                for polygon in &polygons {
+                    let points: Vec<(f64, f64)> = polygon.iter().map(|&p| to_svg(p)).collect();
+                    path.extend(PathData::polygon(&points));
+                }
+
+                // A hairline stroke in the fill color hides seams between cells
+                let color = color_of((band[0] + band[1]) / 2.0);
+                let fill = Style::filled(color)
+                    .stroke(color, 0.5)
+                    .round_join()
+                    .opacity(self.alpha);
+                renderer.path(&path, &fill);
+            }
+        }
+
+        if self.line_width <= 0.0 && !style.inline_labels {
+            return;
+        }
+
+        for &level in &levels {
+            let lines = isolines(&self.x_data, &self.y_data, z, level);
+            let color = style.line_color.unwrap_or_else(|| color_of(level));
+
+            if self.line_width > 0.0 {
+                let mut path = PathData::new();
+                for line in &lines {
+                    let points: Vec<(f64, f64)> = line.points.iter().map(|&p| to_svg(p)).collect();
+                    path.extend(if line.closed {
+                        PathData::polygon(&points)
+                    } else {
+                        PathData::polyline(&points)
+                    });
+                }
+                if !path.is_empty() {
+                    let stroke = Style::stroked(color, self.line_width).opacity(self.alpha);
+                    renderer.path(&path, &stroke);
+                }
+            }
+
+            if style.inline_labels {
+                // Label the middle of the longest isoline of this level
+                if let Some(line) = lines.iter().max_by_key(|l| l.points.len()) {
+                    let (px, py) = to_svg(line.points[line.points.len() / 2]);
+                    let text = TextStyle::new(style.font_size, color)
+                        .anchor(Anchor::Middle)
+                        .dy(0.35)
+                        .halo(Color::WHITE, 3.0);
+                    renderer.text(px, py, &format_number(level), &text);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cone() -> (Vec<f64>, Vec<f64>, Vec<Vec<f64>>) {
+        let x: Vec<f64> = (0..21).map(|i| i as f64 / 10.0 - 1.0).collect();
+        let y = x.clone();
+        let z = y
+            .iter()
+            .map(|&yv| x.iter().map(|&xv| (xv * xv + yv * yv).sqrt()).collect())
+            .collect();
+        (x, y, z)
+    }
+
+    #[test]
+    fn test_isolines_closed_circle() {
+        let (x, y, z) = cone();
+        let lines = isolines(&x, &y, &z, 0.5);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].closed);
+        for &(px, py) in &lines[0].points {
+            assert!(((px * px + py * py).sqrt() - 0.5).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn test_band_polygons_area() {
+        let (x, y, z) = cone();
+        // The band 0 <= r <= 0.5 approximates a disc of radius 0.5
+        let area: f64 = band_polygons(&x, &y, &z, 0.0, 0.5)
+            .iter()
+            .map(|p| {
+                let n = p.len();
+                (0..n)
+                    .map(|k| p[k].0 * p[(k + 1) % n].1 - p[(k + 1) % n].0 * p[k].1)
+                    .sum::<f64>()
+                    .abs()
+                    / 2.0
+            })
+            .sum();
+        assert!((area - std::f64::consts::PI * 0.25).abs() < 0.02);
+    }
+
+    #[test]
+    fn test_contour_levels() {
+        let (x, y, z) = cone();
+        let plot = Plot::contourf(x.clone(), y.clone(), z.clone()).levels(Levels::Auto(5));
+        let levels = plot.contour_levels();
+        assert!(levels[0] <= 0.0 && *levels.last().unwrap() >= 2f64.sqrt());
+
+        let plot = Plot::contour(x, y, z).levels(Levels::Values(vec![0.25, 0.5]));
+        assert_eq!(plot.contour_levels(), vec![0.25, 0.5]);
+    }
+}
@@ -0,0 +1,569 @@
+//! Date and time axes
+//!
+//! Times are plotted as Unix timestamps in seconds. A [`DateAxis`] installed
+//! as an axis scale places ticks on calendar boundaries and labels them as
+//! dates instead of large numbers.
+
+use crate::scale::{Scale, Transform};
+
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 3600.0;
+const DAY: f64 = 86400.0;
+
+/// Largest timestamp magnitude dates are computed for, about a billion years
+/// either side of 1970 so that years stay well inside `i32`
+const MAX_TIMESTAMP: f64 = (i32::MAX / 2) as f64 * 365.2425 * DAY;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAY_NAMES: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// A calendar date and time of day, precise to the second
+///
+/// Fields are not validated; out of range values roll over when converted to
+/// a timestamp (e.g. month 13 is January of the next year).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    pub year: i32,
+    /// 1 to 12
+    pub month: u32,
+    /// 1 to 31
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    /// Midnight at the start of the given day
+    pub fn new(year: i32, month: u32, day: u32) -> Self {
+        DateTime {
+            year,
+            month,
+            day,
+            hour: 0,
+            minute: 0,
+            second: 0,
+        }
+    }
+
+    /// The same day at the given time
+    pub fn and_hms(mut self, hour: u32, minute: u32, second: u32) -> Self {
+        self.hour = hour;
+        self.minute = minute;
+        self.second = second;
+        self
+    }
+
+    /// Date and time of a Unix timestamp, rounded down to the second
+    ///
+    /// Timestamps more than about a billion years from 1970 are clamped to
+    /// that range.
+    pub fn from_timestamp(timestamp: f64) -> Self {
+        let seconds = timestamp.clamp(-MAX_TIMESTAMP, MAX_TIMESTAMP).floor() as i64;
+        let days = seconds.div_euclid(DAY as i64);
+        let time = seconds.rem_euclid(DAY as i64) as u32;
+        let (year, month, day) = civil_from_days(days);
+        DateTime {
+            year,
+            month,
+            day,
+            hour: time / 3600,
+            minute: time / 60 % 60,
+            second: time % 60,
+        }
+    }
+
+    /// Seconds since 1970-01-01 00:00:00
+    pub fn timestamp(&self) -> f64 {
+        // Normalize the month so that e.g. month 13 rolls over
+        let months = self.year as i64 * 12 + self.month as i64 - 1;
+        let (year, month) = (
+            months.div_euclid(12) as i32,
+            months.rem_euclid(12) as u32 + 1,
+        );
+        let days = days_from_civil(year, month, 1) + self.day as i64 - 1;
+        let seconds = self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64;
+        (days * DAY as i64 + seconds) as f64
+    }
+
+    /// Day of the week, 0 for Sunday through 6 for Saturday
+    pub fn weekday(&self) -> u32 {
+        let days = (self.timestamp() / DAY).floor() as i64;
+        // 1970-01-01 was a Thursday
+        (days + 4).rem_euclid(7) as u32
+    }
+
+    /// Day of the year, starting at 1 for January 1st
+    pub fn ordinal(&self) -> u32 {
+        let start = DateTime::new(self.year, 1, 1).timestamp();
+        ((self.timestamp() - start) / DAY) as u32 + 1
+    }
+
+    /// Format with strftime-like directives
+    ///
+    /// Supported: `%Y` `%y` `%m` `%d` `%e` `%j` `%H` `%I` `%p` `%M` `%S`
+    /// `%b` `%B` `%a` `%A` and `%%`. Unknown directives are kept verbatim.
+    pub fn format(&self, format: &str) -> String {
+        let mut out = String::new();
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+            let month = MONTH_NAMES[(self.month.clamp(1, 12) - 1) as usize];
+            let weekday = WEEKDAY_NAMES[self.weekday() as usize];
+            match chars.next() {
+                Some('Y') => out.push_str(&self.year.to_string()),
+                Some('y') => out.push_str(&format!("{:02}", self.year.rem_euclid(100))),
+                Some('m') => out.push_str(&format!("{:02}", self.month)),
+                Some('d') => out.push_str(&format!("{:02}", self.day)),
+                Some('e') => out.push_str(&format!("{:>2}", self.day)),
+                Some('j') => out.push_str(&format!("{:03}", self.ordinal())),
+                Some('H') => out.push_str(&format!("{:02}", self.hour)),
+                Some('I') => out.push_str(&format!("{:02}", (self.hour + 11) % 12 + 1)),
+                Some('p') => out.push_str(if self.hour < 12 { "AM" } else { "PM" }),
+                Some('M') => out.push_str(&format!("{:02}", self.minute)),
+                Some('S') => out.push_str(&format!("{:02}", self.second)),
+                Some('b') => out.push_str(&month[..3]),
+                Some('B') => out.push_str(month),
+                Some('a') => out.push_str(&weekday[..3]),
+                Some('A') => out.push_str(weekday),
+                Some('%') => out.push('%'),
+                Some(other) => {
+                    out.push('%');
+                    out.push(other);
+                }
+                None => out.push('%'),
+            }
+        }
+        out
+    }
+}
+
+/// Values that can be placed on a date axis
+pub trait ToTimestamp {
+    /// Seconds since 1970-01-01 00:00:00 UTC
+    fn to_timestamp(&self) -> f64;
+}
+
+impl ToTimestamp for DateTime {
+    fn to_timestamp(&self) -> f64 {
+        self.timestamp()
+    }
+}
+
+impl ToTimestamp for f64 {
+    fn to_timestamp(&self) -> f64 {
+        *self
+    }
+}
+
+impl ToTimestamp for i64 {
+    fn to_timestamp(&self) -> f64 {
+        *self as f64
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> ToTimestamp for chrono::DateTime<Tz> {
+    fn to_timestamp(&self) -> f64 {
+        self.timestamp() as f64 + self.timestamp_subsec_nanos() as f64 * 1e-9
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToTimestamp for chrono::NaiveDateTime {
+    fn to_timestamp(&self) -> f64 {
+        self.and_utc().to_timestamp()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl ToTimestamp for chrono::NaiveDate {
+    fn to_timestamp(&self) -> f64 {
+        self.and_time(chrono::NaiveTime::MIN).to_timestamp()
+    }
+}
+
+/// Convert dates to the timestamps plots are built from
+pub fn timestamps<T: ToTimestamp>(values: &[T]) -> Vec<f64> {
+    values.iter().map(|v| v.to_timestamp()).collect()
+}
+
+/// Calendar unit ticks are placed on
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Year,
+}
+
+/// Tick spacings from finest to coarsest, with their approximate length in seconds
+const INTERVALS: [(Unit, u32, f64); 25] = [
+    (Unit::Second, 1, 1.0),
+    (Unit::Second, 2, 2.0),
+    (Unit::Second, 5, 5.0),
+    (Unit::Second, 10, 10.0),
+    (Unit::Second, 15, 15.0),
+    (Unit::Second, 30, 30.0),
+    (Unit::Minute, 1, MINUTE),
+    (Unit::Minute, 2, 2.0 * MINUTE),
+    (Unit::Minute, 5, 5.0 * MINUTE),
+    (Unit::Minute, 10, 10.0 * MINUTE),
+    (Unit::Minute, 15, 15.0 * MINUTE),
+    (Unit::Minute, 30, 30.0 * MINUTE),
+    (Unit::Hour, 1, HOUR),
+    (Unit::Hour, 2, 2.0 * HOUR),
+    (Unit::Hour, 3, 3.0 * HOUR),
+    (Unit::Hour, 6, 6.0 * HOUR),
+    (Unit::Hour, 12, 12.0 * HOUR),
+    (Unit::Day, 1, DAY),
+    (Unit::Day, 2, 2.0 * DAY),
+    (Unit::Day, 7, 7.0 * DAY),
+    (Unit::Day, 14, 14.0 * DAY),
+    (Unit::Month, 1, 30.4 * DAY),
+    (Unit::Month, 2, 61.0 * DAY),
+    (Unit::Month, 3, 91.3 * DAY),
+    (Unit::Month, 6, 182.6 * DAY),
+];
+
+/// An axis of Unix timestamps with calendar-aware ticks
+///
+/// Install it with `set_xscale(DateAxis::new())`. Without a fixed format,
+/// labels are concise: each tick shows only the coarsest unit that changes
+/// there, e.g. the year on January 1st, the month name on the 1st of other
+/// months and the time of day elsewhere. What the labels leave out is shown
+/// once at the end of the axis: the date when ticks show times of day, the
+/// year when no tick shows it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DateAxis {
+    /// strftime-like tick label format; concise automatic labels if unset
+    pub format: Option<String>,
+    /// Upper bound on the number of ticks
+    pub max_ticks: usize,
+    /// Offset of the displayed local time from UTC in seconds
+    pub utc_offset: i64,
+}
+
+impl DateAxis {
+    /// A UTC date axis with concise labels
+    pub fn new() -> Self {
+        DateAxis {
+            format: None,
+            max_ticks: 8,
+            utc_offset: 0,
+        }
+    }
+
+    /// Label ticks with a strftime-like format, e.g. `"%Y-%m-%d"`
+    pub fn format(mut self, format: &str) -> Self {
+        self.format = Some(format.to_string());
+        self
+    }
+
+    /// Set the upper bound on the number of ticks
+    pub fn max_ticks(mut self, count: usize) -> Self {
+        self.max_ticks = count.max(2);
+        self
+    }
+
+    /// Show local time `offset` seconds ahead of UTC
+    pub fn utc_offset(mut self, offset: i64) -> Self {
+        self.utc_offset = offset;
+        self
+    }
+
+    fn local(&self, timestamp: f64) -> DateTime {
+        DateTime::from_timestamp(timestamp + self.utc_offset as f64)
+    }
+
+    /// Ticks every `step` units between the local times `min` and `max`
+    fn calendar_ticks(unit: Unit, step: u32, min: f64, max: f64) -> Vec<f64> {
+        let mut ticks = Vec::new();
+        let start = DateTime::from_timestamp(min);
+        match unit {
+            Unit::Second | Unit::Minute | Unit::Hour => {
+                let size = match unit {
+                    Unit::Second => 1.0,
+                    Unit::Minute => MINUTE,
+                    _ => HOUR,
+                } * step as f64;
+                // Whole multiples of the step since midnight line up with the clock
+                let mut t = (min / size).ceil() * size;
+                while t <= max {
+                    ticks.push(t);
+                    t += size;
+                }
+            }
+            Unit::Day => {
+                let mut date = DateTime::new(start.year, start.month, start.day);
+                while date.timestamp() <= max {
+                    // Restart the count every month, like ticks on the 1st, 8th, 15th...
+                    if (date.day - 1).is_multiple_of(step) && date.day + step <= 32 || date.day == 1
+                    {
+                        ticks.push(date.timestamp());
+                    }
+                    date = DateTime::from_timestamp(date.timestamp() + DAY);
+                }
+            }
+            Unit::Month => {
+                let mut date = DateTime::new(start.year, start.month, 1);
+                while date.timestamp() <= max {
+                    if (date.month - 1).is_multiple_of(step) {
+                        ticks.push(date.timestamp());
+                    }
+                    date = DateTime::new(date.year, date.month + 1, 1);
+                    date = DateTime::from_timestamp(date.timestamp());
+                }
+            }
+            Unit::Year => {
+                let step = step as i64;
+                let mut year = (start.year as i64).div_euclid(step) * step;
+                while let Ok(y) = i32::try_from(year) {
+                    let t = DateTime::new(y, 1, 1).timestamp();
+                    if t > max {
+                        break;
+                    }
+                    ticks.push(t);
+                    year += step;
+                }
+            }
+        }
+        ticks.retain(|&t| t >= min && t <= max);
+        ticks
+    }
+}
+
+impl Default for DateAxis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Transform for DateAxis {
+    fn forward(&self, value: f64) -> f64 {
+        value
+    }
+
+    fn inverse(&self, value: f64) -> f64 {
+        value
+    }
+
+    /// Ticks on the finest calendar interval that keeps within `max_ticks`
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        let (min, max) = (min.min(max), min.max(max));
+        // Far out timestamps, e.g. nanoseconds passed as seconds, have no calendar ticks
+        if !(min.abs() <= MAX_TIMESTAMP && max.abs() <= MAX_TIMESTAMP) {
+            return Vec::new();
+        }
+
+        // Work in local time so that days start at local midnight
+        let offset = self.utc_offset as f64;
+        let (low, high) = (min + offset, max + offset);
+        let span = high - low;
+        let max_ticks = self.max_ticks.max(2) as f64;
+
+        let ticks = match INTERVALS.iter().find(|(_, _, len)| span / len <= max_ticks) {
+            Some(&(unit, step, _)) => DateAxis::calendar_ticks(unit, step, low, high),
+            None => {
+                // Yearly ticks on a 1-2-5 progression
+                let years = span / (365.25 * DAY) / max_ticks;
+                let magnitude = 10f64.powf(years.max(1.0).log10().floor());
+                let step = [1.0, 2.0, 5.0, 10.0]
+                    .iter()
+                    .map(|m| m * magnitude)
+                    .find(|&s| s >= years)
+                    .unwrap_or(10.0 * magnitude);
+                DateAxis::calendar_ticks(Unit::Year, step as u32, low, high)
+            }
+        };
+        ticks.into_iter().map(|t| t - offset).collect()
+    }
+
+    fn format_tick(&self, value: f64) -> String {
+        let date = self.local(value);
+        if let Some(ref format) = self.format {
+            return date.format(format);
+        }
+
+        let format = if (date.hour, date.minute, date.second) != (0, 0, 0) {
+            if date.second != 0 {
+                "%H:%M:%S"
+            } else {
+                "%H:%M"
+            }
+        } else if date.day != 1 {
+            "%b %d"
+        } else if date.month != 1 {
+            "%b"
+        } else {
+            "%Y"
+        };
+        date.format(format)
+    }
+
+    fn offset_text(&self, ticks: &[f64]) -> Option<String> {
+        if self.format.is_some() {
+            return None;
+        }
+        let dates: Vec<DateTime> = ticks.iter().map(|&t| self.local(t)).collect();
+        let first = dates.first()?;
+        if dates
+            .iter()
+            .any(|d| (d.hour, d.minute, d.second) != (0, 0, 0))
+        {
+            Some(first.format("%Y-%m-%d"))
+        } else if dates.iter().all(|d| (d.month, d.day) != (1, 1)) {
+            Some(first.format("%Y"))
+        } else {
+            None
+        }
+    }
+}
+
+impl From<DateAxis> for Scale {
+    fn from(axis: DateAxis) -> Self {
+        Scale::custom(axis)
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let year = year as i64 - if month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Proleptic Gregorian `(year, month, day)` of a count of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year as i32, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_time_round_trip() {
+        let date = DateTime::new(2024, 2, 29).and_hms(13, 5, 9);
+        assert_eq!(date.timestamp(), 1709211909.0);
+        assert_eq!(DateTime::from_timestamp(date.timestamp()), date);
+        assert_eq!(
+            DateTime::from_timestamp(-1.0),
+            DateTime::new(1969, 12, 31).and_hms(23, 59, 59)
+        );
+        assert_eq!(
+            DateTime::new(2023, 13, 1).timestamp(),
+            DateTime::new(2024, 1, 1).timestamp()
+        );
+        assert_eq!(
+            date.format("%a %d %b %Y %I:%M %p, day %j"),
+            "Thu 29 Feb 2024 01:05 PM, day 060"
+        );
+    }
+
+    #[test]
+    fn test_date_ticks() {
+        let axis = DateAxis::new();
+        let day = |d: u32| DateTime::new(2024, 3, d).timestamp();
+
+        // A week of data gets daily ticks at midnight
+        let ticks = axis.ticks(day(1) + 3600.0, day(7) + 3600.0);
+        assert_eq!(ticks, (2..=7).map(day).collect::<Vec<_>>());
+        assert_eq!(axis.format_tick(day(2)), "Mar 02");
+        assert_eq!(axis.format_tick(day(1)), "Mar");
+        assert_eq!(axis.format_tick(day(1) + 5400.0), "01:30");
+
+        // A few years get ticks on January 1st
+        let ticks = axis.ticks(
+            DateTime::new(2019, 6, 1).timestamp(),
+            DateTime::new(2024, 6, 1).timestamp(),
+        );
+        let labels: Vec<String> = ticks.iter().map(|&t| axis.format_tick(t)).collect();
+        assert!(labels.contains(&"2020".to_string()));
+        assert!(labels.iter().all(|l| l.len() == 4 || l.len() == 3));
+
+        // Minutes line up with the clock
+        let ticks = axis.ticks(day(1) + 61.0, day(1) + 61.0 + 400.0);
+        assert_eq!(ticks.first(), Some(&(day(1) + 120.0)));
+
+        let fixed = DateAxis::new().format("%Y-%m-%d");
+        assert_eq!(fixed.format_tick(day(5)), "2024-03-05");
+    }
+
+    #[test]
+    fn test_out_of_range_dates() {
+        // Nanosecond timestamps read as seconds
+        let axis = DateAxis::new();
+        assert!(axis.ticks(1.7e18, 1.7e18 + 8.64e13).is_empty());
+        assert_eq!(
+            DateTime::from_timestamp(1.7e18),
+            DateTime::from_timestamp(MAX_TIMESTAMP)
+        );
+
+        // Spans of millions of years still get yearly ticks near the limit
+        let ticks = axis.ticks(MAX_TIMESTAMP - 8.64e13, MAX_TIMESTAMP);
+        assert!(!ticks.is_empty());
+        assert!(ticks.iter().all(|&t| t <= MAX_TIMESTAMP));
+    }
+
+    #[test]
+    fn test_date_offset_text() {
+        let axis = DateAxis::new();
+        let day = |d: u32| DateTime::new(2024, 3, d).timestamp();
+
+        // Times of day are dated once at the end of the axis, also across midnight
+        let ticks = axis.ticks(day(1) + 18.0 * 3600.0, day(2) + 6.0 * 3600.0);
+        assert!(ticks.contains(&day(2)));
+        assert_eq!(axis.offset_text(&ticks).as_deref(), Some("2024-03-01"));
+
+        // Days without a year tick get the year, years need no offset
+        let ticks = axis.ticks(day(1), day(7));
+        assert_eq!(axis.offset_text(&ticks).as_deref(), Some("2024"));
+        let ticks = axis.ticks(
+            DateTime::new(2019, 6, 1).timestamp(),
+            DateTime::new(2024, 6, 1).timestamp(),
+        );
+        assert_eq!(axis.offset_text(&ticks), None);
+        assert_eq!(axis.format("%H:%M").offset_text(&[day(1) + 60.0]), None);
+    }
+}
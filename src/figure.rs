@@ -1,17 +1,64 @@
 //! Figure management and SVG generation
 
-use crate::axes::Axes;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::axes::{self, Axes, SubplotContext};
 use crate::colors::Color;
+use crate::gridspec::{GridSpec, SubplotSpec, Subplots};
+use crate::layout::Margins;
+use crate::pdf::{PdfError, PdfPages};
+use crate::raster::{self, RasterError};
+use crate::render::{Group, Renderer, Style, SvgRenderer};
+use crate::save::{Format, Metadata, SaveError, SaveOptions};
+
+/// Which subplots share the range of an axis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Share {
+    /// Every subplot has its own range
+    #[default]
+    None,
+    /// All subplots share one range
+    All,
+    /// Subplots in the same row share a range
+    Row,
+    /// Subplots in the same column share a range
+    Col,
+}
+
+impl Share {
+    /// Whether the subplots in cells `a` and `b` share a range
+    fn links(self, a: &SubplotSpec, b: &SubplotSpec) -> bool {
+        match self {
+            Share::None => false,
+            Share::All => true,
+            Share::Row => a.row == b.row,
+            Share::Col => a.col == b.col,
+        }
+    }
+}
 
 /// Represents a figure that can contain multiple subplots
 #[derive(Debug)]
 pub struct Figure {
+    /// Size in pixels at [`BASE_DPI`](crate::raster::BASE_DPI)
     pub width: f64,
     pub height: f64,
+    /// Resolution PNG output is rendered at
     pub dpi: f64,
     pub background_color: Color,
+    /// Leave the figure and axes backgrounds unpainted
+    pub transparent: bool,
     pub subplots: Vec<Axes>,
+    /// Grid the subplots are placed in; a near-square grid fitting them if unset
+    pub gridspec: Option<GridSpec>,
+    /// Cells of the subplots, by index; subplots without one fill the grid row by row
+    pub subplot_specs: Vec<Option<SubplotSpec>>,
     pub tight_layout: bool,
+    /// Subplots sharing the x range; inner x tick labels are hidden when shared by column
+    pub sharex: Share,
+    /// Subplots sharing the y range; inner y tick labels are hidden when shared by row
+    pub sharey: Share,
 }
 
 impl Figure {
@@ -22,8 +69,13 @@ impl Figure {
             height: 900.0,
             dpi: 100.0,
             background_color: Color::WHITE,
+            transparent: false,
             subplots: Vec::new(),
+            gridspec: None,
+            subplot_specs: Vec::new(),
             tight_layout: true,
+            sharex: Share::None,
+            sharey: Share::None,
         }
     }
 
@@ -34,8 +86,13 @@ impl Figure {
             height,
             dpi: 100.0,
             background_color: Color::WHITE,
+            transparent: false,
             subplots: Vec::new(),
+            gridspec: None,
+            subplot_specs: Vec::new(),
             tight_layout: true,
+            sharex: Share::None,
+            sharey: Share::None,
         }
     }
 
@@ -58,10 +115,71 @@ impl Figure {
         self
     }
 
+    /// Leave the figure and axes backgrounds unpainted, e.g. to overlay PNGs on slides
+    pub fn set_transparent(&mut self, transparent: bool) -> &mut Self {
+        self.transparent = transparent;
+        self
+    }
+
+    /// Share the x range between subplots
+    pub fn sharex(&mut self, share: Share) -> &mut Self {
+        self.sharex = share;
+        self
+    }
+
+    /// Share the y range between subplots
+    pub fn sharey(&mut self, share: Share) -> &mut Self {
+        self.sharey = share;
+        self
+    }
+
     /// Add a subplot and return a mutable reference to it
     pub fn add_subplot(&mut self) -> &mut Axes {
         let axes = Axes::new();
         self.subplots.push(axes);
+        self.subplot_specs.push(None);
+        self.subplots.last_mut().unwrap()
+    }
+
+    /// Set the grid subplots are placed in, with its ratios and spacing
+    pub fn set_gridspec(&mut self, gridspec: GridSpec) -> &mut Self {
+        self.gridspec = Some(gridspec);
+        self
+    }
+
+    /// Replace the subplots with a `nrows` by `ncols` grid of axes
+    ///
+    /// The axes can be indexed by `(row, col)`, e.g. `axes[(1, 0)].plot(x, y)`.
+    /// Ratios and spacing of an earlier `set_gridspec` are kept if the shape matches.
+    pub fn subplots(&mut self, nrows: usize, ncols: usize) -> Subplots<'_> {
+        let gridspec = match self.gridspec.take() {
+            Some(g) if (g.nrows, g.ncols) == (nrows, ncols) => g,
+            _ => GridSpec::new(nrows, ncols),
+        };
+        let (nrows, ncols) = (gridspec.nrows, gridspec.ncols);
+        self.gridspec = Some(gridspec);
+        self.clear();
+        for row in 0..nrows {
+            for col in 0..ncols {
+                self.add_subplot_spec(SubplotSpec::new(row, col));
+            }
+        }
+        Subplots::new(&mut self.subplots, ncols)
+    }
+
+    /// Add a subplot in the grid cell at `row` and `col`
+    pub fn add_subplot_at(&mut self, row: usize, col: usize) -> &mut Axes {
+        self.add_subplot_spec(SubplotSpec::new(row, col))
+    }
+
+    /// Add a subplot covering the grid cells of `spec`, e.g. `SubplotSpec::new(0, 0).span(1, 3)`
+    ///
+    /// The grid grows to fit cells beyond it.
+    pub fn add_subplot_spec(&mut self, spec: SubplotSpec) -> &mut Axes {
+        // Subplots added directly to `subplots` have no cell
+        self.subplot_specs.resize(self.subplots.len(), None);
+        self.subplot_specs.push(Some(spec));
+        self.subplots.push(Axes::new());
         self.subplots.last_mut().unwrap()
     }
 
@@ -96,48 +214,289 @@ impl Figure {
 
     /// Generate SVG string for the entire figure
     pub fn to_svg(&self) -> String {
+        self.svg_document(self.transparent, None, &Metadata::default())
+    }
+
+    /// SVG of the figure, showing only `bbox` `(x, y, width, height)` if given
+    fn svg_document(
+        &self,
+        transparent: bool,
+        bbox: Option<(f64, f64, f64, f64)>,
+        metadata: &Metadata,
+    ) -> String {
         let mut svg = String::new();
+        let (x, y, width, height) = bbox.unwrap_or((0.0, 0.0, self.width, self.height));
 
         // SVG header
-        svg.push_str(&format!(
-            "<svg width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">\n",
-            self.width, self.height
-        ));
+        if bbox.is_some() {
+            svg.push_str(&format!(
+                "<svg width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\" xmlns=\"http://www.w3.org/2000/svg\">\n",
+                width, height, x, y, width, height
+            ));
+        } else {
+            svg.push_str(&format!(
+                "<svg width=\"{}\" height=\"{}\" xmlns=\"http://www.w3.org/2000/svg\">\n",
+                width, height
+            ));
+        }
+        svg.push_str(&metadata.to_svg());
 
-        // Background
-        svg.push_str(&format!(
-            "<rect width=\"{}\" height=\"{}\" fill=\"{}\" />\n",
-            self.width,
-            self.height,
-            self.background_color.to_svg_string()
-        ));
+        let mut renderer = SvgRenderer::new();
+        self.draw_with(&mut renderer, transparent, (x, y, width, height));
+        svg.push_str(&renderer.finish());
+        svg.push_str("</svg>");
+        svg
+    }
+
+    /// Draw the figure with its top left corner at the origin
+    pub fn draw(&self, renderer: &mut dyn Renderer) {
+        self.draw_with(
+            renderer,
+            self.transparent,
+            (0.0, 0.0, self.width, self.height),
+        );
+    }
+
+    /// Draw the figure, painting its background over the rectangle `(x, y, width, height)`
+    fn draw_with(
+        &self,
+        renderer: &mut dyn Renderer,
+        transparent: bool,
+        (x, y, width, height): (f64, f64, f64, f64),
+    ) {
+        if !transparent {
+            renderer.rect(x, y, width, height, &Style::filled(self.background_color));
+        }
+
+        // Render subplots in their grid cells
+        let (gridspec, specs) = self.layout();
+        let rects: Vec<_> = specs
+            .iter()
+            .map(|spec| gridspec.cell_rect(spec, self.width, self.height))
+            .collect();
+        let mut contexts = self.subplot_contexts(&specs);
+        if self.tight_layout {
+            self.fit_margins(&specs, &rects, &mut contexts);
+        }
+        for context in &mut contexts {
+            context.transparent = transparent;
+        }
+        for ((subplot, &(x, y, width, height)), context) in
+            self.subplots.iter().zip(&rects).zip(&contexts)
+        {
+            renderer.begin_group(&Group::translate(x, y));
+            subplot.draw_in_context(renderer, width, height, context);
+            renderer.end_group();
+        }
+    }
+
+    /// Render the figure to PNG bytes at `dpi`, scaling its size by `dpi / BASE_DPI`
+    pub fn to_png(&self, dpi: f64) -> Result<Vec<u8>, RasterError> {
+        raster::svg_to_png(
+            &self.to_svg(),
+            dpi / raster::BASE_DPI,
+            dpi,
+            &Metadata::default(),
+        )
+    }
+
+    /// Save the figure as a PNG file at the figure's [`dpi`](Figure::dpi)
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> Result<(), RasterError> {
+        std::fs::write(path, self.to_png(self.dpi)?)?;
+        Ok(())
+    }
 
-        // Render subplots
-        if self.subplots.len() == 1 {
-            // Single subplot takes the full figure
-            svg.push_str(&self.subplots[0].to_svg(self.width, self.height));
-        } else if !self.subplots.is_empty() {
-            // Multiple subplots - simple grid layout
-            let cols = (self.subplots.len() as f64).sqrt().ceil() as usize;
-            let rows = (self.subplots.len() + cols - 1) / cols;
-
-            let subplot_width = self.width / cols as f64;
-            let subplot_height = self.height / rows as f64;
-
-            for (i, subplot) in self.subplots.iter().enumerate() {
-                let col = i % cols;
-                let row = i / cols;
-                let x = col as f64 * subplot_width;
-                let y = row as f64 * subplot_height;
-
-                svg.push_str(&format!("<g transform=\"translate({},{})\">\n", x, y));
-                svg.push_str(&subplot.to_svg(subplot_width, subplot_height));
-                svg.push_str("</g>\n");
+    /// Write the figure as a one-page vector PDF with embedded fonts
+    ///
+    /// The page is the figure size at [`BASE_DPI`](crate::raster::BASE_DPI).
+    /// Use [`PdfPages`] for several figures in one document.
+    pub fn to_pdf(&self) -> Result<Vec<u8>, PdfError> {
+        let mut pages = PdfPages::new();
+        pages.add_figure(self)?;
+        Ok(pages.to_bytes())
+    }
+
+    /// Save the figure as a PDF file
+    pub fn save_pdf<P: AsRef<Path>>(&self, path: P) -> Result<(), PdfError> {
+        std::fs::write(path, self.to_pdf()?)?;
+        Ok(())
+    }
+
+    /// Save the figure in the format given by the extension of `path`
+    ///
+    /// `.svg`, `.png` and `.pdf` are supported; PNG is rendered at the figure's
+    /// [`dpi`](Figure::dpi).
+    pub fn savefig<P: AsRef<Path>>(&self, path: P) -> Result<(), SaveError> {
+        self.savefig_with(path, &SaveOptions::new())
+    }
+
+    /// Save the figure with explicit format, resolution, cropping, background or metadata
+    pub fn savefig_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        options: &SaveOptions,
+    ) -> Result<(), SaveError> {
+        let format = match options.format {
+            Some(format) => format,
+            None => Format::from_path(&path)?,
+        };
+        std::fs::write(path, self.to_format(format, options)?)?;
+        Ok(())
+    }
+
+    /// The bytes of the figure saved as `format`
+    pub fn to_format(&self, format: Format, options: &SaveOptions) -> Result<Vec<u8>, SaveError> {
+        let transparent = options.transparent.unwrap_or(self.transparent);
+        let bbox = if options.bbox_tight {
+            self.tight_bbox(options.pad)?
+        } else {
+            None
+        };
+        let svg = self.svg_document(transparent, bbox, &options.metadata);
+        Ok(match format {
+            Format::Svg => svg.into_bytes(),
+            Format::Png => {
+                let dpi = options.dpi.unwrap_or(self.dpi);
+                raster::svg_to_png(&svg, dpi / raster::BASE_DPI, dpi, &options.metadata)?
+            }
+            Format::Pdf => {
+                let mut pages = PdfPages::new();
+                pages.set_metadata(options.metadata.clone()).add_svg(&svg)?;
+                pages.to_bytes()
+            }
+        })
+    }
+
+    /// Bounds of the drawn content with `pad` around it, or `None` if nothing is drawn
+    fn tight_bbox(&self, pad: f64) -> Result<Option<(f64, f64, f64, f64)>, RasterError> {
+        let svg = self.svg_document(true, None, &Metadata::default());
+        Ok(raster::content_bounds(&svg)?
+            .map(|(x, y, width, height)| (x - pad, y - pad, width + 2.0 * pad, height + 2.0 * pad)))
+    }
+
+    /// The grid and the cell of each subplot
+    fn layout(&self) -> (GridSpec, Vec<SubplotSpec>) {
+        let specs: Vec<Option<SubplotSpec>> = (0..self.subplots.len())
+            .map(|i| self.subplot_specs.get(i).copied().flatten())
+            .collect();
+        let mut gridspec = match self.gridspec {
+            Some(ref gridspec) => gridspec.clone(),
+            None if specs.iter().all(Option::is_none) => {
+                // A near-square grid fitting all subplots
+                let cols = (specs.len() as f64).sqrt().ceil().max(1.0) as usize;
+                GridSpec::new(specs.len().div_ceil(cols), cols)
             }
+            None => GridSpec::new(1, 1),
+        };
+
+        // Grow the grid to fit the given cells, then fill the free ones row by row with the others
+        for spec in specs.iter().flatten() {
+            gridspec.ncols = gridspec.ncols.max(spec.cols().end);
         }
+        let ncols = gridspec.ncols;
+        let mut taken: Vec<SubplotSpec> = specs.iter().flatten().copied().collect();
+        let mut next = 0;
+        let specs: Vec<SubplotSpec> = specs
+            .iter()
+            .map(|spec| {
+                spec.unwrap_or_else(|| {
+                    let is_taken = |cell: usize| {
+                        taken.iter().any(|spec| {
+                            spec.rows().contains(&(cell / ncols))
+                                && spec.cols().contains(&(cell % ncols))
+                        })
+                    };
+                    while is_taken(next) {
+                        next += 1;
+                    }
+                    let spec = SubplotSpec::new(next / ncols, next % ncols);
+                    taken.push(spec);
+                    spec
+                })
+            })
+            .collect();
+        for spec in &specs {
+            gridspec.nrows = gridspec.nrows.max(spec.rows().end);
+        }
+        (gridspec, specs)
+    }
 
-        svg.push_str("</svg>");
-        svg
+    /// Shared ranges and hidden inner tick labels of the subplots in the cells `specs`
+    fn subplot_contexts(&self, specs: &[SubplotSpec]) -> Vec<SubplotContext> {
+        let (x_ranges, y_ranges): (Vec<_>, Vec<_>) = self
+            .subplots
+            .iter()
+            .map(|subplot| subplot.calculate_data_ranges())
+            .unzip();
+
+        // Union of the ranges of the subplots with data that `share` links to `spec`
+        let shared = |share: Share, spec, ranges: &[(f64, f64)]| {
+            (0..specs.len())
+                .filter(|&i| share.links(spec, &specs[i]) && !self.subplots[i].plots.is_empty())
+                .map(|i| ranges[i])
+                .reduce(axes::union)
+        };
+        let overlap = |a: Range<usize>, b: Range<usize>| a.start < b.end && b.start < a.end;
+
+        specs
+            .iter()
+            .map(|spec| SubplotContext {
+                x_limits: shared(self.sharex, spec, &x_ranges),
+                y_limits: shared(self.sharey, spec, &y_ranges),
+                // Only the bottom subplot of a column and the leftmost of a row keep their labels
+                hide_x_ticklabels: matches!(self.sharex, Share::All | Share::Col)
+                    && specs.iter().any(|other| {
+                        overlap(other.cols(), spec.cols()) && other.row >= spec.rows().end
+                    }),
+                hide_y_ticklabels: matches!(self.sharey, Share::All | Share::Row)
+                    && specs.iter().any(|other| {
+                        overlap(other.rows(), spec.rows()) && other.cols().end <= spec.col
+                    }),
+                margins: None,
+                transparent: false,
+            })
+            .collect()
+    }
+
+    /// Give each subplot margins that fit its text in its cell `rects`
+    ///
+    /// Subplots starting or ending on the same grid line get the same margin
+    /// there, so that their plotting areas line up.
+    fn fit_margins(
+        &self,
+        specs: &[SubplotSpec],
+        rects: &[(f64, f64, f64, f64)],
+        contexts: &mut [SubplotContext],
+    ) {
+        let margins: Vec<Margins> = self
+            .subplots
+            .iter()
+            .zip(rects)
+            .zip(contexts.iter())
+            .map(|((subplot, &(_, _, width, height)), context)| {
+                subplot.tight_margins(width, height, context)
+            })
+            .collect();
+
+        for (i, context) in contexts.iter_mut().enumerate() {
+            let spec = &specs[i];
+            let mut aligned = margins[i];
+            for (other, m) in specs.iter().zip(&margins) {
+                if other.col == spec.col {
+                    aligned.left = aligned.left.max(m.left);
+                }
+                if other.cols().end == spec.cols().end {
+                    aligned.right = aligned.right.max(m.right);
+                }
+                if other.row == spec.row {
+                    aligned.top = aligned.top.max(m.top);
+                }
+                if other.rows().end == spec.rows().end {
+                    aligned.bottom = aligned.bottom.max(m.bottom);
+                }
+            }
+            context.margins = Some(aligned);
+        }
     }
 
     /// Display the figure (prints SVG to stdout for now)
@@ -149,9 +508,12 @@ impl Figure {
     /// Clear all subplots
     pub fn clear(&mut self) {
         self.subplots.clear();
+        self.subplot_specs.clear();
     }
 
-    /// Set tight layout
+    /// Fit the margins of the subplots to their tick labels, axis labels and titles
+    ///
+    /// Without it every subplot keeps a fixed margin around its plotting area.
     pub fn tight_layout(&mut self, enable: bool) -> &mut Self {
         self.tight_layout = enable;
         self
@@ -280,3 +642,103 @@ impl Default for Figure {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plot::Plot;
+
+    #[test]
+    fn test_shared_axes() {
+        let mut fig = Figure::with_size(800.0, 800.0);
+        for (n, scale) in [(5.0, 1.0), (10.0, 100.0), (20.0, 1.0), (40.0, 2.0)] {
+            fig.add_subplot()
+                .add_plot(Plot::line(vec![0.0, n], vec![0.0, scale]));
+        }
+        fig.sharex(Share::All).sharey(Share::Row);
+
+        let (_, specs) = fig.layout();
+        let contexts = fig.subplot_contexts(&specs);
+        let x_limits = contexts[0].x_limits.unwrap();
+        assert!(x_limits.0 <= 0.0 && x_limits.1 >= 40.0);
+        assert!(contexts.iter().all(|c| c.x_limits == Some(x_limits)));
+        assert_eq!(contexts[0].y_limits, contexts[1].y_limits);
+        assert_ne!(contexts[1].y_limits, contexts[2].y_limits);
+
+        // Only the bottom row shows x tick labels and the left column y tick labels
+        let hidden: Vec<_> = contexts
+            .iter()
+            .map(|c| (c.hide_x_ticklabels, c.hide_y_ticklabels))
+            .collect();
+        assert_eq!(
+            hidden,
+            vec![(true, false), (true, true), (false, false), (false, true)]
+        );
+    }
+
+    #[test]
+    fn test_auto_placement_skips_spans() {
+        let mut fig = Figure::new();
+        fig.set_gridspec(GridSpec::new(2, 3));
+        fig.add_subplot_spec(SubplotSpec::new(0, 0).span(1, 3));
+        for _ in 0..3 {
+            fig.add_subplot();
+        }
+        fig.add_subplot_at(1, 1);
+        fig.add_subplot();
+
+        let (gridspec, specs) = fig.layout();
+        let cells: Vec<_> = specs.iter().map(|spec| (spec.row, spec.col)).collect();
+        assert_eq!(cells, [(0, 0), (1, 0), (1, 2), (2, 0), (1, 1), (2, 1)]);
+        assert_eq!((gridspec.nrows, gridspec.ncols), (3, 3));
+    }
+
+    #[test]
+    fn test_savefig_options() {
+        let mut fig = Figure::with_size(400.0, 300.0);
+        fig.add_subplot()
+            .add_plot(Plot::line(vec![0.0, 1.0], vec![0.0, 1.0]));
+        fig.tight_layout(false);
+
+        let options = SaveOptions::new()
+            .bbox_tight(0.0)
+            .transparent(true)
+            .metadata(Metadata::new().title("A & B"));
+        let svg = String::from_utf8(fig.to_format(Format::Svg, &options).unwrap()).unwrap();
+        assert!(svg.contains("<title>A &amp; B</title>"));
+        // Cropped to the axes and their labels inside the default margins
+        let width: f64 = svg["<svg width=\"".len()..]
+            .split('"')
+            .next()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(width > 280.0 && width < 380.0);
+        assert!(!svg.contains("fill=\"rgb(255,255,255)\""));
+
+        assert!(matches!(
+            fig.savefig("figure.bmp"),
+            Err(SaveError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_to_png() {
+        let mut fig = Figure::with_size(200.0, 100.0);
+        fig.add_subplot()
+            .add_plot(Plot::line(vec![0.0, 1.0], vec![0.0, 1.0]))
+            .set_title("title");
+        fig.set_transparent(true);
+
+        let png = fig.to_png(200.0).unwrap();
+        let mut reader = png::Decoder::new(png.as_slice()).read_info().unwrap();
+        let info = reader.info();
+        assert_eq!((info.width, info.height), (400, 200));
+        assert_eq!(info.pixel_dims.unwrap().xppu, 7874);
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        // The corner is unpainted, while some pixels are drawn
+        assert_eq!(pixels[3], 0);
+        assert!(pixels.chunks(4).any(|pixel| pixel[3] == 255));
+    }
+}
@@ -0,0 +1,220 @@
+//! Grid layout of subplots in a figure
+
+use std::ops::{Index, IndexMut, Range};
+
+use crate::axes::Axes;
+
+/// A grid of rows and columns that subplots are placed in
+///
+/// Columns share the figure width in proportion to `width_ratios` and rows
+/// share the height in proportion to `height_ratios`. `wspace` and `hspace`
+/// add gaps between columns and rows as a fraction of the average column
+/// width and row height.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridSpec {
+    pub nrows: usize,
+    pub ncols: usize,
+    /// Relative column widths; equal if empty
+    pub width_ratios: Vec<f64>,
+    /// Relative row heights; equal if empty
+    pub height_ratios: Vec<f64>,
+    pub wspace: f64,
+    pub hspace: f64,
+}
+
+impl GridSpec {
+    /// A grid of equally sized cells without gaps
+    pub fn new(nrows: usize, ncols: usize) -> Self {
+        GridSpec {
+            nrows: nrows.max(1),
+            ncols: ncols.max(1),
+            width_ratios: Vec::new(),
+            height_ratios: Vec::new(),
+            wspace: 0.0,
+            hspace: 0.0,
+        }
+    }
+
+    /// Set the relative widths of the columns
+    pub fn width_ratios(mut self, ratios: Vec<f64>) -> Self {
+        self.width_ratios = ratios;
+        self
+    }
+
+    /// Set the relative heights of the rows
+    pub fn height_ratios(mut self, ratios: Vec<f64>) -> Self {
+        self.height_ratios = ratios;
+        self
+    }
+
+    /// Set the gap between columns as a fraction of the average column width
+    pub fn wspace(mut self, wspace: f64) -> Self {
+        self.wspace = wspace.max(0.0);
+        self
+    }
+
+    /// Set the gap between rows as a fraction of the average row height
+    pub fn hspace(mut self, hspace: f64) -> Self {
+        self.hspace = hspace.max(0.0);
+        self
+    }
+
+    /// Pixel rectangle `(x, y, width, height)` covered by `spec` in a `width` by `height` figure
+    pub fn cell_rect(&self, spec: &SubplotSpec, width: f64, height: f64) -> (f64, f64, f64, f64) {
+        let (x, w) = span(
+            &tracks(width, self.ncols, &self.width_ratios, self.wspace),
+            &spec.cols(),
+        );
+        let (y, h) = span(
+            &tracks(height, self.nrows, &self.height_ratios, self.hspace),
+            &spec.rows(),
+        );
+        (x, y, w, h)
+    }
+}
+
+/// Start and size of `count` tracks sharing `length` by `ratios`, with gaps of `space` times the average
+fn tracks(length: f64, count: usize, ratios: &[f64], space: f64) -> Vec<(f64, f64)> {
+    let ratio = |i: usize| {
+        ratios
+            .get(i)
+            .copied()
+            .filter(|r| r.is_finite() && *r > 0.0)
+            .unwrap_or(1.0)
+    };
+    let total: f64 = (0..count).map(ratio).sum();
+    let average = length / (count as f64 + (count as f64 - 1.0) * space);
+    let gap = average * space;
+    let mut start = 0.0;
+    (0..count)
+        .map(|i| {
+            let size = average * count as f64 * ratio(i) / total;
+            let track = (start, size);
+            start += size + gap;
+            track
+        })
+        .collect()
+}
+
+/// Start and size of the tracks in `range`, including the gaps between them
+fn span(tracks: &[(f64, f64)], range: &Range<usize>) -> (f64, f64) {
+    let last = tracks.len() - 1;
+    let first = tracks[range.start.min(last)];
+    let end = tracks[(range.end.max(range.start + 1) - 1).min(last)];
+    (first.0, end.0 + end.1 - first.0)
+}
+
+/// The cells of a grid covered by one subplot
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubplotSpec {
+    pub row: usize,
+    pub col: usize,
+    pub rowspan: usize,
+    pub colspan: usize,
+}
+
+impl SubplotSpec {
+    /// The single cell at `row` and `col`
+    pub fn new(row: usize, col: usize) -> Self {
+        SubplotSpec {
+            row,
+            col,
+            rowspan: 1,
+            colspan: 1,
+        }
+    }
+
+    /// Extend over `rowspan` rows and `colspan` columns
+    pub fn span(mut self, rowspan: usize, colspan: usize) -> Self {
+        self.rowspan = rowspan.max(1);
+        self.colspan = colspan.max(1);
+        self
+    }
+
+    /// Rows covered
+    pub fn rows(&self) -> Range<usize> {
+        self.row..self.row + self.rowspan
+    }
+
+    /// Columns covered
+    pub fn cols(&self) -> Range<usize> {
+        self.col..self.col + self.colspan
+    }
+}
+
+/// Axes created together by `Figure::subplots`, indexed by `(row, col)` or position
+#[derive(Debug)]
+pub struct Subplots<'a> {
+    axes: &'a mut [Axes],
+    ncols: usize,
+}
+
+impl<'a> Subplots<'a> {
+    pub(crate) fn new(axes: &'a mut [Axes], ncols: usize) -> Self {
+        Subplots { axes, ncols }
+    }
+
+    pub fn len(&self) -> usize {
+        self.axes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.is_empty()
+    }
+
+    /// Iterate over the axes row by row
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Axes> {
+        self.axes.iter_mut()
+    }
+}
+
+impl Index<usize> for Subplots<'_> {
+    type Output = Axes;
+
+    fn index(&self, index: usize) -> &Axes {
+        &self.axes[index]
+    }
+}
+
+impl IndexMut<usize> for Subplots<'_> {
+    fn index_mut(&mut self, index: usize) -> &mut Axes {
+        &mut self.axes[index]
+    }
+}
+
+impl Index<(usize, usize)> for Subplots<'_> {
+    type Output = Axes;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Axes {
+        assert!(col < self.ncols, "column {} out of range", col);
+        &self.axes[row * self.ncols + col]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Subplots<'_> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Axes {
+        assert!(col < self.ncols, "column {} out of range", col);
+        &mut self.axes[row * self.ncols + col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_rects() {
+        let grid = GridSpec::new(2, 3)
+            .height_ratios(vec![2.0, 1.0])
+            .wspace(0.5);
+        // Columns are 200 wide with 100 between them
+        assert_eq!(
+            grid.cell_rect(&SubplotSpec::new(0, 0).span(1, 3), 800.0, 600.0),
+            (0.0, 0.0, 800.0, 400.0)
+        );
+        assert_eq!(
+            grid.cell_rect(&SubplotSpec::new(1, 1), 800.0, 600.0),
+            (300.0, 400.0, 200.0, 200.0)
+        );
+    }
+}
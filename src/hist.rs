@@ -0,0 +1,300 @@
+//! Histogram binning and histogram plots
+
+use crate::plot::{BarAlign, Plot};
+use crate::stats::{percentile, sorted_finite, std_dev};
+
+/// Automatic rules for choosing the number of bins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinRule {
+    /// `ceil(log2(n)) + 1` bins, good for small normal-ish samples
+    Sturges,
+    /// Bin width `3.49 * std * n^(-1/3)`
+    Scott,
+    /// Bin width `2 * IQR * n^(-1/3)`, robust against outliers
+    FreedmanDiaconis,
+    /// The smaller bin width of Sturges and Freedman-Diaconis (numpy's "auto")
+    Auto,
+}
+
+/// How the bins of a histogram are chosen
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bins {
+    /// A fixed number of equal-width bins
+    Count(usize),
+    /// Explicit, monotonically increasing bin edges
+    Edges(Vec<f64>),
+    /// Equal-width bins chosen by an automatic rule
+    Rule(BinRule),
+}
+
+/// How the histogram is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistType {
+    /// One bar per bin
+    Bar,
+    /// Unfilled step outline
+    Step,
+    /// Filled step outline
+    StepFilled,
+}
+
+/// Options controlling histogram computation and drawing
+#[derive(Debug, Clone)]
+pub struct HistOptions {
+    pub bins: Bins,
+    pub range: Option<(f64, f64)>,
+    pub density: bool,
+    pub cumulative: bool,
+    pub histtype: HistType,
+}
+
+impl HistOptions {
+    /// Create default options: 10 bins, raw counts, bar histtype
+    pub fn new() -> Self {
+        HistOptions {
+            bins: Bins::Count(10),
+            range: None,
+            density: false,
+            cumulative: false,
+            histtype: HistType::Bar,
+        }
+    }
+
+    /// Set the binning strategy
+    pub fn bins(mut self, bins: Bins) -> Self {
+        self.bins = bins;
+        self
+    }
+
+    /// Only count values within `(min, max)`
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Normalize counts so that the histogram integrates to 1
+    pub fn density(mut self, enable: bool) -> Self {
+        self.density = enable;
+        self
+    }
+
+    /// Accumulate counts from left to right
+    pub fn cumulative(mut self, enable: bool) -> Self {
+        self.cumulative = enable;
+        self
+    }
+
+    /// Set how the histogram is drawn
+    pub fn histtype(mut self, histtype: HistType) -> Self {
+        self.histtype = histtype;
+        self
+    }
+}
+
+impl Default for HistOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Computed histogram: `counts[i]` covers `[edges[i], edges[i + 1])`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub counts: Vec<f64>,
+    pub edges: Vec<f64>,
+}
+
+impl Histogram {
+    /// Bin the finite values of `data` according to `options`
+    ///
+    /// The last bin is closed on both sides so that the maximum value is counted.
+    pub fn compute(data: &[f64], options: &HistOptions) -> Self {
+        let sorted = sorted_finite(data);
+        let (min, max) = options
+            .range
+            .unwrap_or_else(|| match (sorted.first(), sorted.last()) {
+                (Some(&min), Some(&max)) if min < max => (min, max),
+                (Some(&v), Some(_)) => (v - 0.5, v + 0.5),
+                _ => (0.0, 1.0),
+            });
+
+        let edges = match &options.bins {
+            Bins::Edges(edges) => edges.clone(),
+            Bins::Count(count) => equal_edges(min, max, (*count).max(1)),
+            Bins::Rule(rule) => {
+                let in_range: Vec<f64> = sorted
+                    .iter()
+                    .copied()
+                    .filter(|&v| v >= min && v <= max)
+                    .collect();
+                equal_edges(min, max, bin_count(&in_range, *rule, max - min))
+            }
+        };
+
+        let bin_count = edges.len().saturating_sub(1);
+        let mut counts = vec![0.0; bin_count];
+        if bin_count > 0 {
+            let last = edges[bin_count];
+            for &value in &sorted {
+                if value < edges[0] || value > last {
+                    continue;
+                }
+                // First edge strictly greater than the value, clamped into the last bin
+                let index = edges.partition_point(|&e| e <= value).saturating_sub(1);
+                counts[index.min(bin_count - 1)] += 1.0;
+            }
+        }
+
+        if options.density {
+            let total: f64 = counts.iter().sum();
+            if total > 0.0 {
+                for (i, count) in counts.iter_mut().enumerate() {
+                    *count /= total * (edges[i + 1] - edges[i]);
+                }
+            }
+        }
+
+        if options.cumulative {
+            let mut acc = 0.0;
+            for (i, count) in counts.iter_mut().enumerate() {
+                // Densities accumulate as areas so the last bin reaches 1
+                acc += if options.density {
+                    *count * (edges[i + 1] - edges[i])
+                } else {
+                    *count
+                };
+                *count = acc;
+            }
+        }
+
+        Histogram { counts, edges }
+    }
+
+    /// Build a plot that draws this histogram
+    pub fn to_plot(&self, histtype: HistType) -> Plot {
+        let n = self.counts.len();
+        match histtype {
+            HistType::Bar => {
+                let widths: Vec<f64> = self.edges.windows(2).map(|w| w[1] - w[0]).collect();
+                Plot::bar(self.edges[..n].to_vec(), self.counts.clone())
+                    .bar_widths(widths)
+                    .align(BarAlign::Edge)
+            }
+            HistType::Step | HistType::StepFilled => {
+                let (x, y) = self.step_outline();
+                if histtype == HistType::Step {
+                    Plot::line(x, y)
+                } else {
+                    Plot::fill(x, y)
+                }
+            }
+        }
+    }
+
+    /// Outline of the histogram, starting and ending on the zero baseline
+    fn step_outline(&self) -> (Vec<f64>, Vec<f64>) {
+        let mut x = Vec::with_capacity(2 * self.counts.len() + 2);
+        let mut y = Vec::with_capacity(2 * self.counts.len() + 2);
+
+        if let Some(&first) = self.edges.first() {
+            x.push(first);
+            y.push(0.0);
+        }
+        for (i, &count) in self.counts.iter().enumerate() {
+            x.extend([self.edges[i], self.edges[i + 1]]);
+            y.extend([count, count]);
+        }
+        if let Some(&last) = self.edges.last() {
+            x.push(last);
+            y.push(0.0);
+        }
+
+        (x, y)
+    }
+}
+
+fn equal_edges(min: f64, max: f64, count: usize) -> Vec<f64> {
+    let step = (max - min) / count as f64;
+    (0..=count).map(|i| min + step * i as f64).collect()
+}
+
+/// Number of bins an automatic rule picks for sorted data spanning `span`
+///
+/// Width-based rules fall back to Sturges when they would need more bins
+/// than there are samples, as a tiny spread next to a far outlier does.
+fn bin_count(sorted: &[f64], rule: BinRule, span: f64) -> usize {
+    let n = sorted.len();
+    if n < 2 || span <= 0.0 {
+        return 1;
+    }
+
+    let sturges = (n as f64).log2().ceil() as usize + 1;
+    let from_width = |width: f64| {
+        let count = (span / width).ceil();
+        (width > 0.0 && count <= n as f64).then(|| (count as usize).max(1))
+    };
+    let nf = n as f64;
+    let scott = || from_width(3.49 * std_dev(sorted) * nf.powf(-1.0 / 3.0));
+    let fd = || {
+        let iqr = percentile(sorted, 75.0) - percentile(sorted, 25.0);
+        from_width(2.0 * iqr * nf.powf(-1.0 / 3.0))
+    };
+
+    match rule {
+        BinRule::Sturges => sturges,
+        BinRule::Scott => scott().unwrap_or(sturges),
+        BinRule::FreedmanDiaconis => fd().unwrap_or(sturges),
+        BinRule::Auto => fd().map_or(sturges, |fd| fd.max(sturges)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram_counts() {
+        let data = vec![0.0, 1.0, 1.5, 2.0, 3.0, 4.0];
+        let hist = Histogram::compute(&data, &HistOptions::new().bins(Bins::Count(4)));
+        assert_eq!(hist.edges, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(hist.counts, vec![1.0, 2.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_histogram_density_cumulative() {
+        let data = vec![0.0, 1.0, 1.5, 2.0, 3.0, 4.0];
+        let options = HistOptions::new()
+            .bins(Bins::Edges(vec![0.0, 2.0, 4.0]))
+            .density(true);
+        let hist = Histogram::compute(&data, &options);
+        assert_eq!(hist.counts, vec![0.25, 0.25]);
+
+        let hist = Histogram::compute(&data, &options.cumulative(true));
+        assert_eq!(hist.counts, vec![0.5, 1.0]);
+    }
+
+    #[test]
+    fn test_bin_rules() {
+        let data: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let options = HistOptions::new().bins(Bins::Rule(BinRule::Sturges));
+        assert_eq!(Histogram::compute(&data, &options).counts.len(), 8);
+
+        let options = HistOptions::new().bins(Bins::Rule(BinRule::FreedmanDiaconis));
+        // IQR = 49.5, width = 2 * 49.5 / 100^(1/3) ~= 21.3
+        assert_eq!(Histogram::compute(&data, &options).counts.len(), 5);
+    }
+
+    #[test]
+    fn test_bin_rules_outlier() {
+        // Almost no spread plus one far outlier would need ~1e13 bins
+        let mut data = vec![1.0; 50];
+        data.extend((0..50).map(|i| 1.0 + i as f64 * 1e-9));
+        data.push(1e6);
+        for rule in [BinRule::Scott, BinRule::FreedmanDiaconis, BinRule::Auto] {
+            let options = HistOptions::new().bins(Bins::Rule(rule));
+            assert!(Histogram::compute(&data, &options).counts.len() <= data.len());
+        }
+        let options = HistOptions::new().bins(Bins::Rule(BinRule::FreedmanDiaconis));
+        assert_eq!(Histogram::compute(&data, &options).counts.len(), 8);
+    }
+}
@@ -0,0 +1,313 @@
+//! Heatmaps and images of 2D matrices
+
+use crate::colors::{Color, Colormap, Normalize};
+use crate::markers::Marker;
+use crate::plot::{Plot, PlotType};
+use crate::render::{Anchor, Renderer, Style, TextStyle};
+use crate::scale::{PlotArea, Scale};
+
+/// Which corner of the axes the first matrix row is placed at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Origin {
+    /// Row 0 at the top, like a matrix or an image (inverts the y axis)
+    Upper,
+    /// Row 0 at the bottom, like a function over a grid
+    Lower,
+}
+
+/// How values are resampled between cell centers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolation {
+    /// Every cell is a single flat color
+    Nearest,
+    /// Values are interpolated between cell centers
+    Bilinear,
+}
+
+/// Image specific settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageStyle {
+    pub colormap: Colormap,
+    /// Maps cell values to colormap positions; spans the data if unset
+    pub norm: Normalize,
+    /// `(left, right, bottom, top)` in data coordinates; cells are unit squares
+    /// centered on integer indices if unset
+    pub extent: Option<(f64, f64, f64, f64)>,
+    pub origin: Origin,
+    pub interpolation: Interpolation,
+    /// Number of decimals of the value written in each cell, if any
+    pub annotate: Option<usize>,
+    pub font_size: f64,
+}
+
+impl ImageStyle {
+    fn new() -> Self {
+        ImageStyle {
+            colormap: Colormap::viridis(),
+            norm: Normalize::linear(),
+            extent: None,
+            origin: Origin::Upper,
+            interpolation: Interpolation::Nearest,
+            annotate: None,
+            font_size: 12.0,
+        }
+    }
+}
+
+impl Plot {
+    /// Create a new image of the matrix `z` where `z[row][col]` is one cell
+    pub fn imshow(z: Vec<Vec<f64>>) -> Self {
+        Plot {
+            x_data: Vec::new(),
+            y_data: Vec::new(),
+            z_data: Some(z),
+            plot_type: PlotType::Image(ImageStyle::new()),
+            color: None,
+            marker: Marker::None,
+            marker_size: 0.0,
+            line_width: 0.0,
+            label: None,
+            alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    fn image_style_mut(&mut self) -> Option<&mut ImageStyle> {
+        match self.plot_type {
+            PlotType::Image(ref mut style) => Some(style),
+            _ => None,
+        }
+    }
+
+    /// Place the image at `(left, right, bottom, top)` in data coordinates
+    pub fn extent(mut self, left: f64, right: f64, bottom: f64, top: f64) -> Self {
+        if let Some(style) = self.image_style_mut() {
+            style.extent = Some((left, right, bottom, top));
+        }
+        self
+    }
+
+    /// Set which corner the first row is placed at
+    pub fn origin(mut self, origin: Origin) -> Self {
+        if let Some(style) = self.image_style_mut() {
+            style.origin = origin;
+        }
+        self
+    }
+
+    /// Set how values are resampled between cell centers
+    pub fn interpolation(mut self, interpolation: Interpolation) -> Self {
+        if let Some(style) = self.image_style_mut() {
+            style.interpolation = interpolation;
+        }
+        self
+    }
+
+    /// Write every cell's value with the given number of decimals
+    pub fn annotate(mut self, decimals: usize) -> Self {
+        if let Some(style) = self.image_style_mut() {
+            style.annotate = Some(decimals);
+        }
+        self
+    }
+
+    /// Number of rows and columns of the image matrix
+    fn image_shape(&self) -> (usize, usize) {
+        match self.z_data {
+            Some(ref z) => (z.len(), z.iter().map(|r| r.len()).min().unwrap_or(0)),
+            None => (0, 0),
+        }
+    }
+
+    /// The `(left, right, bottom, top)` rectangle covered by an image plot
+    ///
+    /// With the default extent and upper origin, `bottom` is greater than `top`.
+    pub fn image_extent(&self) -> Option<(f64, f64, f64, f64)> {
+        let PlotType::Image(ref style) = self.plot_type else {
+            return None;
+        };
+        if let Some(extent) = style.extent {
+            return Some(extent);
+        }
+
+        let (rows, cols) = self.image_shape();
+        let (first, last) = (-0.5, rows as f64 - 0.5);
+        Some(match style.origin {
+            Origin::Upper => (-0.5, cols as f64 - 0.5, last, first),
+            Origin::Lower => (-0.5, cols as f64 - 0.5, first, last),
+        })
+    }
+
+    pub(crate) fn draw_image(
+        &self,
+        renderer: &mut dyn Renderer,
+        style: &ImageStyle,
+        area: &PlotArea,
+    ) {
+        let (rows, cols) = self.image_shape();
+        let (Some(z), Some(extent), Some((colormap, norm))) =
+            (&self.z_data, self.image_extent(), self.color_mapping())
+        else {
+            return;
+        };
+        if rows == 0 || cols == 0 {
+            return;
+        }
+
+        let (left, right, bottom, top) = extent;
+        // Rows run from `top` to `bottom` for the upper origin, the other way for lower
+        let (row_start, row_end) = match style.origin {
+            Origin::Upper => (top, bottom),
+            Origin::Lower => (bottom, top),
+        };
+        let to_svg = |col: f64, row: f64| {
+            let x = left + (right - left) * col / cols as f64;
+            let y = row_start + (row_end - row_start) * row / rows as f64;
+            area.point(x, y)
+        };
+        let color_of = |value: f64| {
+            if value.is_finite() {
+                Some(colormap.at(norm.apply(value)))
+            } else {
+                None
+            }
+        };
+
+        // Colors of evenly sized cells covering the image, `None` where there is no value
+        let grid: Vec<Vec<Option<Color>>> = match style.interpolation {
+            Interpolation::Nearest => z
+                .iter()
+                .map(|row| {
+                    (0..cols)
+                        .map(|c| row.get(c).copied().and_then(color_of))
+                        .collect()
+                })
+                .collect(),
+            Interpolation::Bilinear => {
+                // Resample to roughly 100 sub-cells along each axis
+                let sub_cols = (100 / cols).max(1);
+                let sub_rows = (100 / rows).max(1);
+                let sample = |col: f64, row: f64| {
+                    // Cell centers sit at half-integer positions
+                    let fc = (col - 0.5).clamp(0.0, (cols - 1) as f64);
+                    let fr = (row - 0.5).clamp(0.0, (rows - 1) as f64);
+                    let (c0, r0) = (fc.floor() as usize, fr.floor() as usize);
+                    let (c1, r1) = ((c0 + 1).min(cols - 1), (r0 + 1).min(rows - 1));
+                    let (tc, tr) = (fc - c0 as f64, fr - r0 as f64);
+                    let top = z[r0][c0] + (z[r0][c1] - z[r0][c0]) * tc;
+                    let bottom = z[r1][c0] + (z[r1][c1] - z[r1][c0]) * tc;
+                    top + (bottom - top) * tr
+                };
+
+                let (dc, dr) = (1.0 / sub_cols as f64, 1.0 / sub_rows as f64);
+                (0..rows * sub_rows)
+                    .map(|i| {
+                        (0..cols * sub_cols)
+                            .map(|j| {
+                                let (c, r) = (j as f64 * dc, i as f64 * dr);
+                                color_of(sample(c + dc / 2.0, r + dr / 2.0))
+                            })
+                            .collect()
+                    })
+                    .collect()
+            }
+        };
+
+        let (grid_rows, grid_cols) = (grid.len(), grid[0].len());
+        let linear = matches!(area.x_scale, Scale::Linear) && matches!(area.y_scale, Scale::Linear);
+        if linear {
+            // Cells are evenly spaced on screen too, so the grid is drawn as one image
+            let (x0, y0) = to_svg(0.0, 0.0);
+            let (x1, y1) = to_svg(cols as f64, rows as f64);
+            let mut pixels: Vec<Vec<Color>> = grid
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|color| match color {
+                            Some(color) => color.multiply_alpha(self.alpha),
+                            None => Color::rgba(0, 0, 0, 0.0),
+                        })
+                        .collect()
+                })
+                .collect();
+            // Images run left to right and top to bottom, whichever way the axes do
+            if x1 < x0 {
+                pixels.iter_mut().for_each(|row| row.reverse());
+            }
+            if y1 < y0 {
+                pixels.reverse();
+            }
+            renderer.image(
+                x0.min(x1),
+                y0.min(y1),
+                (x1 - x0).abs(),
+                (y1 - y0).abs(),
+                &pixels,
+            );
+        } else {
+            let (dc, dr) = (
+                cols as f64 / grid_cols as f64,
+                rows as f64 / grid_rows as f64,
+            );
+            for (i, row) in grid.iter().enumerate() {
+                for (j, color) in row.iter().enumerate() {
+                    let Some(color) = *color else {
+                        continue;
+                    };
+                    let (c, r) = (j as f64 * dc, i as f64 * dr);
+                    let (x0, y0) = to_svg(c, r);
+                    let (x1, y1) = to_svg(c + dc, r + dr);
+                    // Slight overlap hides antialiasing seams between neighbouring cells
+                    renderer.rect(
+                        x0.min(x1),
+                        y0.min(y1),
+                        (x1 - x0).abs(),
+                        (y1 - y0).abs(),
+                        &Style::filled(color).stroke(color, 0.5).opacity(self.alpha),
+                    );
+                }
+            }
+        }
+
+        if let Some(decimals) = style.annotate {
+            for (r, row) in z.iter().enumerate() {
+                for (c, &value) in row.iter().take(cols).enumerate() {
+                    let Some(color) = color_of(value) else {
+                        continue;
+                    };
+                    // Dark text on light cells and vice versa
+                    let luminance =
+                        0.299 * color.r as f64 + 0.587 * color.g as f64 + 0.114 * color.b as f64;
+                    let text_color = if luminance > 128.0 {
+                        Color::BLACK
+                    } else {
+                        Color::WHITE
+                    };
+                    let (x, y) = to_svg(c as f64 + 0.5, r as f64 + 0.5);
+                    let text = TextStyle::new(style.font_size, text_color)
+                        .anchor(Anchor::Middle)
+                        .dy(0.35);
+                    renderer.text(x, y, &format!("{:.*}", decimals, value), &text);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_extent() {
+        let z = vec![vec![0.0, 1.0, 2.0], vec![3.0, 4.0, 5.0]];
+        let plot = Plot::imshow(z.clone());
+        assert_eq!(plot.image_extent(), Some((-0.5, 2.5, 1.5, -0.5)));
+        assert_eq!(plot.color_mapping().unwrap().1.limits(), (0.0, 5.0));
+
+        let plot = Plot::imshow(z).origin(Origin::Lower).value_range(-1.0, 1.0);
+        assert_eq!(plot.image_extent(), Some((-0.5, 2.5, -0.5, 1.5)));
+        assert_eq!(plot.color_mapping().unwrap().1.limits(), (-1.0, 1.0));
+    }
+}
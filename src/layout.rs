@@ -0,0 +1,37 @@
+//! Space around the plotting area of axes
+
+/// Margin on every side of the plotting area when not laid out tightly
+pub const DEFAULT_MARGIN: f64 = 60.0;
+
+/// Space left between the outermost text and the edge of the axes
+pub(crate) const EDGE_PAD: f64 = 8.0;
+
+/// Space between tick labels and an axis label, or an axis label and the title
+pub(crate) const LABEL_GAP: f64 = 6.0;
+
+/// Space in pixels between the edges of axes and their plotting area
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Margins {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+}
+
+impl Margins {
+    /// The same margin on every side
+    pub fn uniform(margin: f64) -> Self {
+        Margins {
+            left: margin,
+            right: margin,
+            top: margin,
+            bottom: margin,
+        }
+    }
+}
+
+impl Default for Margins {
+    fn default() -> Self {
+        Margins::uniform(DEFAULT_MARGIN)
+    }
+}
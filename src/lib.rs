@@ -4,14 +4,34 @@
 //! similar to matplotlib in Python.
 
 pub mod axes;
+pub mod boxplot;
+pub mod category;
+pub mod colorbar;
 pub mod colors;
+pub mod contour;
+pub mod dates;
 // pub mod dot;
 pub mod figure;
+pub mod gridspec;
+pub mod hist;
+pub mod image;
+pub mod layout;
 pub mod markers;
+pub mod pdf;
+pub mod pie;
 pub mod plot;
 pub mod prelude;
+pub mod raster;
+pub mod render;
+pub mod save;
+pub mod scale;
+pub mod secondary;
+pub mod stats;
+pub mod text;
+pub mod ticker;
 pub mod utils;
 pub mod viewer;
+pub mod violin;
 
 pub use axes::Axes;
 pub use colors::Color;
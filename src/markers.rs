@@ -2,6 +2,9 @@
 
 use std::fmt;
 
+use crate::colors::Color;
+use crate::render::{PathData, Renderer, Style, SvgRenderer};
+
 /// Marker styles for plots
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Marker {
@@ -34,111 +37,116 @@ pub enum Marker {
 impl Marker {
     /// Get the SVG element for the marker
     pub fn to_svg_element(&self, x: f64, y: f64, size: f64, color: &str) -> String {
+        let mut svg = SvgRenderer::new();
+        self.draw(&mut svg, x, y, size, Color::from(color));
+        svg.finish()
+    }
+
+    /// Draw the marker centered at `(x, y)`, `size` pixels across
+    pub fn draw(&self, renderer: &mut dyn Renderer, x: f64, y: f64, size: f64, color: Color) {
         let half_size = size / 2.0;
+        let fill = Style::filled(color);
+        // Graphviz style shapes are black outlines with inner strokes
+        let outline = Style::stroked(Color::BLACK, 1.0);
         match self {
-            Marker::Circle => {
-                format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" />", x, y, half_size, color)
-            },
-            Marker::Square => {
-                format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" />", 
-                       x - half_size, y - half_size, size, size, color)
-            },
+            Marker::Circle => renderer.circle(x, y, half_size, &fill),
+            Marker::Square => renderer.rect(x - half_size, y - half_size, size, size, &fill),
             Marker::TriangleUp => {
                 let h = half_size * 0.866; // sqrt(3)/2
-                let points = format!("{},{} {},{} {},{}", 
-                                    x, y - h, x - half_size, y + h, x + half_size, y + h);
-                format!("<polygon points=\"{}\" fill=\"{}\" />", points, color)
-            },
+                let points = [(x, y - h), (x - half_size, y + h), (x + half_size, y + h)];
+                renderer.path(&PathData::polygon(&points), &fill);
+            }
             Marker::TriangleDown => {
                 let h = half_size * 0.866;
-                let points = format!("{},{} {},{} {},{}", 
-                                    x, y + h, x - half_size, y - h, x + half_size, y - h);
-                format!("<polygon points=\"{}\" fill=\"{}\" />", points, color)
-            },
+                let points = [(x, y + h), (x - half_size, y - h), (x + half_size, y - h)];
+                renderer.path(&PathData::polygon(&points), &fill);
+            }
             Marker::Diamond => {
-                let points = format!("{},{} {},{} {},{} {},{}", 
-                                    x, y - half_size, x + half_size, y, 
-                                    x, y + half_size, x - half_size, y);
-                format!("<polygon points=\"{}\" fill=\"{}\" />", points, color)
-            },
+                let points = [
+                    (x, y - half_size),
+                    (x + half_size, y),
+                    (x, y + half_size),
+                    (x - half_size, y),
+                ];
+                renderer.path(&PathData::polygon(&points), &fill);
+            }
             Marker::Plus => {
                 let thin = half_size * 0.2;
-                format!("<g fill=\"{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" /><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" /></g>",
-                       color,
-                       x - thin, y - half_size, thin * 2.0, size,
-                       x - half_size, y - thin, size, thin * 2.0)
-            },
+                renderer.rect(x - thin, y - half_size, thin * 2.0, size, &fill);
+                renderer.rect(x - half_size, y - thin, size, thin * 2.0, &fill);
+            }
             Marker::Cross => {
-                let thin = half_size * 0.2;
-                let offset = half_size * 0.707;
-                format!("<g fill=\"{}\" transform=\"translate({},{}) rotate(45)\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" /><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" /></g>",
-                       color, x, y,
-                       -thin, -offset, thin * 2.0, offset * 2.0,
-                       -offset, -thin, offset * 2.0, thin * 2.0)
-            },
-            Marker::Star => {
-                let mut points = Vec::new();
-                for i in 0..10 {
-                    let angle = (i as f64) * std::f64::consts::PI / 5.0;
-                    let radius = if i % 2 == 0 { half_size } else { half_size * 0.4 };
-                    let px = x + radius * angle.cos();
-                    let py = y + radius * angle.sin();
-                    points.push(format!("{},{}", px, py));
+                // A plus turned by 45 degrees
+                let thin = half_size * 0.2 * std::f64::consts::FRAC_1_SQRT_2;
+                let reach = half_size * 0.707 * std::f64::consts::FRAC_1_SQRT_2;
+                for (dx, dy) in [(1.0, 1.0), (1.0, -1.0)] {
+                    let points = [
+                        (x - reach * dx - thin * dy, y - reach * dy + thin * dx),
+                        (x - reach * dx + thin * dy, y - reach * dy - thin * dx),
+                        (x + reach * dx + thin * dy, y + reach * dy - thin * dx),
+                        (x + reach * dx - thin * dy, y + reach * dy + thin * dx),
+                    ];
+                    renderer.path(&PathData::polygon(&points), &fill);
                 }
-                format!("<polygon points=\"{}\" fill=\"{}\" />", points.join(" "), color)
-            },
+            }
+            Marker::Star => {
+                let points: Vec<(f64, f64)> = (0..10)
+                    .map(|i| {
+                        let angle = (i as f64) * std::f64::consts::PI / 5.0;
+                        let radius = if i % 2 == 0 { half_size } else { half_size * 0.4 };
+                        (x + radius * angle.cos(), y + radius * angle.sin())
+                    })
+                    .collect();
+                renderer.path(&PathData::polygon(&points), &fill);
+            }
             Marker::Mdiamond => {
                 // Modified diamond shape like graphviz Mdiamond with rectangular aspect ratio
                 let w = half_size * 2.6; // Much wider to match graphviz aspect ratio (~2.17:1)
                 let h = half_size * 1.2; // Keep height similar
                 // Main diamond outline
-                let points = format!("{},{} {},{} {},{} {},{} {},{}", 
-                                    x, y - h, x + w, y, x, y + h, x - w, y, x, y - h);
+                let points = [(x, y - h), (x + w, y), (x, y + h), (x - w, y)];
+                renderer.path(&PathData::polygon(&points), &outline);
                 // Internal polylines matching graphviz style
-                let polylines = format!(
-                    "<polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\" />\
-                     <polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\" />\
-                     <polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\" />\
-                     <polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\" />",
-                    x - w * 0.6, y - h * 0.5, x - w * 0.6, y,
-                    x - w * 0.2, y - h * 0.8, x + w * 0.2, y - h * 0.8,
-                    x + w * 0.6, y, x + w * 0.6, y + h * 0.5,
-                    x + w * 0.2, y + h * 0.8, x - w * 0.2, y + h * 0.8
-                );
-                format!("<g><polygon points=\"{}\" fill=\"none\" stroke=\"black\"/>{}</g>", points, polylines)
-            },
+                for segment in [
+                    [(x - w * 0.6, y - h * 0.5), (x - w * 0.6, y)],
+                    [(x - w * 0.2, y - h * 0.8), (x + w * 0.2, y - h * 0.8)],
+                    [(x + w * 0.6, y), (x + w * 0.6, y + h * 0.5)],
+                    [(x + w * 0.2, y + h * 0.8), (x - w * 0.2, y + h * 0.8)],
+                ] {
+                    renderer.path(&PathData::polyline(&segment), &outline);
+                }
+            }
             Marker::Msquare => {
                 // Modified square shape like graphviz Msquare (octagon)
                 let s = half_size;
                 let cut = s * 0.3;
-                let points = format!("{},{} {},{} {},{} {},{} {},{} {},{} {},{} {},{}",
-                                    x - s + cut, y - s,
-                                    x + s - cut, y - s,
-                                    x + s, y - s + cut,
-                                    x + s, y + s - cut,
-                                    x + s - cut, y + s,
-                                    x - s + cut, y + s,
-                                    x - s, y + s - cut,
-                                    x - s, y - s + cut);
-                let polylines = format!(
-                    "<polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\"/>\
-                     <polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\"/>\
-                     <polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\"/>\
-                     <polyline fill=\"none\" stroke=\"black\" points=\"{},{} {},{}\"/>",
-                    x - s + cut * 2.0, y - s, x - s, y - s + cut * 2.0,
-                    x - s, y - cut, x - s + cut, y,
-                    x + s - cut, y, x + s, y - cut,
-                    x + s, y + s - cut * 2.0, x + s - cut * 2.0, y + s
-                );
-                format!("<g><polygon points=\"{}\" fill=\"none\" stroke=\"black\"/>{}</g>", points, polylines)
-            },
+                let points = [
+                    (x - s + cut, y - s),
+                    (x + s - cut, y - s),
+                    (x + s, y - s + cut),
+                    (x + s, y + s - cut),
+                    (x + s - cut, y + s),
+                    (x - s + cut, y + s),
+                    (x - s, y + s - cut),
+                    (x - s, y - s + cut),
+                ];
+                renderer.path(&PathData::polygon(&points), &outline);
+                for segment in [
+                    [(x - s + cut * 2.0, y - s), (x - s, y - s + cut * 2.0)],
+                    [(x - s, y - cut), (x - s + cut, y)],
+                    [(x + s - cut, y), (x + s, y - cut)],
+                    [(x + s, y + s - cut * 2.0), (x + s - cut * 2.0, y + s)],
+                ] {
+                    renderer.path(&PathData::polyline(&segment), &outline);
+                }
+            }
             Marker::Ellipse => {
                 // Ellipse size matching graphviz standards (rx=27, ry=18 when half_size=7.5)
                 let rx = half_size * 3.6; // Horizontal radius to match graphviz rx=27
                 let ry = half_size * 2.4; // Vertical radius to match graphviz ry=18
-                format!("<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" fill=\"{}\" />", x, y, rx, ry, color)
-            },
-            Marker::None => String::new(),
+                renderer.path(&PathData::ellipse(x, y, rx, ry), &fill);
+            }
+            Marker::None => {}
         }
     }
 
@@ -0,0 +1,660 @@
+//! Vector PDF output with embedded fonts
+//!
+//! PDF is not a [`Renderer`](crate::render::Renderer) backend: each figure
+//! is written to SVG, parsed back with usvg and the resulting tree converted
+//! to PDF. Shapes stay vector paths and labels are written as glyphs of the
+//! embedded fonts, so text stays sharp, selectable and searchable.
+//! [`PdfPages`] collects several figures into one document, one page each.
+//!
+//! Fonts are embedded whole, not subset to the glyphs used, so every
+//! document carries the full font files, a few hundred kilobytes for the
+//! bundled fonts.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use pdf_writer::types::{
+    CidFontType, FontFlags, LineCapStyle, LineJoinStyle, SystemInfo, UnicodeCmap,
+};
+use pdf_writer::{Content, Filter, Finish, Name, Pdf, Rect, Ref, Str, TextStr};
+use resvg::usvg::{self, Node, Transform, fontdb, tiny_skia_path::PathSegment};
+
+use crate::figure::Figure;
+use crate::raster::{self, BASE_DPI};
+use crate::save::Metadata;
+
+/// Size of a figure pixel in PDF points
+const POINTS_PER_PIXEL: f32 = (72.0 / BASE_DPI) as f32;
+
+const IDENTITY: SystemInfo = SystemInfo {
+    registry: Str(b"Adobe"),
+    ordering: Str(b"Identity"),
+    supplement: 0,
+};
+
+/// Error writing a PDF
+#[derive(Debug)]
+pub enum PdfError {
+    /// The figure SVG could not be parsed
+    Svg(usvg::Error),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for PdfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PdfError::Svg(error) => write!(f, "cannot parse figure SVG: {}", error),
+            PdfError::Io(error) => write!(f, "cannot write PDF: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for PdfError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PdfError::Svg(error) => Some(error),
+            PdfError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<usvg::Error> for PdfError {
+    fn from(error: usvg::Error) -> Self {
+        PdfError::Svg(error)
+    }
+}
+
+impl From<std::io::Error> for PdfError {
+    fn from(error: std::io::Error) -> Self {
+        PdfError::Io(error)
+    }
+}
+
+/// A multi-page PDF document with one figure per page
+///
+/// Fonts are embedded once, in full, and shared by all pages; they are the
+/// fonts text was measured with when the document was created.
+pub struct PdfPages {
+    options: usvg::Options<'static>,
+    pages: Vec<usvg::Tree>,
+    metadata: Metadata,
+}
+
+impl PdfPages {
+    /// An empty document
+    pub fn new() -> Self {
+        PdfPages {
+            options: raster::svg_options(),
+            pages: Vec::new(),
+            metadata: Metadata::default(),
+        }
+    }
+
+    /// Add `figure` as the next page, sized like the figure
+    pub fn add_figure(&mut self, figure: &Figure) -> Result<&mut Self, PdfError> {
+        self.add_svg(&figure.to_svg())
+    }
+
+    pub(crate) fn add_svg(&mut self, svg: &str) -> Result<&mut Self, PdfError> {
+        self.pages.push(usvg::Tree::from_str(svg, &self.options)?);
+        Ok(self)
+    }
+
+    /// Set the title, author and other document information
+    pub fn set_metadata(&mut self, metadata: Metadata) -> &mut Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Number of pages
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Write the document
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::new(&self.options.fontdb);
+        let catalog = writer.alloc();
+        let tree = writer.alloc();
+        let pages: Vec<Ref> = self
+            .pages
+            .iter()
+            .map(|page| writer.page(page, tree))
+            .collect();
+        writer.pdf.catalog(catalog).pages(tree);
+        writer
+            .pdf
+            .pages(tree)
+            .kids(pages.iter().copied())
+            .count(pages.len() as i32);
+        writer.finish(&self.metadata)
+    }
+
+    /// Write the document to `path`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PdfError> {
+        std::fs::write(path, self.to_bytes())?;
+        Ok(())
+    }
+}
+
+impl Default for PdfPages {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A font used on some page and the glyphs drawn with it
+struct EmbeddedFont {
+    name: String,
+    id: Ref,
+    units_per_em: f32,
+    /// Text each glyph was drawn for, to let viewers copy it
+    glyphs: BTreeMap<u16, String>,
+}
+
+/// Writes pages, collecting the fonts, opacities and groups they use into shared resources
+struct Writer<'a> {
+    pdf: Pdf,
+    next: Ref,
+    fontdb: &'a fontdb::Database,
+    resources: Ref,
+    fonts: HashMap<fontdb::ID, EmbeddedFont>,
+    /// Graphics states by fill and stroke opacity bits
+    states: HashMap<(u32, u32), (String, Ref)>,
+    groups: Vec<(String, Ref)>,
+}
+
+impl<'a> Writer<'a> {
+    fn new(fontdb: &'a fontdb::Database) -> Self {
+        let mut next = Ref::new(1);
+        let resources = next.bump();
+        Writer {
+            pdf: Pdf::new(),
+            next,
+            fontdb,
+            resources,
+            fonts: HashMap::new(),
+            states: HashMap::new(),
+            groups: Vec::new(),
+        }
+    }
+
+    fn alloc(&mut self) -> Ref {
+        self.next.bump()
+    }
+
+    /// Write `tree` as a page of `parent`, with figure pixels scaled to points and y pointing down
+    fn page(&mut self, tree: &usvg::Tree, parent: Ref) -> Ref {
+        let (width, height) = (
+            tree.size().width() * POINTS_PER_PIXEL,
+            tree.size().height() * POINTS_PER_PIXEL,
+        );
+        let mut content = Content::new();
+        content.transform([POINTS_PER_PIXEL, 0.0, 0.0, -POINTS_PER_PIXEL, 0.0, height]);
+        self.group(&mut content, tree.root());
+
+        let (page, contents) = (self.alloc(), self.alloc());
+        self.pdf
+            .stream(contents, &deflate(&content.finish()))
+            .filter(Filter::FlateDecode);
+        let mut writer = self.pdf.page(page);
+        writer
+            .media_box(Rect::new(0.0, 0.0, width, height))
+            .parent(parent)
+            .contents(contents);
+        writer.pair(Name(b"Resources"), self.resources);
+        page
+    }
+
+    fn group(&mut self, content: &mut Content, group: &usvg::Group) {
+        content.save_state();
+        content.transform(matrix(group.transform()));
+        if let Some(clip) = group.clip_path() {
+            self.clip(content, clip);
+        }
+
+        let opacity = group.opacity().get();
+        if opacity < 1.0 {
+            // Children are composited together, then faded as one
+            let mut inner = Content::new();
+            self.children(&mut inner, group);
+            let bbox = group.layer_bounding_box();
+            let (name, id) = (format!("G{}", self.groups.len()), self.alloc());
+            let data = deflate(&inner.finish());
+            let mut form = self.pdf.form_xobject(id, &data);
+            form.bbox(Rect::new(
+                bbox.left(),
+                bbox.top(),
+                bbox.right(),
+                bbox.bottom(),
+            ));
+            form.filter(Filter::FlateDecode);
+            form.group().transparency().isolated(group.isolate());
+            form.pair(Name(b"Resources"), self.resources);
+            form.finish();
+
+            let state = self.opacity(opacity, opacity);
+            content.set_parameters(Name(state.as_bytes()));
+            content.x_object(Name(name.as_bytes()));
+            self.groups.push((name, id));
+        } else {
+            self.children(content, group);
+        }
+        content.restore_state();
+    }
+
+    fn children(&mut self, content: &mut Content, group: &usvg::Group) {
+        for child in group.children() {
+            match child {
+                Node::Group(group) => self.group(content, group),
+                Node::Path(path) => self.path(content, path),
+                Node::Text(text) => self.text(content, text),
+                // Figures don't embed images
+                Node::Image(_) => {}
+            }
+        }
+    }
+
+    /// Restrict drawing to the paths of `clip`, transformed into the current space
+    fn clip(&mut self, content: &mut Content, clip: &usvg::ClipPath) {
+        if let Some(inner) = clip.clip_path() {
+            self.clip(content, inner);
+        }
+        let mut paths = Vec::new();
+        clip_paths(clip.root(), clip.transform(), &mut paths);
+        for path in &paths {
+            draw_path(content, path);
+        }
+        content.clip_nonzero();
+        content.end_path();
+    }
+
+    fn path(&mut self, content: &mut Content, path: &usvg::Path) {
+        if !path.is_visible() {
+            return;
+        }
+        let fill = path.fill().and_then(|fill| {
+            let (r, g, b) = rgb(fill.paint())?;
+            Some((r, g, b, fill.opacity().get(), fill.rule()))
+        });
+        let stroke = path
+            .stroke()
+            .and_then(|stroke| Some((rgb(stroke.paint())?, stroke)));
+        if fill.is_none() && stroke.is_none() {
+            return;
+        }
+
+        content.save_state();
+        let fill_opacity = fill.map_or(1.0, |fill| fill.3);
+        let stroke_opacity = stroke.map_or(1.0, |(_, stroke)| stroke.opacity().get());
+        if fill_opacity < 1.0 || stroke_opacity < 1.0 {
+            let state = self.opacity(fill_opacity, stroke_opacity);
+            content.set_parameters(Name(state.as_bytes()));
+        }
+        if let Some((r, g, b, _, _)) = fill {
+            content.set_fill_rgb(r, g, b);
+        }
+        if let Some(((r, g, b), stroke)) = stroke {
+            content
+                .set_stroke_rgb(r, g, b)
+                .set_line_width(stroke.width().get())
+                .set_miter_limit(stroke.miterlimit().get())
+                .set_line_cap(match stroke.linecap() {
+                    usvg::LineCap::Butt => LineCapStyle::ButtCap,
+                    usvg::LineCap::Round => LineCapStyle::RoundCap,
+                    usvg::LineCap::Square => LineCapStyle::ProjectingSquareCap,
+                })
+                .set_line_join(match stroke.linejoin() {
+                    usvg::LineJoin::Miter | usvg::LineJoin::MiterClip => LineJoinStyle::MiterJoin,
+                    usvg::LineJoin::Round => LineJoinStyle::RoundJoin,
+                    usvg::LineJoin::Bevel => LineJoinStyle::BevelJoin,
+                });
+            if let Some(dashes) = stroke.dasharray() {
+                content.set_dash_pattern(dashes.iter().copied(), stroke.dashoffset());
+            }
+        }
+
+        // Fill before stroke unless asked otherwise, as one operation when possible
+        let even_odd = matches!(fill, Some((.., usvg::FillRule::EvenOdd)));
+        let fill_op = |content: &mut Content| {
+            draw_path(content, path.data());
+            if even_odd {
+                content.fill_even_odd();
+            } else {
+                content.fill_nonzero();
+            }
+        };
+        let stroke_op = |content: &mut Content| {
+            draw_path(content, path.data());
+            content.stroke();
+        };
+        match (fill.is_some(), stroke.is_some(), path.paint_order()) {
+            (true, true, usvg::PaintOrder::FillAndStroke) => {
+                draw_path(content, path.data());
+                if even_odd {
+                    content.fill_even_odd_and_stroke();
+                } else {
+                    content.fill_nonzero_and_stroke();
+                }
+            }
+            (true, true, usvg::PaintOrder::StrokeAndFill) => {
+                stroke_op(content);
+                fill_op(content);
+            }
+            (true, false, _) => fill_op(content),
+            _ => stroke_op(content),
+        }
+        content.restore_state();
+    }
+
+    /// Draw the glyphs of `text` with the embedded fonts
+    fn text(&mut self, content: &mut Content, text: &usvg::Text) {
+        for span in text.layouted() {
+            let Some(fill) = span.fill.as_ref().filter(|_| span.visible) else {
+                continue;
+            };
+            let Some((r, g, b)) = rgb(fill.paint()) else {
+                continue;
+            };
+
+            content.save_state();
+            if fill.opacity().get() < 1.0 {
+                let state = self.opacity(fill.opacity().get(), 1.0);
+                content.set_parameters(Name(state.as_bytes()));
+            }
+            content.set_fill_rgb(r, g, b);
+            content.begin_text();
+            for glyph in &span.positioned_glyphs {
+                let Some(font) = self.font(glyph.font) else {
+                    continue;
+                };
+                font.glyphs
+                    .entry(glyph.id.0)
+                    .or_insert_with(|| glyph.text.clone());
+                // Glyph outlines are y-up in ems; the page is y-down in pixels
+                let transform = glyph
+                    .transform()
+                    .pre_scale(font.units_per_em, -font.units_per_em);
+                content
+                    .set_font(Name(font.name.as_bytes()), 1.0)
+                    .set_text_matrix(matrix(transform))
+                    .show(Str(&glyph.id.0.to_be_bytes()));
+            }
+            content.end_text();
+            content.restore_state();
+        }
+    }
+
+    /// A font of the database, registered on first use
+    fn font(&mut self, font: fontdb::ID) -> Option<&mut EmbeddedFont> {
+        if !self.fonts.contains_key(&font) {
+            let units_per_em = self.fontdb.with_face_data(font, |data, index| {
+                ttf_parser::Face::parse(data, index)
+                    .ok()
+                    .map(|face| face.units_per_em() as f32)
+            })??;
+            let embedded = EmbeddedFont {
+                name: format!("F{}", self.fonts.len()),
+                id: self.alloc(),
+                units_per_em,
+                glyphs: BTreeMap::new(),
+            };
+            self.fonts.insert(font, embedded);
+        }
+        self.fonts.get_mut(&font)
+    }
+
+    /// Name of a graphics state with the given fill and stroke opacity
+    fn opacity(&mut self, fill: f32, stroke: f32) -> String {
+        let key = (fill.to_bits(), stroke.to_bits());
+        if let Some((name, _)) = self.states.get(&key) {
+            return name.clone();
+        }
+        let (name, id) = (format!("A{}", self.states.len()), self.alloc());
+        self.pdf
+            .ext_graphics(id)
+            .non_stroking_alpha(fill)
+            .stroking_alpha(stroke);
+        self.states.insert(key, (name.clone(), id));
+        name
+    }
+
+    /// Embed the fonts, write the shared resources and finish the document
+    fn finish(mut self, metadata: &Metadata) -> Vec<u8> {
+        let mut fonts: Vec<_> = std::mem::take(&mut self.fonts).into_iter().collect();
+        fonts.sort_by(|a, b| a.1.name.cmp(&b.1.name));
+        for (id, font) in &fonts {
+            self.embed_font(*id, font);
+        }
+
+        let mut resources = self.pdf.indirect(self.resources).dict();
+        let mut dict = resources.insert(Name(b"Font")).dict();
+        for (_, font) in &fonts {
+            dict.pair(Name(font.name.as_bytes()), font.id);
+        }
+        dict.finish();
+        let mut dict = resources.insert(Name(b"ExtGState")).dict();
+        for (name, id) in self.states.values() {
+            dict.pair(Name(name.as_bytes()), *id);
+        }
+        dict.finish();
+        let mut dict = resources.insert(Name(b"XObject")).dict();
+        for (name, id) in &self.groups {
+            dict.pair(Name(name.as_bytes()), *id);
+        }
+        dict.finish();
+        resources.finish();
+
+        let info = self.alloc();
+        let mut writer = self.pdf.document_info(info);
+        writer.producer(TextStr(concat!("plotiron ", env!("CARGO_PKG_VERSION"))));
+        for (key, value) in metadata.entries() {
+            writer.pair(Name(key.as_bytes()), TextStr(&value));
+        }
+        writer.finish();
+        self.pdf.finish()
+    }
+
+    /// Write `font` as a CID-keyed font addressed by glyph ID, with the whole font file embedded
+    fn embed_font(&mut self, id: fontdb::ID, font: &EmbeddedFont) {
+        let (cid_font, descriptor, font_file, to_unicode) =
+            (self.alloc(), self.alloc(), self.alloc(), self.alloc());
+        let pdf = &mut self.pdf;
+        self.fontdb.with_face_data(id, |data, index| {
+            let face = ttf_parser::Face::parse(data, index).expect("font was validated on load");
+            let scale = 1000.0 / face.units_per_em() as f32;
+            let base_font = face
+                .names()
+                .into_iter()
+                .find(|name| name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+                .and_then(|name| name.to_string())
+                .unwrap_or_else(|| font.name.clone());
+            let is_cff = face.tables().cff.is_some() || face.tables().cff2.is_some();
+
+            pdf.type0_font(font.id)
+                .base_font(Name(base_font.as_bytes()))
+                .encoding_predefined(Name(b"Identity-H"))
+                .descendant_font(cid_font)
+                .to_unicode(to_unicode);
+
+            let mut cid = pdf.cid_font(cid_font);
+            cid.subtype(if is_cff {
+                CidFontType::Type0
+            } else {
+                CidFontType::Type2
+            })
+            .base_font(Name(base_font.as_bytes()))
+            .system_info(IDENTITY)
+            .font_descriptor(descriptor);
+            if !is_cff {
+                cid.cid_to_gid_map_predefined(Name(b"Identity"));
+            }
+            let mut widths = cid.widths();
+            for &glyph in font.glyphs.keys() {
+                let advance = face
+                    .glyph_hor_advance(ttf_parser::GlyphId(glyph))
+                    .unwrap_or(0);
+                widths.consecutive(glyph, [advance as f32 * scale]);
+            }
+            widths.finish();
+            cid.finish();
+
+            let bbox = face.global_bounding_box();
+            let mut flags = FontFlags::SYMBOLIC;
+            flags.set(FontFlags::ITALIC, face.is_italic());
+            flags.set(FontFlags::FIXED_PITCH, face.is_monospaced());
+            let mut writer = pdf.font_descriptor(descriptor);
+            writer
+                .name(Name(base_font.as_bytes()))
+                .flags(flags)
+                .bbox(Rect::new(
+                    bbox.x_min as f32 * scale,
+                    bbox.y_min as f32 * scale,
+                    bbox.x_max as f32 * scale,
+                    bbox.y_max as f32 * scale,
+                ))
+                .italic_angle(face.italic_angle())
+                .ascent(face.ascender() as f32 * scale)
+                .descent(face.descender() as f32 * scale)
+                .cap_height(face.capital_height().unwrap_or(face.ascender()) as f32 * scale)
+                .stem_v(80.0);
+            if is_cff {
+                writer.font_file3(font_file);
+            } else {
+                writer.font_file2(font_file);
+            }
+            writer.finish();
+
+            let compressed = deflate(data);
+            let mut stream = pdf.stream(font_file, &compressed);
+            stream.filter(Filter::FlateDecode);
+            if is_cff {
+                stream.pair(Name(b"Subtype"), Name(b"OpenType"));
+            }
+            stream.finish();
+
+            let mut cmap = UnicodeCmap::new(Name(b"Custom"), IDENTITY);
+            for (&glyph, text) in &font.glyphs {
+                if !text.is_empty() {
+                    cmap.pair_with_multiple(glyph, text.chars());
+                }
+            }
+            let cmap = deflate(&cmap.finish());
+            pdf.stream(to_unicode, &cmap).filter(Filter::FlateDecode);
+        });
+    }
+}
+
+/// Collect the paths of a clip path in the space its transform is relative to
+fn clip_paths(
+    group: &usvg::Group,
+    transform: Transform,
+    paths: &mut Vec<usvg::tiny_skia_path::Path>,
+) {
+    let transform = transform.pre_concat(group.transform());
+    for child in group.children() {
+        match child {
+            Node::Group(group) => clip_paths(group, transform, paths),
+            Node::Path(path) => paths.extend(path.data().clone().transform(transform)),
+            Node::Text(text) => clip_paths(text.flattened(), transform, paths),
+            Node::Image(_) => {}
+        }
+    }
+}
+
+fn draw_path(content: &mut Content, path: &usvg::tiny_skia_path::Path) {
+    let (mut start, mut last) = ((0.0, 0.0), (0.0, 0.0));
+    for segment in path.segments() {
+        match segment {
+            PathSegment::MoveTo(p) => {
+                content.move_to(p.x, p.y);
+                (start, last) = ((p.x, p.y), (p.x, p.y));
+            }
+            PathSegment::LineTo(p) => {
+                content.line_to(p.x, p.y);
+                last = (p.x, p.y);
+            }
+            PathSegment::QuadTo(c, p) => {
+                // Raise to a cubic with the same curve
+                let (x1, y1) = (
+                    last.0 + 2.0 / 3.0 * (c.x - last.0),
+                    last.1 + 2.0 / 3.0 * (c.y - last.1),
+                );
+                let (x2, y2) = (p.x + 2.0 / 3.0 * (c.x - p.x), p.y + 2.0 / 3.0 * (c.y - p.y));
+                content.cubic_to(x1, y1, x2, y2, p.x, p.y);
+                last = (p.x, p.y);
+            }
+            PathSegment::CubicTo(c1, c2, p) => {
+                content.cubic_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y);
+                last = (p.x, p.y);
+            }
+            PathSegment::Close => {
+                content.close_path();
+                last = start;
+            }
+        }
+    }
+}
+
+/// RGB components of a paint, taking gradients as their first stop
+fn rgb(paint: &usvg::Paint) -> Option<(f32, f32, f32)> {
+    let color = match paint {
+        usvg::Paint::Color(color) => *color,
+        usvg::Paint::LinearGradient(gradient) => gradient.stops().first()?.color(),
+        usvg::Paint::RadialGradient(gradient) => gradient.stops().first()?.color(),
+        usvg::Paint::Pattern(_) => return None,
+    };
+    Some((
+        color.red as f32 / 255.0,
+        color.green as f32 / 255.0,
+        color.blue as f32 / 255.0,
+    ))
+}
+
+fn matrix(transform: Transform) -> [f32; 6] {
+    [
+        transform.sx,
+        transform.ky,
+        transform.kx,
+        transform.sy,
+        transform.tx,
+        transform.ty,
+    ]
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    miniz_oxide::deflate::compress_to_vec_zlib(data, 6)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plot::Plot;
+
+    #[test]
+    fn test_pages() {
+        let mut figure = Figure::with_size(200.0, 100.0);
+        figure
+            .add_subplot()
+            .add_plot(Plot::line(vec![0.0, 1.0], vec![0.0, 1.0]))
+            .set_title("title");
+        let mut pages = PdfPages::new();
+        pages
+            .add_figure(&figure)
+            .unwrap()
+            .add_figure(&figure)
+            .unwrap();
+        let pdf = pages.to_bytes();
+
+        let count = |needle: &[u8]| pdf.windows(needle.len()).filter(|w| *w == needle).count();
+        assert!(pdf.starts_with(b"%PDF"));
+        // Two 144x72 point pages sharing one embedded font
+        assert_eq!(count(b"/MediaBox [0 0 144 72]"), 2);
+        assert_eq!(count(b"/FontFile2"), 1);
+    }
+}
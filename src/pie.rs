@@ -0,0 +1,305 @@
+//! Pie and donut charts
+
+use std::f64::consts::PI;
+
+use crate::IntoVec;
+use crate::colors::{Color, get_cycle_color};
+use crate::markers::Marker;
+use crate::plot::{Plot, PlotType};
+use crate::render::{Anchor, PathData, Renderer, Style, TextStyle};
+use crate::scale::PlotArea;
+
+/// Pie specific settings
+///
+/// Wedges are drawn around the origin in data coordinates with radius 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PieStyle {
+    pub labels: Vec<String>,
+    /// Number of decimals of the percentage shown inside each wedge, if any
+    pub autopct: Option<usize>,
+    /// Angle in degrees (counter-clockwise from the positive x axis) of the first wedge's start
+    pub start_angle: f64,
+    pub counterclock: bool,
+    /// Per-wedge offset from the center, as a fraction of the radius
+    pub explode: Vec<f64>,
+    /// Inner radius as a fraction of the outer radius; above 0 draws a donut
+    pub inner_radius: f64,
+    /// Wedge colors (cycled); empty means the default color cycle
+    pub colors: Vec<Color>,
+    pub edge_color: Option<Color>,
+    pub label_distance: f64,
+    pub pct_distance: f64,
+    pub font_size: f64,
+}
+
+impl PieStyle {
+    fn new() -> Self {
+        PieStyle {
+            labels: Vec::new(),
+            autopct: None,
+            start_angle: 0.0,
+            counterclock: true,
+            explode: Vec::new(),
+            inner_radius: 0.0,
+            colors: Vec::new(),
+            edge_color: None,
+            label_distance: 1.1,
+            pct_distance: 0.6,
+            font_size: 14.0,
+        }
+    }
+
+    /// Largest distance from the origin covered by wedges and their labels
+    pub fn extent(&self) -> f64 {
+        let explode = self.explode.iter().copied().fold(0.0, f64::max);
+        let labels = if self.labels.is_empty() {
+            1.0
+        } else {
+            self.label_distance + 0.15
+        };
+        (1.0 + explode).max(labels + explode)
+    }
+
+    fn wedge_color(&self, index: usize) -> Color {
+        if self.colors.is_empty() {
+            get_cycle_color(index)
+        } else {
+            self.colors[index % self.colors.len()]
+        }
+    }
+}
+
+/// A single pie wedge with angles in radians
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Wedge {
+    pub start: f64,
+    pub end: f64,
+    pub fraction: f64,
+}
+
+impl Wedge {
+    fn mid(&self) -> f64 {
+        (self.start + self.end) / 2.0
+    }
+}
+
+impl Plot {
+    /// Create a new pie chart of the (non-negative) `values`
+    pub fn pie<V: IntoVec<f64>>(values: V) -> Self {
+        Plot {
+            x_data: Vec::new(),
+            y_data: values.into_vec(),
+            z_data: None,
+            plot_type: PlotType::Pie(PieStyle::new()),
+            color: None,
+            marker: Marker::None,
+            marker_size: 0.0,
+            line_width: 0.0,
+            label: None,
+            alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    fn pie_style_mut(&mut self) -> Option<&mut PieStyle> {
+        match self.plot_type {
+            PlotType::Pie(ref mut style) => Some(style),
+            _ => None,
+        }
+    }
+
+    /// Set the wedge labels drawn outside the pie
+    pub fn wedge_labels<S: AsRef<str>>(mut self, labels: &[S]) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.labels = labels.iter().map(|l| l.as_ref().to_string()).collect();
+        }
+        self
+    }
+
+    /// Show the percentage of every wedge with the given number of decimals
+    pub fn autopct(mut self, decimals: usize) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.autopct = Some(decimals);
+        }
+        self
+    }
+
+    /// Set the start angle of the first wedge in degrees
+    pub fn start_angle(mut self, degrees: f64) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.start_angle = degrees;
+        }
+        self
+    }
+
+    /// Order wedges counter-clockwise (default) or clockwise
+    pub fn counterclock(mut self, enable: bool) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.counterclock = enable;
+        }
+        self
+    }
+
+    /// Offset wedges from the center by a fraction of the radius
+    pub fn explode<E: IntoVec<f64>>(mut self, offsets: E) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.explode = offsets.into_vec();
+        }
+        self
+    }
+
+    /// Draw a donut whose hole has the given fraction of the radius
+    pub fn donut(mut self, inner_radius: f64) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.inner_radius = inner_radius.clamp(0.0, 0.99);
+        }
+        self
+    }
+
+    /// Set the wedge colors
+    pub fn wedge_colors(mut self, colors: Vec<Color>) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.colors = colors;
+        }
+        self
+    }
+
+    /// Outline the wedges with the given color
+    pub fn wedge_edge(mut self, color: Color) -> Self {
+        if let Some(style) = self.pie_style_mut() {
+            style.edge_color = Some(color);
+        }
+        self
+    }
+
+    /// Compute the wedge angles of a pie plot
+    ///
+    /// Returns an empty vector for non-pie plots or when all values are zero.
+    pub fn wedges(&self) -> Vec<Wedge> {
+        let PlotType::Pie(ref style) = self.plot_type else {
+            return Vec::new();
+        };
+
+        let total: f64 = self.y_data.iter().map(|v| v.max(0.0)).sum();
+        if total <= 0.0 {
+            return Vec::new();
+        }
+
+        let direction = if style.counterclock { 1.0 } else { -1.0 };
+        let mut angle = style.start_angle.to_radians();
+        self.y_data
+            .iter()
+            .map(|v| {
+                let fraction = v.max(0.0) / total;
+                let start = angle;
+                angle += direction * fraction * 2.0 * PI;
+                Wedge {
+                    start,
+                    end: angle,
+                    fraction,
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn draw_pie(&self, renderer: &mut dyn Renderer, style: &PieStyle, area: &PlotArea) {
+        // Radii in pixels; equal unless the axes aspect is not forced equal
+        let rx = area.x(1.0) - area.x(0.0);
+        let ry = area.y(0.0) - area.y(1.0);
+        let to_svg = |x: f64, y: f64| area.point(x, y);
+        let inner = style.inner_radius;
+        let text = TextStyle::new(style.font_size, Color::BLACK).dy(0.35);
+
+        let wedges = self.wedges();
+        for (i, wedge) in wedges.iter().enumerate() {
+            if wedge.fraction <= 0.0 {
+                continue;
+            }
+
+            let explode = style.explode.get(i).copied().unwrap_or(0.0);
+            let (cx, cy) = (explode * wedge.mid().cos(), explode * wedge.mid().sin());
+            let point = |r: f64, angle: f64| to_svg(cx + r * angle.cos(), cy + r * angle.sin());
+
+            // Counter-clockwise in data space is counter-clockwise on screen (no sweep flag)
+            let sweep = wedge.end <= wedge.start;
+            let full = wedge.fraction >= 1.0 - 1e-9;
+            // A full circle cannot be drawn with one arc, so split it at the halfway point
+            let angles = if full {
+                vec![wedge.start, wedge.mid(), wedge.end]
+            } else {
+                vec![wedge.start, wedge.end]
+            };
+            let large = !full && wedge.fraction > 0.5;
+
+            let mut path = PathData::new();
+            let (sx, sy) = point(1.0, angles[0]);
+            path.move_to(sx, sy);
+            for &angle in &angles[1..] {
+                let (px, py) = point(1.0, angle);
+                path.arc_to(rx, ry, large, sweep, px, py);
+            }
+            if inner > 0.0 {
+                let (px, py) = point(inner, *angles.last().unwrap());
+                path.line_to(px, py);
+                for &angle in angles.iter().rev().skip(1) {
+                    let (px, py) = point(inner, angle);
+                    path.arc_to(rx * inner, ry * inner, large, !sweep, px, py);
+                }
+            } else {
+                let (px, py) = to_svg(cx, cy);
+                path.line_to(px, py);
+            }
+            path.close();
+
+            let mut fill = Style::filled(style.wedge_color(i)).opacity(self.alpha);
+            if let Some(color) = style.edge_color {
+                fill = fill.stroke(color, 1.0);
+            }
+            renderer.path(&path, &fill);
+
+            let mid = wedge.mid();
+            if let Some(label) = style.labels.get(i) {
+                let (lx, ly) = point(style.label_distance, mid);
+                let anchor = if mid.cos() >= 0.0 {
+                    Anchor::Start
+                } else {
+                    Anchor::End
+                };
+                renderer.text(lx, ly, label, &text.clone().anchor(anchor));
+            }
+            if let Some(decimals) = style.autopct {
+                // Donut percentages sit in the middle of the ring
+                let distance = if inner > 0.0 {
+                    (1.0 + inner) / 2.0
+                } else {
+                    style.pct_distance
+                };
+                let (px, py) = point(distance, mid);
+                let percentage = format!("{:.*}%", decimals, wedge.fraction * 100.0);
+                renderer.text(px, py, &percentage, &text.clone().anchor(Anchor::Middle));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wedge_angles() {
+        let plot = Plot::pie(vec![1.0, 1.0, 2.0]).start_angle(90.0);
+        let wedges = plot.wedges();
+        assert_eq!(wedges.len(), 3);
+        assert_eq!(wedges[0].start, PI / 2.0);
+        assert_eq!(wedges[0].end, PI);
+        assert_eq!(wedges[2].fraction, 0.5);
+        assert!((wedges[2].end - (PI / 2.0 + 2.0 * PI)).abs() < 1e-12);
+
+        let plot = Plot::pie(vec![1.0, 3.0]).counterclock(false);
+        let wedges = plot.wedges();
+        assert_eq!(wedges[1].start, -PI / 2.0);
+        assert_eq!(wedges[1].end, -2.0 * PI);
+    }
+}
@@ -1,17 +1,124 @@
 //! Plot types and plotting functionality
 
 use crate::IntoVec;
-use crate::colors::Color;
+use crate::boxplot::BoxStyle;
+use crate::category;
+use crate::colors::{Color, Colormap, Normalize};
+use crate::contour::ContourStyle;
+use crate::image::ImageStyle;
 use crate::markers::Marker;
-use crate::utils::map_range;
+use crate::pie::PieStyle;
+use crate::render::{Group, PathData, Renderer, Style, SvgRenderer};
+use crate::scale::PlotArea;
+use crate::violin::ViolinStyle;
 
 /// Different types of plots
 #[derive(Debug, Clone, PartialEq)]
 pub enum PlotType {
     /// Line plot
     Line,
-    /// Scatter plot
-    Scatter,
+    /// Scatter plot, optionally colored by per-point values
+    Scatter(ScatterStyle),
+    /// Bar plot (vertical or horizontal)
+    Bar(BarStyle),
+    /// Filled polygon through the data points
+    Fill,
+    /// Pie or donut chart of the y data
+    Pie(PieStyle),
+    /// Box-and-whisker summary of the y data
+    Box(BoxStyle),
+    /// Kernel density estimate of the y data, mirrored around a position
+    Violin(ViolinStyle),
+    /// Isolines or filled contours of the z data over the x/y grid
+    Contour(ContourStyle),
+    /// Colored cells of the z data matrix (heatmap)
+    Image(ImageStyle),
+}
+
+/// Scatter specific settings
+///
+/// With `color_values` set, every point is colored by its value through
+/// `colormap` and `norm` instead of the plot color.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScatterStyle {
+    pub color_values: Option<Vec<f64>>,
+    pub colormap: Colormap,
+    pub norm: Normalize,
+}
+
+impl ScatterStyle {
+    fn new() -> Self {
+        ScatterStyle {
+            color_values: None,
+            colormap: Colormap::viridis(),
+            norm: Normalize::linear(),
+        }
+    }
+}
+
+/// Orientation of bars, boxes and other plots drawn along one axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Orientation {
+    /// Values grow along the y axis (e.g. `bar`)
+    Vertical,
+    /// Values grow along the x axis (e.g. `barh`)
+    Horizontal,
+}
+
+/// Alignment of a bar relative to its position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarAlign {
+    /// Bar is centered on its position
+    Center,
+    /// Bar's left (or bottom, for horizontal bars) edge sits on its position
+    Edge,
+}
+
+/// Bar specific settings
+///
+/// `widths`, `bottoms` and `colors` are cycled over the bars, so a single
+/// value applies to all of them. Empty `colors` means the plot color is used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BarStyle {
+    pub orientation: Orientation,
+    pub align: BarAlign,
+    pub widths: Vec<f64>,
+    pub bottoms: Vec<f64>,
+    pub colors: Vec<Color>,
+    pub edge_color: Option<Color>,
+    pub edge_width: f64,
+}
+
+impl BarStyle {
+    fn new(orientation: Orientation) -> Self {
+        BarStyle {
+            orientation,
+            align: BarAlign::Center,
+            widths: vec![0.8],
+            bottoms: vec![0.0],
+            colors: Vec::new(),
+            edge_color: None,
+            edge_width: 0.0,
+        }
+    }
+
+    /// Width of the bar at `index`
+    pub fn width_at(&self, index: usize) -> f64 {
+        cycled(&self.widths, index, 0.8)
+    }
+
+    /// Bottom offset (baseline) of the bar at `index`
+    pub fn bottom_at(&self, index: usize) -> f64 {
+        cycled(&self.bottoms, index, 0.0)
+    }
+}
+
+fn cycled(values: &[f64], index: usize, default: f64) -> f64 {
+    if values.is_empty() {
+        default
+    } else {
+        values[index % values.len()]
+    }
 }
 
 /// A single plot/series of data
@@ -27,6 +134,10 @@ pub struct Plot {
     pub line_width: f64,
     pub label: Option<String>,
     pub alpha: f64,
+    /// Category name of each x value; `x_data` then holds category positions
+    pub x_categories: Option<Vec<String>>,
+    /// Category name of each y value; `y_data` then holds category positions
+    pub y_categories: Option<Vec<String>>,
 }
 
 impl Plot {
@@ -47,6 +158,8 @@ impl Plot {
             line_width: 2.0,
             label: None,
             alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
         }
     }
 
@@ -60,16 +173,153 @@ impl Plot {
             x_data: x.into_vec(),
             y_data: y.into_vec(),
             z_data: None,
-            plot_type: PlotType::Scatter,
+            plot_type: PlotType::Scatter(ScatterStyle::new()),
             color: None,
             marker: Marker::Circle,
             marker_size: 4.0,
             line_width: 0.0,
             label: None,
             alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    /// Create a new vertical bar plot with bars at `x` of the given `height`
+    pub fn bar<X, H>(x: X, height: H) -> Self
+    where
+        X: IntoVec<f64>,
+        H: IntoVec<f64>,
+    {
+        Plot {
+            x_data: x.into_vec(),
+            y_data: height.into_vec(),
+            z_data: None,
+            plot_type: PlotType::Bar(BarStyle::new(Orientation::Vertical)),
+            color: None,
+            marker: Marker::None,
+            marker_size: 0.0,
+            line_width: 0.0,
+            label: None,
+            alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
         }
     }
 
+    /// Create a new horizontal bar plot with bars at `y` of the given `width`
+    pub fn barh<Y, W>(y: Y, width: W) -> Self
+    where
+        Y: IntoVec<f64>,
+        W: IntoVec<f64>,
+    {
+        Plot {
+            x_data: width.into_vec(),
+            y_data: y.into_vec(),
+            z_data: None,
+            plot_type: PlotType::Bar(BarStyle::new(Orientation::Horizontal)),
+            color: None,
+            marker: Marker::None,
+            marker_size: 0.0,
+            line_width: 0.0,
+            label: None,
+            alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    /// Create a new line plot with one point per x category
+    pub fn line_categories<C, S, Y>(categories: C, y: Y) -> Self
+    where
+        C: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        Y: IntoVec<f64>,
+    {
+        Plot::line(Vec::<f64>::new(), y).x_categories(categories)
+    }
+
+    /// Create a new scatter plot with one point per x category
+    pub fn scatter_categories<C, S, Y>(categories: C, y: Y) -> Self
+    where
+        C: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        Y: IntoVec<f64>,
+    {
+        Plot::scatter(Vec::<f64>::new(), y).x_categories(categories)
+    }
+
+    /// Create a new vertical bar plot with one bar per category
+    pub fn bar_categories<C, S, H>(categories: C, height: H) -> Self
+    where
+        C: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        H: IntoVec<f64>,
+    {
+        Plot::bar(Vec::<f64>::new(), height).x_categories(categories)
+    }
+
+    /// Create a new horizontal bar plot with one bar per category
+    pub fn barh_categories<C, S, W>(categories: C, width: W) -> Self
+    where
+        C: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        W: IntoVec<f64>,
+    {
+        Plot::barh(Vec::<f64>::new(), width).y_categories(categories)
+    }
+
+    /// Create a new filled polygon through the given points
+    pub fn fill<X, Y>(x: X, y: Y) -> Self
+    where
+        X: IntoVec<f64>,
+        Y: IntoVec<f64>,
+    {
+        Plot {
+            x_data: x.into_vec(),
+            y_data: y.into_vec(),
+            z_data: None,
+            plot_type: PlotType::Fill,
+            color: None,
+            marker: Marker::None,
+            marker_size: 0.0,
+            line_width: 0.0,
+            label: None,
+            alpha: 1.0,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    /// Place the points at string categories along the x axis
+    ///
+    /// Replaces the x values. Categories get positions in the order they first
+    /// appear, shared with the other plots of the axes they are added to.
+    pub fn x_categories<C, S>(mut self, categories: C) -> Self
+    where
+        C: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let names = category::names(categories);
+        self.x_data = category::positions(&names);
+        self.x_categories = Some(names);
+        self
+    }
+
+    /// Place the points at string categories along the y axis
+    ///
+    /// Replaces the y values, like [`Plot::x_categories`].
+    pub fn y_categories<C, S>(mut self, categories: C) -> Self
+    where
+        C: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let names = category::names(categories);
+        self.y_data = category::positions(&names);
+        self.y_categories = Some(names);
+        self
+    }
+
     /// Set the color of the plot
     pub fn color(mut self, color: Color) -> Self {
         self.color = Some(color);
@@ -106,11 +356,141 @@ impl Plot {
         self
     }
 
+    /// Set a single width (thickness) for all bars
+    pub fn bar_width(self, width: f64) -> Self {
+        self.bar_widths(vec![width])
+    }
+
+    /// Set per-bar widths (thickness), cycled if shorter than the data
+    pub fn bar_widths<W: IntoVec<f64>>(mut self, widths: W) -> Self {
+        if let PlotType::Bar(ref mut style) = self.plot_type {
+            style.widths = widths.into_vec();
+        }
+        self
+    }
+
+    /// Set the bar baselines, cycled if shorter than the data
+    pub fn bottom<B: IntoVec<f64>>(mut self, bottoms: B) -> Self {
+        if let PlotType::Bar(ref mut style) = self.plot_type {
+            style.bottoms = bottoms.into_vec();
+        }
+        self
+    }
+
+    /// Set how bars are aligned to their positions
+    pub fn align(mut self, align: BarAlign) -> Self {
+        if let PlotType::Bar(ref mut style) = self.plot_type {
+            style.align = align;
+        }
+        self
+    }
+
+    /// Set per-bar fill colors (cycled if shorter than the data)
+    pub fn bar_colors(mut self, colors: Vec<Color>) -> Self {
+        if let PlotType::Bar(ref mut style) = self.plot_type {
+            style.colors = colors;
+        }
+        self
+    }
+
+    /// Set the bar outline color and width
+    pub fn edge(mut self, color: Color, width: f64) -> Self {
+        if let PlotType::Bar(ref mut style) = self.plot_type {
+            style.edge_color = Some(color);
+            style.edge_width = width;
+        }
+        self
+    }
+
+    /// Set the position of a box or violin plot along the category axis
+    pub fn position(mut self, position: f64) -> Self {
+        match self.plot_type {
+            PlotType::Box(ref mut style) => style.position = position,
+            PlotType::Violin(ref mut style) => style.position = position,
+            _ => {}
+        }
+        self
+    }
+
+    /// Draw a box or violin plot vertically (default) or horizontally
+    pub fn orientation(mut self, orientation: Orientation) -> Self {
+        match self.plot_type {
+            PlotType::Box(ref mut style) => style.orientation = orientation,
+            PlotType::Violin(ref mut style) => style.orientation = orientation,
+            _ => {}
+        }
+        self
+    }
+
+    /// Color every scatter point by its value through the colormap
+    pub fn color_values<V: IntoVec<f64>>(mut self, values: V) -> Self {
+        if let PlotType::Scatter(ref mut style) = self.plot_type {
+            style.color_values = Some(values.into_vec());
+        }
+        self
+    }
+
+    /// Set the colormap of a color-mapped scatter, contour or image plot
+    pub fn colormap<C: Into<Colormap>>(mut self, colormap: C) -> Self {
+        let colormap = colormap.into();
+        match self.plot_type {
+            PlotType::Scatter(ref mut style) => style.colormap = colormap,
+            PlotType::Contour(ref mut style) => style.colormap = colormap,
+            PlotType::Image(ref mut style) => style.colormap = colormap,
+            _ => {}
+        }
+        self
+    }
+
+    /// Set how values are normalized before the colormap lookup
+    pub fn norm(mut self, norm: Normalize) -> Self {
+        match self.plot_type {
+            PlotType::Scatter(ref mut style) => style.norm = norm,
+            PlotType::Contour(ref mut style) => style.norm = norm,
+            PlotType::Image(ref mut style) => style.norm = norm,
+            _ => {}
+        }
+        self
+    }
+
+    /// Set the values mapped to the lowest and highest colormap colors
+    pub fn value_range(mut self, vmin: f64, vmax: f64) -> Self {
+        match self.plot_type {
+            PlotType::Scatter(ref mut style) => style.norm = style.norm.range(vmin, vmax),
+            PlotType::Contour(ref mut style) => style.norm = style.norm.range(vmin, vmax),
+            PlotType::Image(ref mut style) => style.norm = style.norm.range(vmin, vmax),
+            _ => {}
+        }
+        self
+    }
+
+    /// The colormap and data-resolved norm of a color-mapped plot
+    ///
+    /// Unset norm limits are taken from the color values of a scatter plot,
+    /// the levels of a contour plot and the cells of an image.
+    pub fn color_mapping(&self) -> Option<(&Colormap, Normalize)> {
+        match &self.plot_type {
+            PlotType::Scatter(style) => {
+                let values = style.color_values.as_ref()?;
+                Some((&style.colormap, style.norm.autoscaled(values)))
+            }
+            PlotType::Contour(style) => Some((
+                &style.colormap,
+                style.norm.autoscaled(&self.contour_levels()),
+            )),
+            PlotType::Image(style) => {
+                let z = self.z_data.as_ref()?;
+                Some((&style.colormap, style.norm.autoscaled(z.iter().flatten())))
+            }
+            _ => None,
+        }
+    }
+
     pub fn plot_color(&self) -> Color {
         self.color.unwrap_or(Color::BLACK)
     }
 
-    /// Generate SVG elements for this plot
+    /// Generate SVG elements for this plot on linear axes
     pub fn to_svg(
         &self,
         x_min: f64,
@@ -120,117 +500,259 @@ impl Plot {
         plot_width: f64,
         plot_height: f64,
     ) -> String {
-        let mut svg = String::new();
+        let area = PlotArea::linear((x_min, x_max), (y_min, y_max), plot_width, plot_height);
+        self.to_svg_in(&area)
+    }
 
+    /// Generate SVG elements for this plot inside the given plotting area
+    pub fn to_svg_in(&self, area: &PlotArea) -> String {
+        let mut svg = SvgRenderer::new();
+        self.draw(&mut svg, area);
+        svg.finish()
+    }
+
+    /// Draw this plot inside `area`, whose top left corner is at the origin
+    pub fn draw(&self, renderer: &mut dyn Renderer, area: &PlotArea) {
         // Skip length check for special plot types that don't require matching x/y data lengths
-        if self.x_data.len() != self.y_data.len() || self.x_data.is_empty() {
-            return svg;
+        let paired = matches!(
+            self.plot_type,
+            PlotType::Line | PlotType::Scatter(_) | PlotType::Bar(_) | PlotType::Fill
+        );
+        let empty = self.y_data.is_empty() && self.z_data.is_none();
+        if (paired && self.x_data.len() != self.y_data.len()) || empty {
+            return;
         }
 
-        let color_str = self.plot_color().to_svg_string();
-
-        match self.plot_type {
+        match &self.plot_type {
             PlotType::Line => {
                 if self.line_width > 0.0 {
-                    svg.push_str(&self.generate_line_svg(
-                        x_min,
-                        x_max,
-                        y_min,
-                        y_max,
-                        plot_width,
-                        plot_height,
-                        &color_str,
-                    ));
+                    self.draw_line(renderer, area);
                 }
                 if self.marker.is_visible() {
-                    svg.push_str(&self.generate_markers_svg(
-                        x_min,
-                        x_max,
-                        y_min,
-                        y_max,
-                        plot_width,
-                        plot_height,
-                        &color_str,
-                    ));
+                    self.draw_markers(renderer, area);
                 }
             }
-            PlotType::Scatter => {
-                svg.push_str(&self.generate_markers_svg(
-                    x_min,
-                    x_max,
-                    y_min,
-                    y_max,
-                    plot_width,
-                    plot_height,
-                    &color_str,
-                ));
-            }
+            PlotType::Scatter(_) => self.draw_markers(renderer, area),
+            PlotType::Bar(style) => self.draw_bars(renderer, style, area),
+            PlotType::Fill => self.draw_fill(renderer, area),
+            PlotType::Pie(style) => self.draw_pie(renderer, style, area),
+            PlotType::Box(style) => self.draw_box(renderer, style, area),
+            PlotType::Violin(style) => self.draw_violin(renderer, style, area),
+            PlotType::Contour(style) => self.draw_contour(renderer, style, area),
+            PlotType::Image(style) => self.draw_image(renderer, style, area),
         }
+    }
 
-        svg
+    /// Split a bar plot into its style, bar positions and bar lengths
+    pub(crate) fn bar_parts_mut(
+        &mut self,
+    ) -> Option<(&mut BarStyle, &mut Vec<f64>, &mut Vec<f64>)> {
+        let PlotType::Bar(ref mut style) = self.plot_type else {
+            return None;
+        };
+        match style.orientation {
+            Orientation::Vertical => Some((style, &mut self.x_data, &mut self.y_data)),
+            Orientation::Horizontal => Some((style, &mut self.y_data, &mut self.x_data)),
+        }
     }
 
-    fn generate_line_svg(
-        &self,
-        x_min: f64,
-        x_max: f64,
-        y_min: f64,
-        y_max: f64,
-        plot_width: f64,
-        plot_height: f64,
-        color: &str,
-    ) -> String {
-        let mut path_data = String::new();
+    /// Rectangles `(x0, x1, y0, y1)` in data coordinates for every bar
+    ///
+    /// Returns an empty vector for non-bar plots.
+    pub fn bar_rects(&self) -> Vec<(f64, f64, f64, f64)> {
+        let PlotType::Bar(ref style) = self.plot_type else {
+            return Vec::new();
+        };
 
-        for (i, (&x, &y)) in self.x_data.iter().zip(self.y_data.iter()).enumerate() {
-            let svg_x = map_range(x, x_min, x_max, 0.0, plot_width);
-            let svg_y = map_range(y, y_min, y_max, plot_height, 0.0); // Flip Y axis
+        let (positions, lengths) = match style.orientation {
+            Orientation::Vertical => (&self.x_data, &self.y_data),
+            Orientation::Horizontal => (&self.y_data, &self.x_data),
+        };
+
+        positions
+            .iter()
+            .zip(lengths.iter())
+            .enumerate()
+            .map(|(i, (&pos, &len))| {
+                let width = style.width_at(i);
+                let start = match style.align {
+                    BarAlign::Center => pos - width / 2.0,
+                    BarAlign::Edge => pos,
+                };
+                let bottom = style.bottom_at(i);
+                match style.orientation {
+                    Orientation::Vertical => (start, start + width, bottom, bottom + len),
+                    Orientation::Horizontal => (bottom, bottom + len, start, start + width),
+                }
+            })
+            .collect()
+    }
+
+    fn draw_bars(&self, renderer: &mut dyn Renderer, style: &BarStyle, area: &PlotArea) {
+        for (i, (x0, x1, y0, y1)) in self.bar_rects().into_iter().enumerate() {
+            let (sx0, sy0) = area.point(x0, y0);
+            let (sx1, sy1) = area.point(x1, y1);
+            // Bars with an end the axis scale masks are left out
+            if !(sx0.is_finite() && sx1.is_finite() && sy0.is_finite() && sy1.is_finite()) {
+                continue;
+            }
 
-            if i == 0 {
-                path_data.push_str(&format!("M {},{}", svg_x, svg_y));
+            let color = if style.colors.is_empty() {
+                self.plot_color()
             } else {
-                path_data.push_str(&format!(" L {},{}", svg_x, svg_y));
+                style.colors[i % style.colors.len()]
+            };
+            let mut bar = Style::filled(color).opacity(self.alpha);
+            if let Some(edge_color) = style.edge_color.filter(|_| style.edge_width > 0.0) {
+                bar = bar.stroke(edge_color, style.edge_width);
             }
-        }
 
-        format!(
-            "<path d=\"{}\" stroke=\"{}\" stroke-width=\"{}\" fill=\"none\" opacity=\"{}\"/>",
-            path_data, color, self.line_width, self.alpha
-        )
+            renderer.rect(
+                sx0.min(sx1),
+                sy0.min(sy1),
+                (sx1 - sx0).abs(),
+                (sy1 - sy0).abs(),
+                &bar,
+            );
+        }
     }
 
-    fn generate_markers_svg(
-        &self,
-        x_min: f64,
-        x_max: f64,
-        y_min: f64,
-        y_max: f64,
-        plot_width: f64,
-        plot_height: f64,
-        color: &str,
-    ) -> String {
-        let mut svg = String::new();
+    fn draw_line(&self, renderer: &mut dyn Renderer, area: &PlotArea) {
+        let mut path = PathData::new();
+        let mut pen_down = false;
 
         for (&x, &y) in self.x_data.iter().zip(self.y_data.iter()) {
-            let svg_x = map_range(x, x_min, x_max, 0.0, plot_width);
-            let svg_y = map_range(y, y_min, y_max, plot_height, 0.0); // Flip Y axis
-
-            let marker_svg = self
-                .marker
-                .to_svg_element(svg_x, svg_y, self.marker_size, color);
-            if !marker_svg.is_empty() {
-                svg.push_str(&format!("<g opacity=\"{}\">{}</g>", self.alpha, marker_svg));
-
-                // // Add text label if available
-                // if let Some(ref label) = self.label {
-                //     svg.push_str(&format!(
-                //         "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"12\" fill=\"black\" dy=\"0.35em\">{}</text>",
-                //         svg_x, svg_y, label
-                //     ));
-                // }
+            let (svg_x, svg_y) = area.point(x, y);
+
+            // Points masked by the axis scale break the line
+            if !(svg_x.is_finite() && svg_y.is_finite()) {
+                pen_down = false;
+                continue;
+            }
+            if pen_down {
+                path.line_to(svg_x, svg_y);
+            } else {
+                path.move_to(svg_x, svg_y);
+                pen_down = true;
             }
         }
 
-        svg
+        let style = Style::stroked(self.plot_color(), self.line_width).opacity(self.alpha);
+        renderer.path(&path, &style);
+    }
+
+    fn draw_fill(&self, renderer: &mut dyn Renderer, area: &PlotArea) {
+        let points: Vec<(f64, f64)> = self
+            .x_data
+            .iter()
+            .zip(self.y_data.iter())
+            .map(|(&x, &y)| area.point(x, y))
+            .filter(|(x, y)| x.is_finite() && y.is_finite())
+            .collect();
+
+        let color = self.plot_color();
+        let mut style = Style::filled(color).opacity(self.alpha);
+        if self.line_width > 0.0 {
+            style = style.stroke(color, self.line_width);
+        }
+        renderer.path(&PathData::polygon(&points), &style);
+    }
+
+    fn draw_markers(&self, renderer: &mut dyn Renderer, area: &PlotArea) {
+        if !self.marker.is_visible() {
+            return;
+        }
+
+        // Color-mapped scatter points get one color per value
+        let mapped_colors: Option<Vec<Color>> = match (&self.plot_type, self.color_mapping()) {
+            (PlotType::Scatter(style), Some((colormap, norm))) => style
+                .color_values
+                .as_ref()
+                .map(|values| values.iter().map(|&v| colormap.at(norm.apply(v))).collect()),
+            _ => None,
+        };
+
+        for (i, (&x, &y)) in self.x_data.iter().zip(self.y_data.iter()).enumerate() {
+            let (svg_x, svg_y) = area.point(x, y);
+            if !(svg_x.is_finite() && svg_y.is_finite()) {
+                continue;
+            }
+
+            let color = mapped_colors
+                .as_ref()
+                .and_then(|colors| colors.get(i).copied())
+                .unwrap_or_else(|| self.plot_color());
+            // Each marker is faded on its own so that overlapping markers show through
+            if self.alpha < 1.0 {
+                renderer.begin_group(&Group::opacity(self.alpha));
+            }
+            self.marker
+                .draw(renderer, svg_x, svg_y, self.marker_size, color);
+            if self.alpha < 1.0 {
+                renderer.end_group();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bar_rects() {
+        let plot = Plot::bar(vec![1.0, 2.0], vec![3.0, 4.0])
+            .bar_width(0.5)
+            .bottom(vec![1.0, 0.0]);
+        assert_eq!(
+            plot.bar_rects(),
+            vec![(0.75, 1.25, 1.0, 4.0), (1.75, 2.25, 0.0, 4.0)]
+        );
+
+        let plot = Plot::barh(vec![1.0], vec![2.0]).align(BarAlign::Edge);
+        assert_eq!(plot.bar_rects(), vec![(0.0, 2.0, 1.0, 1.8)]);
+
+        // Too few widths are cycled like colors instead of falling back to the default
+        let plot = Plot::bar(vec![0.0; 5], vec![1.0; 5])
+            .bar_widths(vec![0.2, 0.4, 0.6])
+            .align(BarAlign::Edge);
+        let widths: Vec<f64> = plot.bar_rects().iter().map(|r| r.1 - r.0).collect();
+        assert_eq!(widths, vec![0.2, 0.4, 0.6, 0.2, 0.4]);
+    }
+
+    #[test]
+    fn test_nan_breaks_line_on_log_and_logit_axes() {
+        use crate::render::{Command, RecordingRenderer, Segment};
+        use crate::scale::{NonPositive, Scale};
+
+        let cases = [
+            (Scale::log(), vec![1.0, 10.0, f64::NAN, 100.0], (1.0, 100.0)),
+            (
+                Scale::logit().nonpositive(NonPositive::Clip),
+                vec![0.1, 0.5, f64::NAN, 0.9],
+                (0.1, 0.9),
+            ),
+        ];
+        for (scale, y, y_limits) in cases {
+            let plot = Plot::line(vec![0.0, 1.0, 2.0, 3.0], y);
+            let area = PlotArea {
+                y_scale: scale,
+                ..PlotArea::linear((0.0, 3.0), y_limits, 100.0, 50.0)
+            };
+            let mut recording = RecordingRenderer::new();
+            plot.draw(&mut recording, &area);
+
+            let Some(Command::Path { path, .. }) = recording.commands.first() else {
+                panic!("no line drawn");
+            };
+            // The missing point starts a new subpath instead of dropping to the axis edge
+            let moves = path
+                .segments
+                .iter()
+                .filter(|s| matches!(s, Segment::MoveTo(..)))
+                .count();
+            assert_eq!(moves, 2);
+            assert_eq!(path.segments.len(), 3);
+        }
     }
 }
@@ -0,0 +1,157 @@
+//! Rasterization of figures to PNG without a display
+//!
+//! The SVG of a figure is rendered with antialiasing using the same fonts
+//! text is measured with, so no system fonts or windowing system are needed.
+
+use std::fmt;
+
+use resvg::{tiny_skia, usvg};
+
+use crate::save::Metadata;
+use crate::text;
+
+/// Resolution at which one unit of figure size is one pixel
+pub const BASE_DPI: f64 = 100.0;
+
+/// Error rasterizing or saving a figure
+#[derive(Debug)]
+pub enum RasterError {
+    /// The figure SVG could not be parsed
+    Svg(usvg::Error),
+    /// The image would have no pixels or too many
+    Size {
+        width: f64,
+        height: f64,
+    },
+    Encode(png::EncodingError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for RasterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RasterError::Svg(error) => write!(f, "cannot parse figure SVG: {}", error),
+            RasterError::Size { width, height } => {
+                write!(f, "cannot rasterize a {}x{} pixel image", width, height)
+            }
+            RasterError::Encode(error) => write!(f, "cannot encode PNG: {}", error),
+            RasterError::Io(error) => write!(f, "cannot write PNG: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for RasterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RasterError::Svg(error) => Some(error),
+            RasterError::Size { .. } => None,
+            RasterError::Encode(error) => Some(error),
+            RasterError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<usvg::Error> for RasterError {
+    fn from(error: usvg::Error) -> Self {
+        RasterError::Svg(error)
+    }
+}
+
+impl From<png::EncodingError> for RasterError {
+    fn from(error: png::EncodingError) -> Self {
+        RasterError::Encode(error)
+    }
+}
+
+impl From<std::io::Error> for RasterError {
+    fn from(error: std::io::Error) -> Self {
+        RasterError::Io(error)
+    }
+}
+
+/// SVG parsing options that resolve every font family to the fonts text is measured with
+pub(crate) fn svg_options() -> usvg::Options<'static> {
+    let fonts = text::fonts();
+    let mut options = usvg::Options::default();
+    let database = options.fontdb_mut();
+    for font in &fonts {
+        database.load_font_source(usvg::fontdb::Source::Binary(font.shared_data()));
+    }
+    if let Some(family) = fonts[0].family_name() {
+        database.set_serif_family(family.clone());
+        database.set_sans_serif_family(family.clone());
+        options.font_family = family;
+    }
+    options
+}
+
+/// Bounds `(x, y, width, height)` of what `svg` draws, including strokes and text
+pub(crate) fn content_bounds(svg: &str) -> Result<Option<(f64, f64, f64, f64)>, RasterError> {
+    let tree = usvg::Tree::from_str(svg, &svg_options())?;
+    if !tree.root().has_children() {
+        return Ok(None);
+    }
+    let bbox = tree.root().abs_stroke_bounding_box();
+    Ok(Some((
+        bbox.x() as f64,
+        bbox.y() as f64,
+        bbox.width() as f64,
+        bbox.height() as f64,
+    )))
+}
+
+/// Render `svg` to a PNG `scale` times its size, tagged with `dpi` for print and `metadata`
+pub(crate) fn svg_to_png(
+    svg: &str,
+    scale: f64,
+    dpi: f64,
+    metadata: &Metadata,
+) -> Result<Vec<u8>, RasterError> {
+    let tree = usvg::Tree::from_str(svg, &svg_options())?;
+    let size = tree.size();
+    let (width, height) = (
+        (size.width() as f64 * scale).round(),
+        (size.height() as f64 * scale).round(),
+    );
+    let mut pixmap = tiny_skia::Pixmap::new(width as u32, height as u32)
+        .ok_or(RasterError::Size { width, height })?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale as f32, scale as f32),
+        &mut pixmap.as_mut(),
+    );
+
+    // Pixels are premultiplied while drawing but PNG stores straight alpha
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, pixmap.width(), pixmap.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let pixels_per_meter = (dpi / 0.0254).round() as u32;
+    encoder.set_pixel_dims(Some(png::PixelDimensions {
+        xppu: pixels_per_meter,
+        yppu: pixels_per_meter,
+        unit: png::Unit::Meter,
+    }));
+    for (key, value) in metadata.entries() {
+        // Keywords PNG readers know
+        let keyword = match key {
+            "Subject" => "Description",
+            "Creator" => "Software",
+            key => key,
+        };
+        encoder.add_itxt_chunk(keyword.to_string(), value)?;
+    }
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&data)?;
+    writer.finish()?;
+    Ok(png)
+}
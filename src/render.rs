@@ -0,0 +1,756 @@
+//! Drawing backends
+//!
+//! Artists draw themselves through the [`Renderer`] trait in pixels, with y
+//! growing downwards. [`SvgRenderer`] writes SVG and [`RecordingRenderer`]
+//! keeps the calls so that tests can inspect what was drawn.
+//!
+//! These are the only two backends. PNG and PDF output do not implement
+//! [`Renderer`]: they render the SVG written by [`SvgRenderer`], so they see
+//! whatever it produces, including [`Renderer::svg_fragment`] markup.
+
+use std::fmt::Write;
+
+use crate::colors::Color;
+use crate::text;
+
+/// One step of a [`PathData`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    MoveTo(f64, f64),
+    LineTo(f64, f64),
+    /// Elliptical arc to `(x, y)` with the flags of SVG arcs
+    ArcTo {
+        rx: f64,
+        ry: f64,
+        large_arc: bool,
+        sweep: bool,
+        x: f64,
+        y: f64,
+    },
+    Close,
+}
+
+/// Outline of a shape made of lines and arcs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathData {
+    pub segments: Vec<Segment>,
+}
+
+impl PathData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Closed outline through `points`
+    pub fn polygon(points: &[(f64, f64)]) -> Self {
+        let mut path = PathData::polyline(points);
+        if !path.is_empty() {
+            path.close();
+        }
+        path
+    }
+
+    /// Open outline through `points`
+    pub fn polyline(points: &[(f64, f64)]) -> Self {
+        let mut path = PathData::new();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            if i == 0 {
+                path.move_to(x, y);
+            } else {
+                path.line_to(x, y);
+            }
+        }
+        path
+    }
+
+    /// Rectangle with corners rounded by `radius`
+    pub fn rounded_rect(x: f64, y: f64, width: f64, height: f64, radius: f64) -> Self {
+        let r = radius.min(width / 2.0).min(height / 2.0).max(0.0);
+        let mut path = PathData::new();
+        path.move_to(x + r, y)
+            .line_to(x + width - r, y)
+            .arc_to(r, r, false, true, x + width, y + r)
+            .line_to(x + width, y + height - r)
+            .arc_to(r, r, false, true, x + width - r, y + height)
+            .line_to(x + r, y + height)
+            .arc_to(r, r, false, true, x, y + height - r)
+            .line_to(x, y + r)
+            .arc_to(r, r, false, true, x + r, y)
+            .close();
+        path
+    }
+
+    /// Ellipse centered at `(cx, cy)`
+    pub fn ellipse(cx: f64, cy: f64, rx: f64, ry: f64) -> Self {
+        let mut path = PathData::new();
+        path.move_to(cx - rx, cy)
+            .arc_to(rx, ry, false, true, cx + rx, cy)
+            .arc_to(rx, ry, false, true, cx - rx, cy)
+            .close();
+        path
+    }
+
+    pub fn move_to(&mut self, x: f64, y: f64) -> &mut Self {
+        self.segments.push(Segment::MoveTo(x, y));
+        self
+    }
+
+    pub fn line_to(&mut self, x: f64, y: f64) -> &mut Self {
+        self.segments.push(Segment::LineTo(x, y));
+        self
+    }
+
+    pub fn arc_to(
+        &mut self,
+        rx: f64,
+        ry: f64,
+        large_arc: bool,
+        sweep: bool,
+        x: f64,
+        y: f64,
+    ) -> &mut Self {
+        self.segments.push(Segment::ArcTo {
+            rx,
+            ry,
+            large_arc,
+            sweep,
+            x,
+            y,
+        });
+        self
+    }
+
+    pub fn close(&mut self) -> &mut Self {
+        self.segments.push(Segment::Close);
+        self
+    }
+
+    /// Add the segments of `other` after those of this path
+    pub fn extend(&mut self, other: PathData) -> &mut Self {
+        self.segments.extend(other.segments);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Path data in SVG syntax, e.g. `"M 0,0 L 1,1"`
+    pub fn to_svg(&self) -> String {
+        let mut d = String::new();
+        for segment in &self.segments {
+            if !d.is_empty() {
+                d.push(' ');
+            }
+            match *segment {
+                Segment::MoveTo(x, y) => write!(d, "M {},{}", x, y),
+                Segment::LineTo(x, y) => write!(d, "L {},{}", x, y),
+                Segment::ArcTo {
+                    rx,
+                    ry,
+                    large_arc,
+                    sweep,
+                    x,
+                    y,
+                } => write!(
+                    d,
+                    "A {},{} 0 {} {} {},{}",
+                    rx, ry, large_arc as u8, sweep as u8, x, y
+                ),
+                Segment::Close => write!(d, "Z"),
+            }
+            .unwrap();
+        }
+        d
+    }
+}
+
+/// How shapes are filled and outlined
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub fill: Option<Color>,
+    pub stroke: Option<Color>,
+    pub stroke_width: f64,
+    /// Dash and gap lengths in pixels; solid if empty
+    pub dash: Vec<f64>,
+    /// Opacity of the whole shape, on top of the alpha of its colors
+    pub opacity: f64,
+    /// Round the corners where outline segments meet
+    pub round_join: bool,
+}
+
+impl Style {
+    /// Neither filled nor outlined
+    pub fn new() -> Self {
+        Style {
+            fill: None,
+            stroke: None,
+            stroke_width: 1.0,
+            dash: Vec::new(),
+            opacity: 1.0,
+            round_join: false,
+        }
+    }
+
+    /// Filled with `color` and not outlined
+    pub fn filled(color: Color) -> Self {
+        Style::new().fill(color)
+    }
+
+    /// Outlined with `color` lines `width` pixels wide and not filled
+    pub fn stroked(color: Color, width: f64) -> Self {
+        Style::new().stroke(color, width)
+    }
+
+    pub fn fill(mut self, color: Color) -> Self {
+        self.fill = Some(color);
+        self
+    }
+
+    pub fn stroke(mut self, color: Color, width: f64) -> Self {
+        self.stroke = Some(color);
+        self.stroke_width = width;
+        self
+    }
+
+    pub fn dash(mut self, dash: Vec<f64>) -> Self {
+        self.dash = dash;
+        self
+    }
+
+    pub fn opacity(mut self, opacity: f64) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn round_join(mut self) -> Self {
+        self.round_join = true;
+        self
+    }
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Horizontal alignment of text with its position
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Anchor {
+    #[default]
+    Start,
+    Middle,
+    End,
+}
+
+/// How text is drawn
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    pub font_size: f64,
+    pub color: Color,
+    pub anchor: Anchor,
+    /// Downward shift of the baseline in ems, e.g. 0.35 to center text on its position
+    pub dy: f64,
+    /// Counterclockwise rotation in degrees about the position
+    pub rotation: f64,
+    pub bold: bool,
+    /// Font family; the font text is measured with if unset
+    pub font_family: Option<String>,
+    /// Outline `(color, width)` drawn behind the glyphs to keep text readable
+    pub halo: Option<(Color, f64)>,
+}
+
+impl TextStyle {
+    pub fn new(font_size: f64, color: Color) -> Self {
+        TextStyle {
+            font_size,
+            color,
+            anchor: Anchor::Start,
+            dy: 0.0,
+            rotation: 0.0,
+            bold: false,
+            font_family: None,
+            halo: None,
+        }
+    }
+
+    pub fn anchor(mut self, anchor: Anchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    pub fn dy(mut self, dy: f64) -> Self {
+        self.dy = dy;
+        self
+    }
+
+    pub fn rotation(mut self, rotation: f64) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn bold(mut self, bold: bool) -> Self {
+        self.bold = bold;
+        self
+    }
+
+    pub fn font_family(mut self, family: &str) -> Self {
+        self.font_family = Some(family.to_string());
+        self
+    }
+
+    pub fn halo(mut self, color: Color, width: f64) -> Self {
+        self.halo = Some((color, width));
+        self
+    }
+}
+
+/// Settings of a group of drawing calls
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Group {
+    /// Offset added to every position drawn in the group
+    pub offset: (f64, f64),
+    /// Opacity the group is composited with as a whole
+    pub opacity: f64,
+}
+
+impl Group {
+    /// A group moved by `(x, y)`
+    pub fn translate(x: f64, y: f64) -> Self {
+        Group {
+            offset: (x, y),
+            opacity: 1.0,
+        }
+    }
+
+    /// A group composited with `opacity`
+    pub fn opacity(opacity: f64) -> Self {
+        Group {
+            offset: (0.0, 0.0),
+            opacity,
+        }
+    }
+}
+
+/// A drawing backend
+///
+/// The crate implements it for [`SvgRenderer`] and [`RecordingRenderer`]
+/// only; raster and PDF output are made from the SVG rather than drawn
+/// through their own renderer.
+///
+/// Clips and groups nest: every `begin_*` call is matched by the `end_*` call
+/// of the same kind before the enclosing clip or group ends.
+pub trait Renderer {
+    fn path(&mut self, path: &PathData, style: &Style);
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, style: &Style);
+
+    fn circle(&mut self, cx: f64, cy: f64, r: f64, style: &Style);
+
+    fn text(&mut self, x: f64, y: f64, text: &str, style: &TextStyle);
+
+    /// Rows of pixels, top row first, stretched over the rectangle without smoothing
+    fn image(&mut self, x: f64, y: f64, width: f64, height: f64, pixels: &[Vec<Color>]);
+
+    /// Clip what is drawn until the matching [`end_clip`](Renderer::end_clip) to a rectangle
+    fn begin_clip(&mut self, x: f64, y: f64, width: f64, height: f64);
+
+    fn end_clip(&mut self);
+
+    fn begin_group(&mut self, group: &Group);
+
+    fn end_group(&mut self);
+
+    /// Straight line from `from` to `to`
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), style: &Style) {
+        self.path(&PathData::polyline(&[from, to]), style);
+    }
+
+    /// SVG markup added with [`Axes::add_svg_element`](crate::axes::Axes::add_svg_element)
+    ///
+    /// Only SVG output shows it; other backends ignore it.
+    fn svg_fragment(&mut self, _svg: &str) {}
+}
+
+/// Renderer that writes the elements of an SVG document
+#[derive(Debug, Default)]
+pub struct SvgRenderer {
+    svg: String,
+    clips: usize,
+}
+
+impl SvgRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The elements written so far
+    pub fn finish(self) -> String {
+        self.svg
+    }
+
+    fn style_attributes(&mut self, style: &Style) {
+        match style.fill {
+            Some(color) => self.paint("fill", color),
+            None => self.svg.push_str(" fill=\"none\""),
+        }
+        if let Some(color) = style.stroke {
+            self.paint("stroke", color);
+            write!(self.svg, " stroke-width=\"{}\"", style.stroke_width).unwrap();
+            if !style.dash.is_empty() {
+                let dash: Vec<String> = style.dash.iter().map(|d| d.to_string()).collect();
+                write!(self.svg, " stroke-dasharray=\"{}\"", dash.join(",")).unwrap();
+            }
+            if style.round_join {
+                self.svg.push_str(" stroke-linejoin=\"round\"");
+            }
+        }
+        if style.opacity < 1.0 {
+            write!(self.svg, " opacity=\"{}\"", style.opacity).unwrap();
+        }
+    }
+
+    /// `fill` or `stroke` attribute with the alpha of `color` as its opacity
+    fn paint(&mut self, attribute: &str, color: Color) {
+        let opaque = Color { a: 1.0, ..color };
+        write!(self.svg, " {}=\"{}\"", attribute, opaque.to_svg_string()).unwrap();
+        if color.a < 1.0 {
+            write!(self.svg, " {}-opacity=\"{}\"", attribute, color.a).unwrap();
+        }
+    }
+}
+
+impl Renderer for SvgRenderer {
+    fn path(&mut self, path: &PathData, style: &Style) {
+        write!(self.svg, "<path d=\"{}\"", path.to_svg()).unwrap();
+        self.style_attributes(style);
+        self.svg.push_str(" />\n");
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, style: &Style) {
+        write!(
+            self.svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"",
+            x, y, width, height
+        )
+        .unwrap();
+        self.style_attributes(style);
+        self.svg.push_str(" />\n");
+    }
+
+    fn circle(&mut self, cx: f64, cy: f64, r: f64, style: &Style) {
+        write!(self.svg, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\"", cx, cy, r).unwrap();
+        self.style_attributes(style);
+        self.svg.push_str(" />\n");
+    }
+
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), style: &Style) {
+        write!(
+            self.svg,
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"",
+            from.0, from.1, to.0, to.1
+        )
+        .unwrap();
+        self.style_attributes(style);
+        self.svg.push_str(" />\n");
+    }
+
+    fn text(&mut self, x: f64, y: f64, text: &str, style: &TextStyle) {
+        let anchor = match style.anchor {
+            Anchor::Start => "start",
+            Anchor::Middle => "middle",
+            Anchor::End => "end",
+        };
+        write!(
+            self.svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"{}\" font-size=\"{}\"",
+            x, y, anchor, style.font_size
+        )
+        .unwrap();
+        // Unset families are drawn in the font text is measured with, so that
+        // what viewers show matches the layout
+        if let Some(family) = style.font_family.clone().or_else(text::main_family) {
+            write!(self.svg, " font-family=\"{}\"", escape_xml(&family)).unwrap();
+        }
+        if style.bold {
+            self.svg.push_str(" font-weight=\"bold\"");
+        }
+        self.paint("fill", style.color);
+        if let Some((color, width)) = style.halo {
+            self.paint("stroke", color);
+            write!(
+                self.svg,
+                " stroke-width=\"{}\" paint-order=\"stroke\"",
+                width
+            )
+            .unwrap();
+        }
+        if style.dy != 0.0 {
+            write!(self.svg, " dy=\"{}em\"", style.dy).unwrap();
+        }
+        if style.rotation != 0.0 {
+            write!(
+                self.svg,
+                " transform=\"rotate({}, {}, {})\"",
+                -style.rotation, x, y
+            )
+            .unwrap();
+        }
+        writeln!(self.svg, ">{}</text>", escape_xml(text)).unwrap();
+    }
+
+    /// One rectangle per pixel so that every SVG reader shows the image
+    fn image(&mut self, x: f64, y: f64, width: f64, height: f64, pixels: &[Vec<Color>]) {
+        let rows = pixels.len();
+        let cols = pixels.iter().map(Vec::len).max().unwrap_or(0);
+        if rows == 0 || cols == 0 {
+            return;
+        }
+        let (dx, dy) = (width / cols as f64, height / rows as f64);
+        for (r, row) in pixels.iter().enumerate() {
+            for (c, &color) in row.iter().enumerate() {
+                if color.a <= 0.0 {
+                    continue;
+                }
+                // A hairline outline in the pixel color hides antialiasing seams
+                let opaque = Color { a: 1.0, ..color };
+                let style = Style::filled(opaque).stroke(opaque, 0.5).opacity(color.a);
+                self.rect(x + c as f64 * dx, y + r as f64 * dy, dx, dy, &style);
+            }
+        }
+    }
+
+    fn begin_clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        let id = self.clips;
+        self.clips += 1;
+        write!(
+            self.svg,
+            "<clipPath id=\"clip{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" /></clipPath>\n<g clip-path=\"url(#clip{})\">\n",
+            id, x, y, width, height, id
+        )
+        .unwrap();
+    }
+
+    fn end_clip(&mut self) {
+        self.svg.push_str("</g>\n");
+    }
+
+    fn begin_group(&mut self, group: &Group) {
+        self.svg.push_str("<g");
+        if group.offset != (0.0, 0.0) {
+            write!(
+                self.svg,
+                " transform=\"translate({},{})\"",
+                group.offset.0, group.offset.1
+            )
+            .unwrap();
+        }
+        if group.opacity < 1.0 {
+            write!(self.svg, " opacity=\"{}\"", group.opacity).unwrap();
+        }
+        self.svg.push_str(">\n");
+    }
+
+    fn end_group(&mut self) {
+        self.svg.push_str("</g>\n");
+    }
+
+    fn svg_fragment(&mut self, svg: &str) {
+        self.svg.push_str(svg);
+        self.svg.push('\n');
+    }
+}
+
+/// A call made to a [`RecordingRenderer`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Path {
+        path: PathData,
+        style: Style,
+    },
+    Rect {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        style: Style,
+    },
+    Circle {
+        cx: f64,
+        cy: f64,
+        r: f64,
+        style: Style,
+    },
+    Text {
+        x: f64,
+        y: f64,
+        text: String,
+        style: TextStyle,
+    },
+    Image {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        pixels: Vec<Vec<Color>>,
+    },
+    BeginClip {
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+    },
+    EndClip,
+    BeginGroup(Group),
+    EndGroup,
+}
+
+/// Renderer that records every call, for tests and debugging
+#[derive(Debug, Clone, Default)]
+pub struct RecordingRenderer {
+    pub commands: Vec<Command>,
+}
+
+impl RecordingRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The strings of all text drawn, in order
+    pub fn texts(&self) -> Vec<&str> {
+        self.commands
+            .iter()
+            .filter_map(|command| match command {
+                Command::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn path(&mut self, path: &PathData, style: &Style) {
+        self.commands.push(Command::Path {
+            path: path.clone(),
+            style: style.clone(),
+        });
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, style: &Style) {
+        self.commands.push(Command::Rect {
+            x,
+            y,
+            width,
+            height,
+            style: style.clone(),
+        });
+    }
+
+    fn circle(&mut self, cx: f64, cy: f64, r: f64, style: &Style) {
+        self.commands.push(Command::Circle {
+            cx,
+            cy,
+            r,
+            style: style.clone(),
+        });
+    }
+
+    fn text(&mut self, x: f64, y: f64, text: &str, style: &TextStyle) {
+        self.commands.push(Command::Text {
+            x,
+            y,
+            text: text.to_string(),
+            style: style.clone(),
+        });
+    }
+
+    fn image(&mut self, x: f64, y: f64, width: f64, height: f64, pixels: &[Vec<Color>]) {
+        self.commands.push(Command::Image {
+            x,
+            y,
+            width,
+            height,
+            pixels: pixels.to_vec(),
+        });
+    }
+
+    fn begin_clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.commands.push(Command::BeginClip {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    fn end_clip(&mut self) {
+        self.commands.push(Command::EndClip);
+    }
+
+    fn begin_group(&mut self, group: &Group) {
+        self.commands.push(Command::BeginGroup(*group));
+    }
+
+    fn end_group(&mut self) {
+        self.commands.push(Command::EndGroup);
+    }
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_svg_renderer() {
+        let mut svg = SvgRenderer::new();
+        svg.begin_group(&Group::translate(10.0, 20.0));
+        svg.begin_clip(0.0, 0.0, 100.0, 50.0);
+        svg.path(
+            &PathData::polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]),
+            &Style::filled(Color::rgba(255, 0, 0, 0.5)),
+        );
+        svg.end_clip();
+        svg.text(
+            5.0,
+            6.0,
+            "a < b",
+            &TextStyle::new(9.0, Color::BLACK).rotation(30.0),
+        );
+        svg.end_group();
+
+        let svg = svg.finish();
+        assert!(svg.starts_with("<g transform=\"translate(10,20)\">"));
+        assert!(
+            svg.contains("d=\"M 0,0 L 1,0 L 1,1 Z\" fill=\"rgb(255,0,0)\" fill-opacity=\"0.5\"")
+        );
+        assert!(svg.contains("clip-path=\"url(#clip0)\""));
+        assert!(svg.contains("font-size=\"9\"") && svg.contains("rotate(-30, 5, 6)"));
+        assert!(svg.contains(">a &lt; b</text>"));
+        assert_eq!(svg.matches("</g>").count(), 2);
+    }
+
+    #[test]
+    fn test_svg_text_uses_measured_font() {
+        let family = text::main_family().unwrap();
+        let mut svg = SvgRenderer::new();
+        svg.text(0.0, 0.0, "tick", &TextStyle::new(10.0, Color::BLACK));
+        svg.text(
+            0.0,
+            0.0,
+            "mono",
+            &TextStyle::new(10.0, Color::BLACK).font_family("monospace"),
+        );
+
+        let svg = svg.finish();
+        assert!(svg.contains(&format!("font-family=\"{}\"", family)));
+        assert!(svg.contains("font-family=\"monospace\""));
+    }
+}
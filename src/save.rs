@@ -0,0 +1,300 @@
+//! Saving figures to files in several formats
+//!
+//! [`Figure::savefig`](crate::figure::Figure::savefig) picks the format from
+//! the file extension; [`SaveOptions`] sets it explicitly along with the
+//! resolution, tight cropping, a transparent background and metadata.
+
+use std::fmt;
+use std::path::Path;
+
+use crate::pdf::PdfError;
+use crate::raster::RasterError;
+use crate::render::escape_xml;
+
+/// Output file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    Svg,
+    Png,
+    Pdf,
+}
+
+impl Format {
+    /// The format of files with extension `extension`, ignoring case
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        match extension.to_ascii_lowercase().as_str() {
+            "svg" => Some(Format::Svg),
+            "png" => Some(Format::Png),
+            "pdf" => Some(Format::Pdf),
+            _ => None,
+        }
+    }
+
+    /// The format of the file at `path`, from its extension
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Format, SaveError> {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .ok_or(SaveError::MissingExtension)?;
+        Format::from_extension(extension)
+            .ok_or_else(|| SaveError::UnsupportedFormat(extension.to_string()))
+    }
+
+    /// The usual file extension
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::Png => "png",
+            Format::Pdf => "pdf",
+        }
+    }
+}
+
+/// Document information stored in the saved file
+///
+/// SVG keeps it as a title, Dublin Core metadata and a "Created with"
+/// comment, PNG as text chunks and PDF in the document information dictionary.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Vec<String>,
+    /// Application that made the figure
+    pub creator: Option<String>,
+}
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = Some(title.to_string());
+        self
+    }
+
+    pub fn author(mut self, author: &str) -> Self {
+        self.author = Some(author.to_string());
+        self
+    }
+
+    pub fn subject(mut self, subject: &str) -> Self {
+        self.subject = Some(subject.to_string());
+        self
+    }
+
+    pub fn keywords(mut self, keywords: &[&str]) -> Self {
+        self.keywords = keywords.iter().map(|k| k.to_string()).collect();
+        self
+    }
+
+    pub fn creator(mut self, creator: &str) -> Self {
+        self.creator = Some(creator.to_string());
+        self
+    }
+
+    /// Entries that are set, under their conventional names
+    pub(crate) fn entries(&self) -> Vec<(&'static str, String)> {
+        let keywords = (!self.keywords.is_empty()).then(|| self.keywords.join(", "));
+        [
+            ("Title", self.title.clone()),
+            ("Author", self.author.clone()),
+            ("Subject", self.subject.clone()),
+            ("Keywords", keywords),
+            ("Creator", self.creator.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect()
+    }
+
+    /// `<title>` and `<metadata>` elements for an SVG document, with the
+    /// creator as a comment since Dublin Core has no element for it
+    pub(crate) fn to_svg(&self) -> String {
+        let mut svg = String::new();
+        if let Some(creator) = &self.creator {
+            let mut creator = creator.clone();
+            while creator.contains("--") {
+                creator = creator.replace("--", "- -");
+            }
+            svg.push_str(&format!("<!-- Created with {} -->\n", creator));
+        }
+        if let Some(title) = &self.title {
+            svg.push_str(&format!("<title>{}</title>\n", escape_xml(title)));
+        }
+        let elements: Vec<String> = self
+            .entries()
+            .into_iter()
+            .filter_map(|(key, value)| {
+                let element = match key {
+                    "Title" => "title",
+                    "Author" => "creator",
+                    "Subject" => "description",
+                    "Keywords" => "subject",
+                    _ => return None,
+                };
+                Some(format!("<dc:{0}>{1}</dc:{0}>", element, escape_xml(&value)))
+            })
+            .collect();
+        if !elements.is_empty() {
+            svg.push_str(&format!(
+                "<metadata><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\"><rdf:Description>{}</rdf:Description></rdf:RDF></metadata>\n",
+                elements.concat()
+            ));
+        }
+        svg
+    }
+}
+
+/// How [`Figure::savefig_with`](crate::figure::Figure::savefig_with) writes a figure
+#[derive(Debug, Clone, PartialEq)]
+pub struct SaveOptions {
+    /// Format to write; taken from the file extension if unset
+    pub format: Option<Format>,
+    /// Resolution of raster output; the figure's own if unset
+    pub dpi: Option<f64>,
+    /// Crop to the drawn content instead of the whole figure
+    pub bbox_tight: bool,
+    /// Space kept around the content when cropping, in figure pixels
+    pub pad: f64,
+    /// Whether to leave backgrounds unpainted; the figure's own setting if unset
+    pub transparent: Option<bool>,
+    pub metadata: Metadata,
+}
+
+impl SaveOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = Some(format);
+        self
+    }
+
+    pub fn dpi(mut self, dpi: f64) -> Self {
+        self.dpi = Some(dpi);
+        self
+    }
+
+    /// Crop to the drawn content plus `pad` figure pixels on each side
+    pub fn bbox_tight(mut self, pad: f64) -> Self {
+        self.bbox_tight = true;
+        self.pad = pad.max(0.0);
+        self
+    }
+
+    pub fn transparent(mut self, transparent: bool) -> Self {
+        self.transparent = Some(transparent);
+        self
+    }
+
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        SaveOptions {
+            format: None,
+            dpi: None,
+            bbox_tight: false,
+            pad: 10.0,
+            transparent: None,
+            metadata: Metadata::default(),
+        }
+    }
+}
+
+/// Error saving a figure
+#[derive(Debug)]
+pub enum SaveError {
+    /// The path has no extension to choose a format by
+    MissingExtension,
+    /// No format is known for the extension
+    UnsupportedFormat(String),
+    Raster(RasterError),
+    Pdf(PdfError),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::MissingExtension => {
+                write!(f, "cannot choose a format for a path without an extension")
+            }
+            SaveError::UnsupportedFormat(extension) => {
+                write!(f, "unsupported file format: {}", extension)
+            }
+            SaveError::Raster(error) => error.fmt(f),
+            SaveError::Pdf(error) => error.fmt(f),
+            SaveError::Io(error) => write!(f, "cannot write figure: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SaveError::MissingExtension | SaveError::UnsupportedFormat(_) => None,
+            // Displayed as their own message
+            SaveError::Raster(error) => error.source(),
+            SaveError::Pdf(error) => error.source(),
+            SaveError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<RasterError> for SaveError {
+    fn from(error: RasterError) -> Self {
+        SaveError::Raster(error)
+    }
+}
+
+impl From<PdfError> for SaveError {
+    fn from(error: PdfError) -> Self {
+        SaveError::Pdf(error)
+    }
+}
+
+impl From<std::io::Error> for SaveError {
+    fn from(error: std::io::Error) -> Self {
+        SaveError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_path() {
+        assert_eq!(Format::from_path("plots/a.PNG").unwrap(), Format::Png);
+        assert_eq!(Format::from_path("a.pdf").unwrap(), Format::Pdf);
+        assert!(matches!(
+            Format::from_path("a"),
+            Err(SaveError::MissingExtension)
+        ));
+        assert!(matches!(
+            Format::from_path("a.bmp"),
+            Err(SaveError::UnsupportedFormat(extension)) if extension == "bmp"
+        ));
+    }
+
+    #[test]
+    fn test_metadata_svg() {
+        let svg = Metadata::new()
+            .author("Ann")
+            .creator("plotiron--demo")
+            .to_svg();
+        assert!(svg.contains("<!-- Created with plotiron- -demo -->"));
+        assert!(svg.contains("<dc:creator>Ann</dc:creator>"));
+        assert!(!svg.contains("publisher"));
+    }
+}
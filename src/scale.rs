@@ -0,0 +1,802 @@
+//! Axis scales and the mapping from data to pixel coordinates
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::utils::{format_number, generate_ticks, map_range};
+
+/// Value a clipped non-positive (or, for logit, out of range) value is
+/// transformed to: far outside any sensible axis range
+const CLIPPED: f64 = 1000.0;
+
+/// Most major ticks a log-like locator places before skipping decades
+const MAX_DECADE_TICKS: usize = 9;
+
+/// What happens to values a scale cannot represent, such as zero on a log axis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NonPositive {
+    /// Drop them: lines break and markers are skipped
+    Mask,
+    /// Send them far past the low (or high) end of the axis, so bars
+    /// starting at zero reach the edge of the plotting area
+    Clip,
+}
+
+/// A mapping between data values and the linear space an axis is drawn in
+///
+/// Implement this to define a custom axis scale and install it with
+/// [`Scale::custom`]; every plot type, the grid and the tick labels go
+/// through it. Only `forward` and `inverse` are required.
+pub trait Transform: fmt::Debug + Send + Sync {
+    /// Transform a data value to the linear axis space; NaN if it cannot be shown
+    fn forward(&self, value: f64) -> f64;
+
+    /// Transform a value of the linear axis space back to data
+    fn inverse(&self, value: f64) -> f64;
+
+    /// Whether `value` can be placed on the axis without masking or clipping
+    fn is_valid(&self, value: f64) -> bool {
+        value.is_finite() && self.forward(value).is_finite()
+    }
+
+    /// Major tick values between `min` and `max` (in either order)
+    ///
+    /// Defaults to nice round data values.
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        let (min, max) = (min.min(max), min.max(max));
+        generate_ticks(min, max, 8)
+            .into_iter()
+            .filter(|&v| v >= min && v <= max && self.is_valid(v))
+            .collect()
+    }
+
+    /// Label of a tick at `value`
+    fn format_tick(&self, value: f64) -> String {
+        format_number(value)
+    }
+
+    /// Text shown at the end of the axis that applies to every label of `ticks`
+    fn offset_text(&self, _ticks: &[f64]) -> Option<String> {
+        None
+    }
+}
+
+/// How data values are spread along an axis
+///
+/// Whatever the scale, values that cannot be represented are ignored when the
+/// axis limits are computed from the data.
+#[derive(Debug, Clone, Default)]
+pub enum Scale {
+    #[default]
+    Linear,
+    /// Logarithmic in any base; only positive values are representable
+    Log { base: f64, nonpositive: NonPositive },
+    /// Linear within `linthresh` of zero and logarithmic beyond it, with the
+    /// linear region drawn `linscale` decades wide
+    SymLog {
+        base: f64,
+        linthresh: f64,
+        linscale: f64,
+    },
+    /// Log-odds `log10(p / (1 - p))`; only values in (0, 1) are representable
+    Logit { nonpositive: NonPositive },
+    /// A user-defined transform
+    Custom(Arc<dyn Transform>),
+}
+
+impl Scale {
+    /// Base 10 logarithmic scale
+    ///
+    /// Non-positive values are clipped, like all log scales, so bars and
+    /// fills starting at zero still reach the bottom of the axis.
+    pub fn log() -> Self {
+        Scale::log_base(10.0)
+    }
+
+    /// Logarithmic scale in the given base, which must be greater than 1
+    ///
+    /// Non-positive values are clipped, so bars and fills starting at zero
+    /// still reach the bottom of the axis.
+    pub fn try_log_base(base: f64) -> Result<Self, ScaleError> {
+        check_base(base)?;
+        Ok(Scale::Log {
+            base,
+            nonpositive: NonPositive::Clip,
+        })
+    }
+
+    /// Logarithmic scale in the given base, falling back to base 10 if
+    /// `base` is not greater than 1
+    ///
+    /// Use [`try_log_base`](Scale::try_log_base) to reject a bad base
+    /// instead. Non-positive values are clipped, as with [`Scale::log`].
+    pub fn log_base(base: f64) -> Self {
+        let base = if check_base(base).is_ok() { base } else { 10.0 };
+        Scale::Log {
+            base,
+            nonpositive: NonPositive::Clip,
+        }
+    }
+
+    /// Base 10 symmetric log scale, linear within `linthresh` of zero
+    ///
+    /// A `linthresh` that is not positive and finite falls back to 1.
+    pub fn symlog(linthresh: f64) -> Self {
+        Scale::symlog_with(10.0, linthresh, 1.0)
+    }
+
+    /// Symmetric log scale in `base`, linear within `linthresh` of zero
+    ///
+    /// The linear region is drawn as wide as `linscale` decades. `base` must
+    /// be greater than 1, `linthresh` and `linscale` positive and finite.
+    pub fn try_symlog_with(base: f64, linthresh: f64, linscale: f64) -> Result<Self, ScaleError> {
+        check_base(base)?;
+        if !(linthresh > 0.0 && linthresh.is_finite()) {
+            return Err(ScaleError::InvalidLinthresh(linthresh));
+        }
+        if !(linscale > 0.0 && linscale.is_finite()) {
+            return Err(ScaleError::InvalidLinscale(linscale));
+        }
+        Ok(Scale::SymLog {
+            base,
+            linthresh,
+            linscale,
+        })
+    }
+
+    /// Symmetric log scale in `base`, linear within `linthresh` of zero
+    ///
+    /// Out of range parameters fall back to base 10, a `linthresh` of 1 and
+    /// a `linscale` of 1; use [`try_symlog_with`](Scale::try_symlog_with) to
+    /// reject them instead.
+    pub fn symlog_with(base: f64, linthresh: f64, linscale: f64) -> Self {
+        let positive = |v: f64| v > 0.0 && v.is_finite();
+        Scale::SymLog {
+            base: if check_base(base).is_ok() { base } else { 10.0 },
+            linthresh: if positive(linthresh) { linthresh } else { 1.0 },
+            linscale: if positive(linscale) { linscale } else { 1.0 },
+        }
+    }
+
+    /// Logit scale for probabilities and other fractions
+    ///
+    /// Unlike log scales, values outside (0, 1) are masked rather than
+    /// clipped: clipping would draw them at both far ends of the axis, and a
+    /// probability of exactly 0 or 1 has no baseline to reach.
+    pub fn logit() -> Self {
+        Scale::Logit {
+            nonpositive: NonPositive::Mask,
+        }
+    }
+
+    /// Scale backed by a user-defined transform
+    pub fn custom<T: Transform + 'static>(transform: T) -> Self {
+        Scale::Custom(Arc::new(transform))
+    }
+
+    /// Scale defined by a pair of functions, e.g. `Scale::func(|x| 1.0 / x, |x| 1.0 / x)`
+    pub fn func<F, I>(forward: F, inverse: I) -> Self
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+        I: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        Scale::custom(FuncTransform::new(forward, inverse))
+    }
+
+    /// Set the policy for values the scale cannot represent
+    pub fn nonpositive(mut self, policy: NonPositive) -> Self {
+        match self {
+            Scale::Log {
+                ref mut nonpositive,
+                ..
+            }
+            | Scale::Logit {
+                ref mut nonpositive,
+            } => *nonpositive = policy,
+            Scale::Linear | Scale::SymLog { .. } | Scale::Custom(_) => {}
+        }
+        self
+    }
+
+    /// Transform a data value to the linear axis space; NaN if it cannot be shown
+    pub fn forward(&self, value: f64) -> f64 {
+        match *self {
+            Scale::Linear => value,
+            Scale::Log { base, nonpositive } => {
+                if value.is_nan() {
+                    f64::NAN
+                } else if value > 0.0 {
+                    logb(value, base)
+                } else {
+                    match nonpositive {
+                        NonPositive::Mask => f64::NAN,
+                        NonPositive::Clip => -CLIPPED,
+                    }
+                }
+            }
+            Scale::SymLog {
+                base,
+                linthresh,
+                linscale,
+            } => {
+                let a = value.abs() / linthresh;
+                let y = if a <= 1.0 {
+                    a * linscale
+                } else {
+                    linscale + logb(a, base)
+                };
+                y.copysign(value)
+            }
+            Scale::Logit { nonpositive } => {
+                if value.is_nan() {
+                    f64::NAN
+                } else if value > 0.0 && value < 1.0 {
+                    (value / (1.0 - value)).log10()
+                } else {
+                    match nonpositive {
+                        NonPositive::Mask => f64::NAN,
+                        NonPositive::Clip if value <= 0.0 => -CLIPPED,
+                        NonPositive::Clip => CLIPPED,
+                    }
+                }
+            }
+            Scale::Custom(ref transform) => transform.forward(value),
+        }
+    }
+
+    /// Transform a value of the linear axis space back to data
+    pub fn inverse(&self, value: f64) -> f64 {
+        match *self {
+            Scale::Linear => value,
+            Scale::Log { base, .. } => base.powf(value),
+            Scale::SymLog {
+                base,
+                linthresh,
+                linscale,
+            } => {
+                let a = value.abs();
+                let v = if a <= linscale {
+                    a / linscale
+                } else {
+                    base.powf(a - linscale)
+                };
+                (v * linthresh).copysign(value)
+            }
+            Scale::Logit { .. } => {
+                let odds = 10f64.powf(value);
+                odds / (1.0 + odds)
+            }
+            Scale::Custom(ref transform) => transform.inverse(value),
+        }
+    }
+
+    /// Whether `value` can be placed on the axis without masking or clipping
+    pub fn is_valid(&self, value: f64) -> bool {
+        match *self {
+            Scale::Linear | Scale::SymLog { .. } => value.is_finite(),
+            Scale::Log { .. } => value.is_finite() && value > 0.0,
+            Scale::Logit { .. } => value > 0.0 && value < 1.0,
+            Scale::Custom(ref transform) => transform.is_valid(value),
+        }
+    }
+
+    /// Major tick values between `min` and `max` (in either order)
+    pub fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        let (min, max) = (min.min(max), min.max(max));
+        let ticks = match *self {
+            Scale::Linear => generate_ticks(min, max, 10),
+            Scale::Log { base, .. } => log_ticks(base, min, max),
+            Scale::SymLog {
+                base, linthresh, ..
+            } => symlog_ticks(base, linthresh, min, max),
+            Scale::Logit { .. } => logit_ticks(min, max),
+            Scale::Custom(ref transform) => return transform.ticks(min, max),
+        };
+        if ticks.len() >= 2 || matches!(self, Scale::Linear) {
+            return ticks;
+        }
+
+        // Less than a decade (or too narrow for the logit ladder): nice linear ticks
+        generate_ticks(min, max, 6)
+            .into_iter()
+            .filter(|&v| v >= min && v <= max && self.is_valid(v))
+            .collect()
+    }
+
+    /// Label of a tick at `value`; decades on log-like scales are written as powers like 10³
+    pub fn format_tick(&self, value: f64) -> String {
+        match *self {
+            Scale::Linear => format_number(value),
+            Scale::Log { base, .. } => {
+                decade_label(base, value).unwrap_or_else(|| format_number(value))
+            }
+            Scale::SymLog { base, .. } => {
+                if value == 0.0 {
+                    return "0".to_string();
+                }
+                match decade_label(base, value.abs()) {
+                    Some(label) if value < 0.0 => format!("-{}", label),
+                    Some(label) => label,
+                    None => format_number(value),
+                }
+            }
+            Scale::Logit { .. } => {
+                if value == 0.5 {
+                    "1/2".to_string()
+                } else if value < 0.5 {
+                    decade_label(10.0, value).unwrap_or_else(|| format_number(value))
+                } else {
+                    match decade_label(10.0, 1.0 - value) {
+                        Some(label) => format!("1-{}", label),
+                        None => format_number(value),
+                    }
+                }
+            }
+            Scale::Custom(ref transform) => transform.format_tick(value),
+        }
+    }
+
+    /// Text shown at the end of the axis that applies to every label of `ticks`
+    ///
+    /// Only custom scales have one, e.g. the date of a date axis showing times of day.
+    pub fn offset_text(&self, ticks: &[f64]) -> Option<String> {
+        match *self {
+            Scale::Custom(ref transform) => transform.offset_text(ticks),
+            _ => None,
+        }
+    }
+}
+
+impl Transform for Scale {
+    fn forward(&self, value: f64) -> f64 {
+        Scale::forward(self, value)
+    }
+
+    fn inverse(&self, value: f64) -> f64 {
+        Scale::inverse(self, value)
+    }
+
+    fn is_valid(&self, value: f64) -> bool {
+        Scale::is_valid(self, value)
+    }
+
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        Scale::ticks(self, min, max)
+    }
+
+    fn format_tick(&self, value: f64) -> String {
+        Scale::format_tick(self, value)
+    }
+
+    fn offset_text(&self, ticks: &[f64]) -> Option<String> {
+        Scale::offset_text(self, ticks)
+    }
+}
+
+impl PartialEq for Scale {
+    /// Custom scales are equal only to clones of themselves
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Scale::Linear, Scale::Linear) => true,
+            (
+                Scale::Log {
+                    base: b1,
+                    nonpositive: n1,
+                },
+                Scale::Log {
+                    base: b2,
+                    nonpositive: n2,
+                },
+            ) => b1 == b2 && n1 == n2,
+            (
+                Scale::SymLog {
+                    base: b1,
+                    linthresh: t1,
+                    linscale: s1,
+                },
+                Scale::SymLog {
+                    base: b2,
+                    linthresh: t2,
+                    linscale: s2,
+                },
+            ) => b1 == b2 && t1 == t2 && s1 == s2,
+            (Scale::Logit { nonpositive: n1 }, Scale::Logit { nonpositive: n2 }) => n1 == n2,
+            (Scale::Custom(t1), Scale::Custom(t2)) => Arc::ptr_eq(t1, t2),
+            _ => false,
+        }
+    }
+}
+
+/// A transform defined by a pair of closures, see [`Scale::func`]
+#[derive(Clone)]
+pub struct FuncTransform {
+    forward: Arc<dyn Fn(f64) -> f64 + Send + Sync>,
+    inverse: Arc<dyn Fn(f64) -> f64 + Send + Sync>,
+}
+
+impl FuncTransform {
+    /// Create a transform from its forward function and that function's inverse
+    pub fn new<F, I>(forward: F, inverse: I) -> Self
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+        I: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        FuncTransform {
+            forward: Arc::new(forward),
+            inverse: Arc::new(inverse),
+        }
+    }
+}
+
+impl fmt::Debug for FuncTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FuncTransform")
+    }
+}
+
+impl Transform for FuncTransform {
+    fn forward(&self, value: f64) -> f64 {
+        (self.forward)(value)
+    }
+
+    fn inverse(&self, value: f64) -> f64 {
+        (self.inverse)(value)
+    }
+}
+
+impl FromStr for Scale {
+    type Err = ScaleError;
+
+    /// Scale from its matplotlib name, e.g. `"symlog"`
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "linear" => Ok(Scale::Linear),
+            "log" => Scale::try_log_base(10.0),
+            "symlog" => Scale::try_symlog_with(10.0, 1.0, 1.0),
+            "logit" => Ok(Scale::logit()),
+            _ => Err(ScaleError::Unknown(name.to_string())),
+        }
+    }
+}
+
+/// Error parsing or building a scale
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScaleError {
+    /// No scale has this name
+    Unknown(String),
+    /// A log base that is not greater than 1
+    InvalidBase(f64),
+    /// A symlog linear threshold that is not positive and finite
+    InvalidLinthresh(f64),
+    /// A symlog linear region width that is not positive and finite
+    InvalidLinscale(f64),
+}
+
+impl fmt::Display for ScaleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScaleError::Unknown(name) => write!(f, "unknown scale: {}", name),
+            ScaleError::InvalidBase(base) => {
+                write!(f, "log scale base must be greater than 1, got {}", base)
+            }
+            ScaleError::InvalidLinthresh(linthresh) => {
+                write!(f, "symlog linthresh must be positive, got {}", linthresh)
+            }
+            ScaleError::InvalidLinscale(linscale) => {
+                write!(f, "symlog linscale must be positive, got {}", linscale)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScaleError {}
+
+fn check_base(base: f64) -> Result<(), ScaleError> {
+    if base > 1.0 && base.is_finite() {
+        Ok(())
+    } else {
+        Err(ScaleError::InvalidBase(base))
+    }
+}
+
+/// Logarithm of `value` in `base`, exact for whole powers of 10 and 2
+pub(crate) fn logb(value: f64, base: f64) -> f64 {
+    if base == 10.0 {
+        value.log10()
+    } else if base == 2.0 {
+        value.log2()
+    } else {
+        value.ln() / base.ln()
+    }
+}
+
+/// Exponent of `value` if it is a whole power of `base`
+fn decade(base: f64, value: f64) -> Option<i32> {
+    if value <= 0.0 {
+        return None;
+    }
+    let exponent = logb(value, base);
+    let rounded = exponent.round();
+    ((exponent - rounded).abs() < 1e-9).then_some(rounded as i32)
+}
+
+/// `base` raised to a superscript exponent, e.g. 10³ or 2⁻⁴
+fn decade_label(base: f64, value: f64) -> Option<String> {
+    let exponent = decade(base, value)?;
+    let base = if (base - std::f64::consts::E).abs() < 1e-12 {
+        "e".to_string()
+    } else {
+        format_number(base)
+    };
+    Some(format!("{}{}", base, superscript(exponent)))
+}
+
+pub(crate) fn superscript(value: i32) -> String {
+    value
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            '-' => '⁻',
+            '0' => '⁰',
+            '1' => '¹',
+            '2' => '²',
+            '3' => '³',
+            '4' => '⁴',
+            '5' => '⁵',
+            '6' => '⁶',
+            '7' => '⁷',
+            '8' => '⁸',
+            _ => '⁹',
+        })
+        .collect()
+}
+
+/// Every `step`-th exponent in `low..=high`, with `step` keeping the count readable
+fn thinned(low: i32, high: i32) -> impl Iterator<Item = i32> {
+    let count = (high - low + 1).max(0) as usize;
+    let step = count.div_ceil(MAX_DECADE_TICKS).max(1) as i32;
+    (low..=high).filter(move |e| e.rem_euclid(step) == 0)
+}
+
+pub(crate) fn log_ticks(base: f64, min: f64, max: f64) -> Vec<f64> {
+    if max <= 0.0 || base <= 1.0 {
+        return Vec::new();
+    }
+    let min = if min > 0.0 { min } else { max * 1e-6 };
+    let low = (logb(min, base) - 1e-9).ceil() as i32;
+    let high = (logb(max, base) + 1e-9).floor() as i32;
+    thinned(low, high).map(|e| base.powi(e)).collect()
+}
+
+fn symlog_ticks(base: f64, linthresh: f64, min: f64, max: f64) -> Vec<f64> {
+    // Decades start at the first whole power of the base beyond the linear region
+    let first = (logb(linthresh, base) - 1e-9).ceil() as i32;
+    let last = |limit: f64| (logb(limit, base) + 1e-9).floor() as i32;
+    let mut ticks = Vec::new();
+
+    if min < 0.0 {
+        ticks.extend(
+            thinned(first, last(-min))
+                .map(|e| -base.powi(e))
+                .filter(|&v| v <= max),
+        );
+    }
+    if min <= 0.0 && max >= 0.0 {
+        ticks.push(0.0);
+    }
+    if max > 0.0 {
+        ticks.extend(
+            thinned(first, last(max))
+                .map(|e| base.powi(e))
+                .filter(|&v| v >= min),
+        );
+    }
+
+    ticks.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    ticks
+}
+
+fn logit_ticks(min: f64, max: f64) -> Vec<f64> {
+    let mut ticks: Vec<f64> = Vec::new();
+    for k in (1..=15).rev() {
+        ticks.push(10f64.powi(-k));
+    }
+    ticks.push(0.5);
+    for k in 1..=15 {
+        ticks.push(1.0 - 10f64.powi(-k));
+    }
+    ticks.retain(|&v| v >= min && v <= max);
+
+    // Keep the middle and every other decade on wide ranges
+    if ticks.len() > MAX_DECADE_TICKS {
+        ticks.retain(|&v| {
+            let distance = if v < 0.5 { v } else { 1.0 - v };
+            v == 0.5 || decade(10.0, distance).is_some_and(|e| e % 2 == 1)
+        });
+    }
+    ticks
+}
+
+/// Maps data coordinates to pixels inside the plotting area
+///
+/// The x axis runs from the left edge at `x_limits.0` to `width`, the y axis
+/// from the bottom edge at `y_limits.0` up to 0 (SVG y grows downwards).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlotArea {
+    pub x_scale: Scale,
+    pub y_scale: Scale,
+    pub x_limits: (f64, f64),
+    pub y_limits: (f64, f64),
+    pub width: f64,
+    pub height: f64,
+}
+
+impl PlotArea {
+    /// A plotting area with linear scales on both axes
+    pub fn linear(x_limits: (f64, f64), y_limits: (f64, f64), width: f64, height: f64) -> Self {
+        PlotArea {
+            x_scale: Scale::Linear,
+            y_scale: Scale::Linear,
+            x_limits,
+            y_limits,
+            width,
+            height,
+        }
+    }
+
+    /// Horizontal pixel position of the data value `x`
+    pub fn x(&self, x: f64) -> f64 {
+        let s = &self.x_scale;
+        let (min, max) = self.x_limits;
+        map_range(
+            s.forward(x),
+            s.forward(min),
+            s.forward(max),
+            0.0,
+            self.width,
+        )
+    }
+
+    /// Vertical pixel position of the data value `y`
+    pub fn y(&self, y: f64) -> f64 {
+        let s = &self.y_scale;
+        let (min, max) = self.y_limits;
+        map_range(
+            s.forward(y),
+            s.forward(min),
+            s.forward(max),
+            self.height,
+            0.0,
+        ) // Flip Y axis
+    }
+
+    /// Pixel position of the data point `(x, y)`
+    pub fn point(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.x(x), self.y(y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_scale() {
+        let scale = Scale::log();
+        assert_eq!(scale.forward(1000.0), 3.0);
+        assert!((scale.inverse(2.0) - 100.0).abs() < 1e-9);
+        assert_eq!(scale.forward(0.0), -CLIPPED);
+        assert!(
+            scale
+                .clone()
+                .nonpositive(NonPositive::Mask)
+                .forward(0.0)
+                .is_nan()
+        );
+
+        assert_eq!(scale.ticks(0.5, 2000.0), vec![1.0, 10.0, 100.0, 1000.0]);
+        assert_eq!(scale.format_tick(1000.0), "10³");
+        assert_eq!(scale.format_tick(0.01), "10⁻²");
+        assert_eq!(Scale::log_base(2.0).format_tick(8.0), "2³");
+        assert_eq!(Scale::log_base(1.0).forward(100.0), 2.0);
+        assert_eq!(Scale::try_log_base(0.5), Err(ScaleError::InvalidBase(0.5)));
+        assert_eq!("log".parse(), Ok(Scale::log()));
+        assert_eq!(
+            "lg".parse::<Scale>(),
+            Err(ScaleError::Unknown("lg".to_string()))
+        );
+
+        // Wide ranges skip decades, narrow ones fall back to linear ticks
+        assert!(scale.ticks(1e-20, 1e20).len() <= MAX_DECADE_TICKS);
+        assert_eq!(scale.ticks(2.0, 8.0).first(), Some(&2.0));
+    }
+
+    #[test]
+    fn test_symlog_and_logit_scales() {
+        let scale = Scale::symlog(1.0);
+        assert_eq!(scale.forward(0.5), 0.5);
+        assert_eq!(scale.forward(-100.0), -3.0);
+        assert!((scale.inverse(scale.forward(-42.0)) + 42.0).abs() < 1e-9);
+        assert_eq!(
+            scale.ticks(-100.0, 10.0),
+            vec![-100.0, -10.0, -1.0, 0.0, 1.0, 10.0]
+        );
+        assert_eq!(scale.format_tick(-100.0), "-10²");
+
+        let scale = Scale::symlog_with(2.0, 0.5, 2.0);
+        assert_eq!(scale.forward(0.25), 1.0);
+        assert_eq!(scale.forward(-4.0), -5.0);
+        assert!((scale.inverse(scale.forward(3.0)) - 3.0).abs() < 1e-9);
+        assert_eq!(scale.ticks(0.0, 4.0), vec![0.0, 0.5, 1.0, 2.0, 4.0]);
+        assert_eq!(
+            Scale::try_symlog_with(10.0, 0.0, 1.0),
+            Err(ScaleError::InvalidLinthresh(0.0))
+        );
+        assert_eq!(
+            Scale::try_symlog_with(10.0, 1.0, -1.0),
+            Err(ScaleError::InvalidLinscale(-1.0))
+        );
+        assert_eq!(Scale::symlog(f64::NAN).forward(-100.0), -3.0);
+
+        let scale = Scale::logit();
+        assert_eq!(scale.forward(0.5), 0.0);
+        assert!((scale.inverse(scale.forward(0.99)) - 0.99).abs() < 1e-12);
+        assert!(scale.forward(1.0).is_nan());
+        assert_eq!(scale.ticks(0.005, 0.995), vec![0.01, 0.1, 0.5, 0.9, 0.99]);
+        assert_eq!(scale.format_tick(0.999), "1-10⁻³");
+    }
+
+    #[test]
+    fn test_custom_scale() {
+        // Reciprocal axis: small values are spread out and large ones crowded together
+        let scale = Scale::func(|x| 1.0 / x, |x| 1.0 / x);
+        let area = PlotArea {
+            x_scale: scale.clone(),
+            ..PlotArea::linear((1.0, 4.0), (0.0, 1.0), 100.0, 50.0)
+        };
+        assert_eq!(area.x(1.0), 0.0);
+        assert!((area.x(2.0) - 200.0 / 3.0).abs() < 1e-9);
+        assert!(!scale.is_valid(0.0));
+        assert_eq!(scale, scale.clone());
+        assert_ne!(scale, Scale::func(|x| 1.0 / x, |x| 1.0 / x));
+
+        #[derive(Debug)]
+        struct Squared;
+        impl Transform for Squared {
+            fn forward(&self, value: f64) -> f64 {
+                value * value
+            }
+            fn inverse(&self, value: f64) -> f64 {
+                value.sqrt()
+            }
+            fn ticks(&self, _min: f64, _max: f64) -> Vec<f64> {
+                vec![1.0, 2.0]
+            }
+        }
+        let scale = Scale::custom(Squared);
+        assert_eq!(scale.ticks(0.0, 3.0), vec![1.0, 2.0]);
+        assert_eq!(scale.format_tick(2.0), "2");
+    }
+
+    #[test]
+    fn test_plot_area() {
+        let area = PlotArea::linear((0.0, 10.0), (0.0, 10.0), 100.0, 50.0);
+        assert_eq!(area.point(5.0, 10.0), (50.0, 0.0));
+
+        let area = PlotArea {
+            x_scale: Scale::log(),
+            ..PlotArea::linear((1.0, 100.0), (0.0, 1.0), 100.0, 50.0)
+        };
+        assert_eq!(area.x(10.0), 50.0);
+    }
+
+    #[test]
+    fn test_nan_is_masked_not_clipped() {
+        // Missing data leaves a gap instead of a spike to the edge of the axes
+        for scale in [Scale::log(), Scale::logit().nonpositive(NonPositive::Clip)] {
+            assert!(scale.forward(f64::NAN).is_nan());
+            assert!(!scale.is_valid(f64::NAN));
+        }
+    }
+}
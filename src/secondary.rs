@@ -0,0 +1,106 @@
+//! Secondary axes showing the data in converted units
+
+use std::sync::Arc;
+
+use crate::scale::{FuncTransform, Transform};
+use crate::ticker::{Formatter, Locator, TickLabelStyle, Ticker};
+use crate::utils::{format_number, generate_ticks};
+
+/// An extra axis on the opposite side showing the primary axis in other units
+///
+/// The forward function converts primary values to the secondary units, e.g.
+/// Celsius to Fahrenheit, and the inverse function converts them back. Ticks
+/// are chosen in the secondary units and placed where they fall on the
+/// primary axis.
+#[derive(Debug, Clone)]
+pub struct SecondaryAxis {
+    pub conversion: FuncTransform,
+    pub label: Option<String>,
+    pub ticker: Ticker,
+}
+
+impl SecondaryAxis {
+    /// Create an axis converting primary values with `forward` and back with `inverse`
+    pub fn new<F, I>(forward: F, inverse: I) -> Self
+    where
+        F: Fn(f64) -> f64 + Send + Sync + 'static,
+        I: Fn(f64) -> f64 + Send + Sync + 'static,
+    {
+        SecondaryAxis {
+            conversion: FuncTransform::new(forward, inverse),
+            label: None,
+            ticker: Ticker::default(),
+        }
+    }
+
+    /// Set the axis label
+    pub fn set_label(&mut self, label: &str) -> &mut Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    /// Place ticks with a locator working in the secondary units
+    pub fn set_locator<L: Locator + 'static>(&mut self, locator: L) -> &mut Self {
+        self.ticker.locator = Some(Arc::new(locator));
+        self
+    }
+
+    /// Write tick labels with a formatter
+    pub fn set_formatter<F: Formatter + 'static>(&mut self, formatter: F) -> &mut Self {
+        self.ticker.formatter = Some(Arc::new(formatter));
+        self
+    }
+
+    /// Set the rotation, font and color of the tick labels
+    pub fn set_ticklabel_style(&mut self, style: TickLabelStyle) -> &mut Self {
+        self.ticker.label_style = style;
+        self
+    }
+
+    /// Ticks as `(primary value, label)` pairs for a primary axis spanning `limits`
+    pub(crate) fn ticks(&self, limits: (f64, f64)) -> Vec<(f64, String)> {
+        let (a, b) = (
+            self.conversion.forward(limits.0),
+            self.conversion.forward(limits.1),
+        );
+        let (low, high) = (a.min(b), a.max(b));
+        if !(low.is_finite() && high.is_finite()) {
+            return Vec::new();
+        }
+
+        let tolerance = (high - low) * 1e-9;
+        let values: Vec<f64> = match self.ticker.locator {
+            Some(ref locator) => locator.ticks(low, high),
+            None => generate_ticks(low, high, 9)
+                .into_iter()
+                .filter(|&t| t >= low - tolerance && t <= high + tolerance)
+                .collect(),
+        };
+        let labels: Vec<String> = match self.ticker.formatter {
+            Some(ref formatter) => formatter.format_ticks(&values),
+            None => values.iter().map(|&v| format_number(v)).collect(),
+        };
+
+        values
+            .iter()
+            .map(|&v| self.conversion.inverse(v))
+            .zip(labels)
+            .filter(|(v, _)| v.is_finite())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secondary_ticks() {
+        let fahrenheit = SecondaryAxis::new(|c| c * 1.8 + 32.0, |f| (f - 32.0) / 1.8);
+        let ticks = fahrenheit.ticks((0.0, 100.0));
+        // Ticks are round in Fahrenheit, placed where they fall in Celsius
+        assert_eq!(ticks.first().map(|t| t.1.as_str()), Some("50"));
+        assert_eq!(ticks.last().map(|t| t.1.as_str()), Some("200"));
+        assert!((ticks[0].0 - 10.0).abs() < 1e-9);
+    }
+}
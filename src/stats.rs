@@ -0,0 +1,145 @@
+//! Statistical helpers shared by the statistical plot types
+
+/// Return a sorted copy of the finite values in `data`
+pub fn sorted_finite(data: &[f64]) -> Vec<f64> {
+    let mut sorted: Vec<f64> = data.iter().copied().filter(|v| v.is_finite()).collect();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted
+}
+
+/// Percentile (0-100) of sorted data using linear interpolation
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+
+    let rank = (p.clamp(0.0, 100.0) / 100.0) * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    let t = rank - lower as f64;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * t
+}
+
+/// Arithmetic mean
+pub fn mean(data: &[f64]) -> f64 {
+    if data.is_empty() {
+        return f64::NAN;
+    }
+    data.iter().sum::<f64>() / data.len() as f64
+}
+
+/// Sample standard deviation (with Bessel's correction)
+pub fn std_dev(data: &[f64]) -> f64 {
+    if data.len() < 2 {
+        return 0.0;
+    }
+    let m = mean(data);
+    let var = data.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (data.len() - 1) as f64;
+    var.sqrt()
+}
+
+/// Bandwidth selection for kernel density estimation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bandwidth {
+    /// Scott's rule: `std * n^(-1/5)`
+    Scott,
+    /// Silverman's rule: `std * (3n/4)^(-1/5)`
+    Silverman,
+    /// A fixed kernel standard deviation
+    Fixed(f64),
+}
+
+/// Gaussian kernel density estimate of a one dimensional sample
+#[derive(Debug, Clone)]
+pub struct GaussianKde {
+    data: Vec<f64>,
+    bandwidth: f64,
+}
+
+impl GaussianKde {
+    /// Build a KDE over the finite values of `data`
+    ///
+    /// Returns `None` if `data` has no finite values.
+    pub fn new(data: &[f64], bandwidth: Bandwidth) -> Option<Self> {
+        let data = sorted_finite(data);
+        if data.is_empty() {
+            return None;
+        }
+
+        let n = data.len() as f64;
+        let std = std_dev(&data);
+        let mut bandwidth = match bandwidth {
+            Bandwidth::Scott => std * n.powf(-0.2),
+            Bandwidth::Silverman => std * (n * 0.75).powf(-0.2),
+            Bandwidth::Fixed(h) => h,
+        };
+        // Constant samples would collapse the kernel to a spike
+        if bandwidth <= 0.0 || !bandwidth.is_finite() {
+            bandwidth = if data[0] == 0.0 {
+                1.0
+            } else {
+                data[0].abs() * 0.1
+            };
+        }
+
+        Some(GaussianKde { data, bandwidth })
+    }
+
+    /// The kernel standard deviation in data units
+    pub fn bandwidth(&self) -> f64 {
+        self.bandwidth
+    }
+
+    /// Smallest and largest sample value
+    pub fn data_range(&self) -> (f64, f64) {
+        (self.data[0], self.data[self.data.len() - 1])
+    }
+
+    /// Estimated probability density at `x`
+    pub fn evaluate(&self, x: f64) -> f64 {
+        let h = self.bandwidth;
+        let norm = 1.0 / (self.data.len() as f64 * h * (2.0 * std::f64::consts::PI).sqrt());
+        self.data
+            .iter()
+            .map(|&xi| (-0.5 * ((x - xi) / h).powi(2)).exp())
+            .sum::<f64>()
+            * norm
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile() {
+        let data = vec![1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&data, 0.0), 1.0);
+        assert_eq!(percentile(&data, 50.0), 2.5);
+        assert_eq!(percentile(&data, 100.0), 4.0);
+    }
+
+    #[test]
+    fn test_std_dev() {
+        let data = vec![2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(mean(&data), 5.0);
+        assert!((std_dev(&data) - 2.138089935299395).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gaussian_kde() {
+        let data = vec![-1.0, 0.0, 0.0, 1.0, 2.0];
+        let kde = GaussianKde::new(&data, Bandwidth::Scott).unwrap();
+        assert!((kde.bandwidth() - std_dev(&data) * 5f64.powf(-0.2)).abs() < 1e-12);
+
+        // The density integrates to one
+        let step = 0.01;
+        let area: f64 = (-2000..2000)
+            .map(|i| kde.evaluate(i as f64 * step) * step)
+            .sum();
+        assert!((area - 1.0).abs() < 1e-6);
+
+        let kde = GaussianKde::new(&data, Bandwidth::Silverman).unwrap();
+        assert!(kde.bandwidth() > std_dev(&data) * 5f64.powf(-0.2));
+    }
+}
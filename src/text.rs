@@ -0,0 +1,367 @@
+//! Text measurement from font metrics
+//!
+//! Labels are measured with the advance widths, ascent and descent of a real
+//! font. A default font is bundled; [`set_font`] replaces it with a TrueType
+//! or OpenType font of your own, and [`add_fallback_font`] adds fonts for
+//! characters it lacks, e.g. CJK. Characters no font covers are estimated.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use ttf_parser::Face;
+
+/// A TrueType or OpenType font
+#[derive(Clone)]
+pub struct Font {
+    data: Arc<dyn AsRef<[u8]> + Send + Sync>,
+    index: u32,
+}
+
+impl Font {
+    /// Load a font from the bytes of a font file, using the first face of a collection
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, FontError> {
+        Font::from_shared(Arc::new(data))
+    }
+
+    /// Load a font from a `.ttf`, `.otf` or `.ttc` file
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, FontError> {
+        Font::from_bytes(std::fs::read(path)?)
+    }
+
+    /// The proportional sans-serif font labels are measured with by default
+    pub fn default_font() -> Self {
+        Font {
+            data: Arc::new(epaint_default_fonts::UBUNTU_LIGHT),
+            index: 0,
+        }
+    }
+
+    /// The bundled font for emoji
+    fn emoji() -> Self {
+        Font {
+            data: Arc::new(epaint_default_fonts::NOTO_EMOJI_REGULAR),
+            index: 0,
+        }
+    }
+
+    fn from_shared(data: Arc<dyn AsRef<[u8]> + Send + Sync>) -> Result<Self, FontError> {
+        Face::parse((*data).as_ref(), 0)?;
+        Ok(Font { data, index: 0 })
+    }
+
+    /// Raw bytes of the font file
+    pub fn data(&self) -> &[u8] {
+        (*self.data).as_ref()
+    }
+
+    pub(crate) fn shared_data(&self) -> Arc<dyn AsRef<[u8]> + Send + Sync> {
+        self.data.clone()
+    }
+
+    fn face(&self) -> Face<'_> {
+        // Checked when the font was loaded
+        Face::parse(self.data(), self.index).expect("font was validated on load")
+    }
+
+    /// Family name, e.g. `"Ubuntu"`, preferring the typographic family over the legacy one
+    pub fn family_name(&self) -> Option<String> {
+        let face = self.face();
+        let name = |id: u16| {
+            face.names()
+                .into_iter()
+                .filter(|name| name.name_id == id && name.is_unicode())
+                .find_map(|name| name.to_string())
+        };
+        name(ttf_parser::name_id::TYPOGRAPHIC_FAMILY).or_else(|| name(ttf_parser::name_id::FAMILY))
+    }
+
+    /// Metrics of `text` at `font_size` in this font alone, estimating characters it lacks
+    pub fn measure(&self, text: &str, font_size: f64) -> TextMetrics {
+        measure_with(std::slice::from_ref(self), text, font_size)
+    }
+}
+
+impl fmt::Debug for Font {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Font")
+            .field("family", &self.family_name())
+            .field("bytes", &self.data().len())
+            .finish()
+    }
+}
+
+/// Error loading a font
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    Parse(ttf_parser::FaceParsingError),
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "cannot read font: {}", error),
+            FontError::Parse(error) => write!(f, "invalid font: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for FontError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FontError::Io(error) => Some(error),
+            FontError::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl From<std::io::Error> for FontError {
+    fn from(error: std::io::Error) -> Self {
+        FontError::Io(error)
+    }
+}
+
+impl From<ttf_parser::FaceParsingError> for FontError {
+    fn from(error: ttf_parser::FaceParsingError) -> Self {
+        FontError::Parse(error)
+    }
+}
+
+/// Size of a run of text in pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMetrics {
+    /// Sum of the advance widths
+    pub width: f64,
+    /// Height above the baseline
+    pub ascent: f64,
+    /// Depth below the baseline, as a positive distance
+    pub descent: f64,
+}
+
+impl TextMetrics {
+    pub fn height(&self) -> f64 {
+        self.ascent + self.descent
+    }
+}
+
+/// Fonts chosen by the user, consulted before the bundled ones
+struct FontSet {
+    primary: Option<Font>,
+    fallbacks: Vec<Font>,
+    /// Metrics of the fonts in use, dropped whenever they change
+    metrics: Option<Metrics>,
+}
+
+impl FontSet {
+    fn fonts(&self) -> Vec<Font> {
+        let mut fonts = vec![self.main_font()];
+        fonts.extend(self.fallbacks.iter().cloned());
+        fonts.push(Font::emoji());
+        fonts
+    }
+
+    fn main_font(&self) -> Font {
+        self.primary.clone().unwrap_or_else(Font::default_font)
+    }
+
+    /// Change the fonts with `change`, forgetting the metrics of the old ones
+    fn update(&mut self, change: impl FnOnce(&mut Self)) {
+        change(self);
+        self.metrics = None;
+    }
+
+    fn metrics(&mut self) -> &mut Metrics {
+        let main = self.main_font();
+        self.metrics
+            .get_or_insert_with(|| Metrics::new(&main.face()))
+    }
+}
+
+/// Metrics of a set of fonts in ems, with the advances of the characters measured so far
+struct Metrics {
+    ascent: f64,
+    descent: f64,
+    advances: HashMap<char, f64>,
+}
+
+impl Metrics {
+    fn new(main: &Face) -> Self {
+        let em = main.units_per_em() as f64;
+        Metrics {
+            ascent: main.ascender() as f64 / em,
+            descent: -(main.descender() as f64) / em,
+            advances: HashMap::new(),
+        }
+    }
+
+    /// Metrics of text `width` ems wide at `font_size`
+    fn scaled(&self, width: f64, font_size: f64) -> TextMetrics {
+        TextMetrics {
+            width: width * font_size,
+            ascent: self.ascent * font_size,
+            descent: self.descent * font_size,
+        }
+    }
+}
+
+static FONTS: RwLock<FontSet> = RwLock::new(FontSet {
+    primary: None,
+    fallbacks: Vec::new(),
+    metrics: None,
+});
+
+/// Measure text with `font` instead of the bundled default font
+pub fn set_font(font: Font) {
+    FONTS
+        .write()
+        .unwrap()
+        .update(|set| set.primary = Some(font));
+}
+
+/// Measure characters missing from the main font with `font`, after earlier fallbacks
+pub fn add_fallback_font(font: Font) {
+    FONTS
+        .write()
+        .unwrap()
+        .update(|set| set.fallbacks.push(font));
+}
+
+/// Go back to measuring with the bundled fonts only
+pub fn reset_fonts() {
+    FONTS.write().unwrap().update(|set| {
+        set.primary = None;
+        set.fallbacks.clear();
+    });
+}
+
+/// The fonts text is measured with, in the order they are tried
+pub fn fonts() -> Vec<Font> {
+    FONTS.read().unwrap().fonts()
+}
+
+/// Family name of the main font text is measured with
+pub(crate) fn main_family() -> Option<String> {
+    FONTS.read().unwrap().main_font().family_name()
+}
+
+/// Metrics of `text` at `font_size`
+///
+/// Each character is measured with the first font that has it. Ascent and
+/// descent are those of the main font.
+pub fn measure(text: &str, font_size: f64) -> TextMetrics {
+    if let Some(ref metrics) = FONTS.read().unwrap().metrics {
+        let width: Option<f64> = text.chars().map(|c| metrics.advances.get(&c)).sum();
+        if let Some(width) = width {
+            return metrics.scaled(width, font_size);
+        }
+    }
+
+    // Only text with characters not measured before needs the fonts parsed
+    let mut set = FONTS.write().unwrap();
+    let fonts = set.fonts();
+    let faces: Vec<Face> = fonts.iter().map(Font::face).collect();
+    let metrics = set.metrics();
+    let width = text
+        .chars()
+        .map(|c| {
+            *metrics
+                .advances
+                .entry(c)
+                .or_insert_with(|| advance(&faces, c))
+        })
+        .sum();
+    metrics.scaled(width, font_size)
+}
+
+fn measure_with(fonts: &[Font], text: &str, font_size: f64) -> TextMetrics {
+    let faces: Vec<Face> = fonts.iter().map(Font::face).collect();
+    let width = text.chars().map(|c| advance(&faces, c)).sum();
+    Metrics::new(&faces[0]).scaled(width, font_size)
+}
+
+/// Advance in ems of `c` in the first of `faces` that has it
+fn advance(faces: &[Face], c: char) -> f64 {
+    faces
+        .iter()
+        .find_map(|face| {
+            let glyph = face.glyph_index(c)?;
+            Some(face.glyph_hor_advance(glyph)? as f64 / face.units_per_em() as f64)
+        })
+        .unwrap_or_else(|| estimated_advance(c))
+}
+
+/// Advance in ems of a character no font has
+fn estimated_advance(c: char) -> f64 {
+    match c as u32 {
+        // Zero width joiners and variation selectors
+        0x200B..=0x200D | 0xFE00..=0xFE0F => 0.0,
+        // Full width CJK, Hangul and fullwidth forms
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1FAFF
+        | 0x20000..=0x3FFFD => 1.0,
+        _ => 0.6,
+    }
+}
+
+/// Width of `text` in pixels
+pub(crate) fn text_width(text: &str, font_size: f64) -> f64 {
+    measure(text, font_size).width
+}
+
+/// Height of text above its baseline
+pub(crate) fn ascent(font_size: f64) -> f64 {
+    vertical_metrics().0 * font_size
+}
+
+/// Depth of text below its baseline
+pub(crate) fn descent(font_size: f64) -> f64 {
+    vertical_metrics().1 * font_size
+}
+
+/// Ascent and descent of the main font in ems
+fn vertical_metrics() -> (f64, f64) {
+    if let Some(ref metrics) = FONTS.read().unwrap().metrics {
+        return (metrics.ascent, metrics.descent);
+    }
+    let mut set = FONTS.write().unwrap();
+    let metrics = set.metrics();
+    (metrics.ascent, metrics.descent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure() {
+        let font = Font::default_font();
+        assert_eq!(font.family_name().as_deref(), Some("Ubuntu"));
+
+        // Proportional widths, scaling with the font size
+        let narrow = font.measure("iii", 10.0);
+        let wide = font.measure("WWW", 10.0);
+        assert!(narrow.width < wide.width / 2.0);
+        assert!((font.measure("WWW", 20.0).width - 2.0 * wide.width).abs() < 1e-9);
+        assert!(narrow.ascent > 7.0 && narrow.descent > 1.0 && narrow.height() < 13.0);
+
+        // CJK falls back to full width estimates and emoji to the bundled emoji font
+        assert_eq!(font.measure("温度", 10.0).width, 20.0);
+        assert!(measure("🙂", 10.0).width > 5.0);
+
+        // Measuring again reads the cached metrics
+        let text = "Wi 🙂 温";
+        assert_eq!(measure(text, 12.0), measure(text, 12.0));
+        let direct = measure_with(&fonts(), text, 12.0);
+        assert!((measure(text, 12.0).width - direct.width).abs() < 1e-9);
+        assert!((ascent(12.0) - direct.ascent).abs() < 1e-9);
+
+        assert!(Font::from_bytes(b"not a font".to_vec()).is_err());
+    }
+}
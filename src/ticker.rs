@@ -0,0 +1,1002 @@
+//! Tick locators and formatters
+//!
+//! A [`Locator`] decides where the ticks of an axis go and a [`Formatter`]
+//! writes their labels. Axes without either fall back to their scale.
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::colors::Color;
+use crate::render::{Style, TextStyle};
+use crate::scale::{Scale, log_ticks, logb, superscript};
+use crate::utils::format_number;
+
+/// Most decimals shared tick labels are written with
+const MAX_DECIMALS: usize = 6;
+/// Most ticks a locator places, guarding against tiny steps on wide ranges
+const MAX_TICKS: usize = 1000;
+
+/// Chooses tick positions along an axis
+pub trait Locator: fmt::Debug + Send + Sync {
+    /// Tick values between `min` and `max`, in increasing order
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64>;
+}
+
+/// Writes tick labels
+pub trait Formatter: fmt::Debug + Send + Sync {
+    /// Label of a single value
+    fn format(&self, value: f64) -> String;
+
+    /// Labels of all ticks shown on an axis
+    ///
+    /// Formatters whose labels depend on the other ticks, e.g. to share a
+    /// precision or an offset, override this.
+    fn format_ticks(&self, ticks: &[f64]) -> Vec<String> {
+        ticks.iter().map(|&t| self.format(t)).collect()
+    }
+
+    /// Text shown at the end of the axis that applies to every label, e.g. `×10⁶`
+    fn offset_text(&self, _ticks: &[f64]) -> Option<String> {
+        None
+    }
+}
+
+/// At most `bins` intervals with a step from a list of nice multiples
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaxNLocator {
+    pub bins: usize,
+    /// Step multiples of a power of ten, in increasing order
+    pub steps: Vec<f64>,
+    /// Only place ticks on whole numbers
+    pub integer: bool,
+}
+
+impl MaxNLocator {
+    /// Create a locator with at most `bins` intervals between ticks
+    pub fn new(bins: usize) -> Self {
+        MaxNLocator {
+            bins: bins.max(1),
+            steps: vec![1.0, 2.0, 2.5, 5.0, 10.0],
+            integer: false,
+        }
+    }
+
+    /// Set the step multiples, e.g. `[1.0, 5.0, 10.0]`
+    pub fn steps(mut self, steps: Vec<f64>) -> Self {
+        self.steps = steps;
+        self
+    }
+
+    /// Only place ticks on whole numbers
+    pub fn integer(mut self, integer: bool) -> Self {
+        self.integer = integer;
+        self
+    }
+}
+
+impl Locator for MaxNLocator {
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        let (min, max) = (min.min(max), min.max(max));
+        if !(min.is_finite() && max.is_finite()) {
+            return Vec::new();
+        }
+        if min == max {
+            return vec![min];
+        }
+
+        let raw_step = (max - min) / self.bins as f64;
+        let magnitude = 10f64.powf(raw_step.log10().floor());
+        let mut step = self
+            .steps
+            .iter()
+            .map(|s| s * magnitude)
+            .find(|&s| s >= raw_step * (1.0 - 1e-9))
+            .unwrap_or(10.0 * magnitude);
+        if self.integer {
+            step = step.ceil().max(1.0);
+        }
+        stepped(step, 0.0, min, max)
+    }
+}
+
+/// Ticks on every multiple of `base`, shifted by `offset`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultipleLocator {
+    pub base: f64,
+    pub offset: f64,
+}
+
+impl MultipleLocator {
+    pub fn new(base: f64) -> Self {
+        MultipleLocator { base, offset: 0.0 }
+    }
+
+    /// Shift the ticks off the multiples of the base
+    pub fn offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+}
+
+impl Locator for MultipleLocator {
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        let (min, max) = (min.min(max), min.max(max));
+        if !(self.base > 0.0 && min.is_finite() && max.is_finite()) {
+            return Vec::new();
+        }
+        stepped(self.base, self.offset, min, max)
+    }
+}
+
+/// Ticks at given values
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedLocator {
+    pub values: Vec<f64>,
+}
+
+impl FixedLocator {
+    pub fn new(values: Vec<f64>) -> Self {
+        FixedLocator { values }
+    }
+}
+
+impl Locator for FixedLocator {
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        let (min, max) = (min.min(max), min.max(max));
+        let tolerance = (max - min).abs() * 1e-9;
+        let mut ticks: Vec<f64> = self
+            .values
+            .iter()
+            .copied()
+            .filter(|&v| v >= min - tolerance && v <= max + tolerance)
+            .collect();
+        ticks.sort_by(f64::total_cmp);
+        ticks
+    }
+}
+
+/// Ticks at whole powers of a base, and optionally at multiples within each decade
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogLocator {
+    pub base: f64,
+    /// Multiples of each power to place ticks at; only the powers themselves if `[1.0]`
+    pub subs: Vec<f64>,
+}
+
+impl LogLocator {
+    pub fn new(base: f64) -> Self {
+        LogLocator {
+            base,
+            subs: vec![1.0],
+        }
+    }
+
+    /// Place ticks at these multiples of every power, e.g. `[1.0, 2.0, 5.0]`
+    pub fn subs(mut self, subs: Vec<f64>) -> Self {
+        self.subs = subs;
+        self
+    }
+}
+
+impl Default for LogLocator {
+    fn default() -> Self {
+        Self::new(10.0)
+    }
+}
+
+impl Locator for LogLocator {
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        let (min, max) = (min.min(max), min.max(max));
+        if self.subs == [1.0] {
+            return log_ticks(self.base, min, max);
+        }
+        if max <= 0.0 || self.base <= 1.0 {
+            return Vec::new();
+        }
+
+        let min = if min > 0.0 { min } else { max * 1e-6 };
+        let low = logb(min, self.base).floor() as i32;
+        let high = logb(max, self.base).ceil() as i32;
+        let mut ticks: Vec<f64> = (low..=high)
+            .flat_map(|e| self.subs.iter().map(move |s| s * self.base.powi(e)))
+            .filter(|&v| v >= min * (1.0 - 1e-9) && v <= max * (1.0 + 1e-9))
+            .take(MAX_TICKS)
+            .collect();
+        ticks.sort_by(f64::total_cmp);
+        ticks.dedup();
+        ticks
+    }
+}
+
+/// Nice ticks for linear axes, with up to about ten intervals
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AutoLocator;
+
+impl Locator for AutoLocator {
+    fn ticks(&self, min: f64, max: f64) -> Vec<f64> {
+        MaxNLocator::new(9).ticks(min, max)
+    }
+}
+
+/// Plain decimal labels with the same number of decimals on every tick
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScalarFormatter {
+    /// Fixed number of decimals; the fewest that tell the ticks apart if unset
+    pub decimals: Option<usize>,
+}
+
+impl ScalarFormatter {
+    pub fn new() -> Self {
+        ScalarFormatter { decimals: None }
+    }
+
+    /// Always write this many decimals
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+}
+
+impl Formatter for ScalarFormatter {
+    fn format(&self, value: f64) -> String {
+        match self.decimals {
+            Some(decimals) => fixed(value, decimals),
+            None => format_number(value),
+        }
+    }
+
+    fn format_ticks(&self, ticks: &[f64]) -> Vec<String> {
+        let decimals = self.decimals.unwrap_or_else(|| shared_decimals(ticks));
+        ticks.iter().map(|&t| fixed(t, decimals)).collect()
+    }
+}
+
+/// Labels in percent of a maximum value
+#[derive(Debug, Clone, PartialEq)]
+pub struct PercentFormatter {
+    /// Value shown as 100%
+    pub xmax: f64,
+    /// Fixed number of decimals; the fewest that tell the ticks apart if unset
+    pub decimals: Option<usize>,
+    pub symbol: String,
+}
+
+impl PercentFormatter {
+    /// Create a formatter showing `xmax` as 100%
+    pub fn new(xmax: f64) -> Self {
+        PercentFormatter {
+            xmax,
+            decimals: None,
+            symbol: "%".to_string(),
+        }
+    }
+
+    /// Always write this many decimals
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    /// Set the symbol written after the number
+    pub fn symbol(mut self, symbol: &str) -> Self {
+        self.symbol = symbol.to_string();
+        self
+    }
+
+    fn percent(&self, value: f64) -> f64 {
+        value / self.xmax * 100.0
+    }
+}
+
+impl Formatter for PercentFormatter {
+    fn format(&self, value: f64) -> String {
+        let percent = self.percent(value);
+        let number = match self.decimals {
+            Some(decimals) => fixed(percent, decimals),
+            None => format_number(percent),
+        };
+        format!("{}{}", number, self.symbol)
+    }
+
+    fn format_ticks(&self, ticks: &[f64]) -> Vec<String> {
+        let percents: Vec<f64> = ticks.iter().map(|&t| self.percent(t)).collect();
+        let decimals = self.decimals.unwrap_or_else(|| shared_decimals(&percents));
+        percents
+            .iter()
+            .map(|&p| format!("{}{}", fixed(p, decimals), self.symbol))
+            .collect()
+    }
+}
+
+/// SI prefixes by power of ten
+const SI_PREFIXES: [(i32, &str); 17] = [
+    (-24, "y"),
+    (-21, "z"),
+    (-18, "a"),
+    (-15, "f"),
+    (-12, "p"),
+    (-9, "n"),
+    (-6, "µ"),
+    (-3, "m"),
+    (0, ""),
+    (3, "k"),
+    (6, "M"),
+    (9, "G"),
+    (12, "T"),
+    (15, "P"),
+    (18, "E"),
+    (21, "Z"),
+    (24, "Y"),
+];
+
+/// Engineering labels with an SI prefix, e.g. `1.5 kHz`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngFormatter {
+    pub unit: String,
+    /// Fixed number of decimals; up to three without trailing zeros if unset
+    pub places: Option<usize>,
+    /// Text between the number and the prefixed unit
+    pub separator: String,
+}
+
+impl EngFormatter {
+    /// Create a formatter for values in `unit`, which may be empty
+    pub fn new(unit: &str) -> Self {
+        EngFormatter {
+            unit: unit.to_string(),
+            places: None,
+            separator: " ".to_string(),
+        }
+    }
+
+    /// Always write this many decimals
+    pub fn places(mut self, places: usize) -> Self {
+        self.places = Some(places);
+        self
+    }
+
+    /// Set the text between the number and the prefixed unit
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    fn number(&self, value: f64) -> String {
+        match self.places {
+            Some(places) => fixed(value, places),
+            None => trimmed(value, 3),
+        }
+    }
+}
+
+impl Formatter for EngFormatter {
+    fn format(&self, value: f64) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        let mut exponent = if value == 0.0 {
+            0
+        } else {
+            (value.abs().log10().floor() as i32).div_euclid(3) * 3
+        };
+        exponent = exponent.clamp(-24, 24);
+        let mut number = self.number(value / 10f64.powi(exponent));
+        // Rounding can carry the mantissa up to the next prefix, e.g. 999.9996 to 1000
+        if exponent < 24 && self.number(1000.0) == number.trim_start_matches('-') {
+            exponent += 3;
+            number = self.number(value / 10f64.powi(exponent));
+        }
+
+        let prefix = SI_PREFIXES
+            .iter()
+            .find(|(e, _)| *e == exponent)
+            .map_or("", |(_, p)| p);
+        let suffix = format!("{}{}", prefix, self.unit);
+        if suffix.is_empty() {
+            number
+        } else {
+            format!("{}{}{}", number, self.separator, suffix)
+        }
+    }
+}
+
+/// Scientific labels sharing a power of ten, and an offset for narrow ranges
+///
+/// Ticks 1000000, 1000500 and 1001000 are labelled 0.0, 0.5 and 1.0 with the
+/// offset text `×10³ +10⁶`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScientificFormatter {
+    /// Subtract a common offset when ticks are close together relative to their size
+    pub use_offset: bool,
+    /// Fixed number of decimals; the fewest that tell the ticks apart if unset
+    pub decimals: Option<usize>,
+}
+
+impl ScientificFormatter {
+    pub fn new() -> Self {
+        ScientificFormatter {
+            use_offset: true,
+            decimals: None,
+        }
+    }
+
+    /// Enable or disable the common offset
+    pub fn use_offset(mut self, use_offset: bool) -> Self {
+        self.use_offset = use_offset;
+        self
+    }
+
+    /// Always write this many decimals
+    pub fn decimals(mut self, decimals: usize) -> Self {
+        self.decimals = Some(decimals);
+        self
+    }
+
+    /// The offset subtracted from the ticks and the power of ten they are written in
+    fn offset_and_exponent(&self, ticks: &[f64]) -> (f64, i32) {
+        let finite = ticks.iter().copied().filter(|t| t.is_finite());
+        let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), t| {
+            (lo.min(t), hi.max(t))
+        });
+        if min > max {
+            return (0.0, 0);
+        }
+
+        let range = max - min;
+        let mut offset = 0.0;
+        if self.use_offset && range > 0.0 {
+            // Keep the digits that change between ticks, drop the ones that don't
+            let unit = 10f64.powi(range.log10().floor() as i32 + 1);
+            let candidate = (min / unit).floor() * unit;
+            if candidate.abs() >= range * 1e3 {
+                offset = candidate;
+            }
+        }
+
+        let largest = (min - offset).abs().max((max - offset).abs());
+        let exponent = if largest > 0.0 {
+            largest.log10().floor() as i32
+        } else {
+            0
+        };
+        (offset, exponent)
+    }
+}
+
+impl Default for ScientificFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Formatter for ScientificFormatter {
+    fn format(&self, value: f64) -> String {
+        scientific(value)
+    }
+
+    fn format_ticks(&self, ticks: &[f64]) -> Vec<String> {
+        let (offset, exponent) = self.offset_and_exponent(ticks);
+        let scaled: Vec<f64> = ticks
+            .iter()
+            .map(|&t| (t - offset) / 10f64.powi(exponent))
+            .collect();
+        let decimals = self.decimals.unwrap_or_else(|| shared_decimals(&scaled));
+        scaled.iter().map(|&v| fixed(v, decimals)).collect()
+    }
+
+    fn offset_text(&self, ticks: &[f64]) -> Option<String> {
+        let (offset, exponent) = self.offset_and_exponent(ticks);
+        let mut parts = Vec::new();
+        if exponent != 0 {
+            parts.push(format!("×10{}", superscript(exponent)));
+        }
+        if offset != 0.0 {
+            let sign = if offset < 0.0 { '−' } else { '+' };
+            parts.push(format!("{}{}", sign, scientific(offset.abs())));
+        }
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Labels written by a closure
+#[derive(Clone)]
+pub struct FuncFormatter {
+    format: Arc<dyn Fn(f64) -> String + Send + Sync>,
+}
+
+impl FuncFormatter {
+    pub fn new<F>(format: F) -> Self
+    where
+        F: Fn(f64) -> String + Send + Sync + 'static,
+    {
+        FuncFormatter {
+            format: Arc::new(format),
+        }
+    }
+}
+
+impl fmt::Debug for FuncFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("FuncFormatter")
+    }
+}
+
+impl Formatter for FuncFormatter {
+    fn format(&self, value: f64) -> String {
+        (self.format)(value)
+    }
+}
+
+/// Given labels for the ticks, see `Axes::set_xticklabels`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedFormatter {
+    pub labels: Vec<String>,
+    /// Tick values the labels belong to; the labels go to the shown ticks in order if unset
+    pub values: Option<Vec<f64>>,
+}
+
+impl FixedFormatter {
+    /// Label the shown ticks in order
+    pub fn new(labels: Vec<String>) -> Self {
+        FixedFormatter {
+            labels,
+            values: None,
+        }
+    }
+
+    /// Label the ticks at `values` with the label at the same index
+    pub fn paired(values: Vec<f64>, labels: Vec<String>) -> Self {
+        FixedFormatter {
+            labels,
+            values: Some(values),
+        }
+    }
+
+    /// Label given for the tick at `value`
+    fn label_of(&self, values: &[f64], value: f64) -> String {
+        values
+            .iter()
+            .position(|&v| (v - value).abs() <= v.abs().max(value.abs()) * 1e-9)
+            .and_then(|i| self.labels.get(i).cloned())
+            .unwrap_or_default()
+    }
+}
+
+impl Formatter for FixedFormatter {
+    fn format(&self, value: f64) -> String {
+        match self.values {
+            Some(ref values) => self.label_of(values, value),
+            None => format_number(value),
+        }
+    }
+
+    /// Ticks without a given label are left unlabelled
+    fn format_ticks(&self, ticks: &[f64]) -> Vec<String> {
+        match self.values {
+            Some(ref values) => ticks.iter().map(|&t| self.label_of(values, t)).collect(),
+            None => (0..ticks.len())
+                .map(|i| self.labels.get(i).cloned().unwrap_or_default())
+                .collect(),
+        }
+    }
+}
+
+/// Text settings of tick labels
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickLabelStyle {
+    /// Counter-clockwise rotation in degrees; long x labels are rotated automatically if unset
+    pub rotation: Option<f64>,
+    /// Font size; the axes font size if unset
+    pub font_size: Option<f64>,
+    /// Text color; the axes text color if unset
+    pub color: Option<Color>,
+    pub font_family: Option<String>,
+    pub bold: bool,
+}
+
+impl TickLabelStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rotate the labels counter-clockwise by `degrees`
+    pub fn rotation(mut self, degrees: f64) -> Self {
+        self.rotation = Some(degrees);
+        self
+    }
+
+    pub fn font_size(mut self, size: f64) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Set the font family, e.g. `"monospace"`
+    pub fn font_family(mut self, family: &str) -> Self {
+        self.font_family = Some(family.to_string());
+        self
+    }
+
+    pub fn bold(mut self, bold: bool) -> Self {
+        self.bold = bold;
+        self
+    }
+
+    /// Text style for the font, size and color, defaulting to `font_size` and `color`
+    pub(crate) fn text_style(&self, font_size: f64, color: Color) -> TextStyle {
+        let mut style = TextStyle::new(
+            self.font_size.unwrap_or(font_size),
+            self.color.unwrap_or(color),
+        )
+        .bold(self.bold);
+        style.font_family = self.font_family.clone();
+        style
+    }
+}
+
+/// Which side of the axis line tick marks are drawn on
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum TickDirection {
+    /// Into the plotting area
+    In,
+    /// Away from the plotting area
+    #[default]
+    Out,
+    /// Across the axis line
+    InOut,
+}
+
+impl TickDirection {
+    /// Lengths of a tick mark of `length` inside and outside the plotting area
+    pub(crate) fn extents(&self, length: f64) -> (f64, f64) {
+        match self {
+            TickDirection::In => (length, 0.0),
+            TickDirection::Out => (0.0, length),
+            TickDirection::InOut => (length / 2.0, length / 2.0),
+        }
+    }
+}
+
+/// Line style of grid lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridStyle {
+    /// Line color; the axes grid color if unset
+    pub color: Option<Color>,
+    pub width: f64,
+    /// Dash and gap lengths in pixels; solid if empty
+    pub dash: Vec<f64>,
+    pub alpha: f64,
+}
+
+impl GridStyle {
+    /// Thin solid lines, the style of the major grid
+    pub fn new() -> Self {
+        GridStyle {
+            color: None,
+            width: 0.3,
+            dash: Vec::new(),
+            alpha: 1.0,
+        }
+    }
+
+    /// Fainter dotted lines, the default style of the minor grid
+    pub fn minor() -> Self {
+        GridStyle {
+            color: None,
+            width: 0.3,
+            dash: vec![1.0, 2.0],
+            alpha: 0.6,
+        }
+    }
+
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn width(mut self, width: f64) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the dash pattern, e.g. `vec![4.0, 2.0]` for dashes of 4 pixels with gaps of 2
+    pub fn dash(mut self, dash: Vec<f64>) -> Self {
+        self.dash = dash;
+        self
+    }
+
+    pub fn alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha;
+        self
+    }
+
+    /// Style of the lines, in `color` unless the style has its own
+    pub(crate) fn line_style(&self, color: Color) -> Style {
+        Style::stroked(
+            self.color.unwrap_or(color).multiply_alpha(self.alpha),
+            self.width,
+        )
+        .dash(self.dash.clone())
+    }
+}
+
+impl Default for GridStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Tick settings of one axis
+#[derive(Debug, Clone)]
+pub struct Ticker {
+    /// Tick positions; chosen by the axis scale if unset
+    pub locator: Option<Arc<dyn Locator>>,
+    /// Tick labels; written by the axis scale if unset
+    pub formatter: Option<Arc<dyn Formatter>>,
+    pub label_style: TickLabelStyle,
+    /// Draw minor ticks; only on logarithmic axes if unset
+    pub minor: Option<bool>,
+    /// Minor tick positions; subdivisions of the major ticks if unset
+    pub minor_locator: Option<Arc<dyn Locator>>,
+    /// Length of major tick marks in pixels
+    pub major_length: f64,
+    /// Length of minor tick marks in pixels
+    pub minor_length: f64,
+    pub direction: TickDirection,
+    /// Values given to `set_xticks`, which tick labels are paired with
+    pub(crate) fixed_values: Option<Vec<f64>>,
+    /// Labels given to `set_xticklabels`
+    pub(crate) fixed_labels: Option<Vec<String>>,
+}
+
+impl Ticker {
+    /// Place ticks with `locator`, dropping fixed tick values and the labels given for them
+    pub(crate) fn set_locator(&mut self, locator: Arc<dyn Locator>) {
+        self.locator = Some(locator);
+        self.fixed_values = None;
+        // The formatter of fixed labels would leave the new ticks unlabeled
+        if self.fixed_labels.take().is_some() {
+            self.formatter = None;
+        }
+    }
+
+    /// Place ticks at `values`, keeping labels given before paired with them
+    pub(crate) fn set_fixed_values(&mut self, values: Vec<f64>) {
+        self.locator = Some(Arc::new(FixedLocator::new(values.clone())));
+        self.fixed_values = Some(values);
+        self.update_fixed_formatter();
+    }
+
+    /// Label the fixed ticks, or the shown ticks in order if there are none
+    pub(crate) fn set_fixed_labels(&mut self, labels: Vec<String>) {
+        self.fixed_labels = Some(labels);
+        self.update_fixed_formatter();
+    }
+
+    fn update_fixed_formatter(&mut self) {
+        let Some(ref labels) = self.fixed_labels else {
+            return;
+        };
+        let formatter = match self.fixed_values {
+            Some(ref values) => FixedFormatter::paired(values.clone(), labels.clone()),
+            None => FixedFormatter::new(labels.clone()),
+        };
+        self.formatter = Some(Arc::new(formatter));
+    }
+}
+
+impl Default for Ticker {
+    fn default() -> Self {
+        Ticker {
+            locator: None,
+            formatter: None,
+            label_style: TickLabelStyle::default(),
+            minor: None,
+            minor_locator: None,
+            major_length: 5.0,
+            minor_length: 2.5,
+            direction: TickDirection::Out,
+            fixed_values: None,
+            fixed_labels: None,
+        }
+    }
+}
+
+/// Minor ticks between (and beyond) the `majors` of an axis with `scale`
+///
+/// Logarithmic axes get ticks at the whole multiples within each decade,
+/// other axes divide the gap between major ticks into four or five parts.
+pub(crate) fn auto_minor_ticks(scale: &Scale, majors: &[f64], min: f64, max: f64) -> Vec<f64> {
+    let (min, max) = (min.min(max), min.max(max));
+    let is_major = |v: &f64| majors.iter().any(|m| (m - v).abs() <= m.abs() * 1e-9);
+
+    if let Scale::Log { base, .. } = *scale
+        && base.fract() == 0.0
+        && base > 2.0
+    {
+        let subs: Vec<f64> = (2..base as usize).map(|s| s as f64).collect();
+        let ticks = LogLocator::new(base).subs(subs).ticks(min, max);
+        // Past a dozen decades the multiples crowd into solid bands
+        if ticks.len() <= 12 * (base as usize - 2) {
+            return ticks.into_iter().filter(|v| !is_major(v)).collect();
+        }
+        return Vec::new();
+    }
+
+    // Subdivide evenly in the scale's transformed space
+    let scaled: Vec<f64> = majors.iter().map(|&m| scale.forward(m)).collect();
+    if scaled.len() < 2 {
+        return Vec::new();
+    }
+    let step = (scaled[1] - scaled[0]).abs();
+    if !(step.is_finite() && step > 0.0) {
+        return Vec::new();
+    }
+    let mantissa = step / 10f64.powf(step.log10().floor());
+    let divisions = if [1.0, 2.5, 5.0, 10.0]
+        .iter()
+        .any(|m| (mantissa - m).abs() < 1e-6)
+    {
+        5
+    } else {
+        4
+    };
+
+    let minor_step = step / divisions as f64;
+    let (low, high) = (scale.forward(min), scale.forward(max));
+    let (low, high) = (low.min(high), low.max(high));
+    stepped(minor_step, scaled[0], low, high)
+        .into_iter()
+        .map(|v| scale.inverse(v))
+        .filter(|v| v.is_finite() && !is_major(v))
+        .collect()
+}
+
+/// Multiples of `step` plus `offset` between `min` and `max`
+fn stepped(step: f64, offset: f64, min: f64, max: f64) -> Vec<f64> {
+    let tolerance = step * 1e-9;
+    let first = ((min - offset - tolerance) / step).ceil();
+    let last = ((max - offset + tolerance) / step).floor();
+    if last < first || last - first >= MAX_TICKS as f64 {
+        return Vec::new();
+    }
+    (first as i64..=last as i64)
+        .map(|i| {
+            let tick = i as f64 * step + offset;
+            // Rounding error around zero would otherwise be labelled like -0
+            if tick.abs() < tolerance { 0.0 } else { tick }
+        })
+        .collect()
+}
+
+/// Fewest decimals that write every value without rounding it
+fn shared_decimals(values: &[f64]) -> usize {
+    (0..MAX_DECIMALS)
+        .find(|&d| {
+            let factor = 10f64.powi(d as i32);
+            values
+                .iter()
+                .filter(|v| v.is_finite())
+                .all(|v| ((v * factor).round() - v * factor).abs() < 1e-6)
+        })
+        .unwrap_or(MAX_DECIMALS)
+}
+
+/// `value` with `decimals` decimals, without a sign on zero
+fn fixed(value: f64, decimals: usize) -> String {
+    let text = format!("{:.*}", decimals, value);
+    match text.strip_prefix('-') {
+        Some(rest) if rest.chars().all(|c| c == '0' || c == '.') => rest.to_string(),
+        _ => text,
+    }
+}
+
+/// `value` with up to `decimals` decimals and no trailing zeros
+fn trimmed(value: f64, decimals: usize) -> String {
+    let text = fixed(value, decimals);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+/// `value` as a mantissa times a power of ten, e.g. `1.5×10⁶`
+fn scientific(value: f64) -> String {
+    if value == 0.0 || !value.is_finite() {
+        return format_number(value);
+    }
+    let exponent = value.abs().log10().floor() as i32;
+    let mantissa = trimmed(value / 10f64.powi(exponent), 3);
+    match (exponent, mantissa.as_str()) {
+        (0, _) => mantissa,
+        (_, "1") => format!("10{}", superscript(exponent)),
+        (_, "-1") => format!("-10{}", superscript(exponent)),
+        _ => format!("{}×10{}", mantissa, superscript(exponent)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{Renderer, SvgRenderer};
+
+    #[test]
+    fn test_locators() {
+        assert_eq!(
+            MaxNLocator::new(4).ticks(0.0, 1.0),
+            vec![0.0, 0.25, 0.5, 0.75, 1.0]
+        );
+        assert_eq!(
+            MaxNLocator::new(4).integer(true).ticks(0.0, 2.0),
+            vec![0.0, 1.0, 2.0]
+        );
+        assert_eq!(
+            MultipleLocator::new(0.5).offset(0.25).ticks(0.0, 1.5),
+            vec![0.25, 0.75, 1.25]
+        );
+        assert_eq!(
+            FixedLocator::new(vec![3.0, 1.0, 10.0]).ticks(0.0, 5.0),
+            vec![1.0, 3.0]
+        );
+        assert_eq!(
+            LogLocator::default().subs(vec![1.0, 5.0]).ticks(1.0, 100.0),
+            vec![1.0, 5.0, 10.0, 50.0, 100.0]
+        );
+        let ticks = AutoLocator.ticks(0.0, 0.3);
+        assert!(ticks.contains(&0.3) || ticks.contains(&0.25));
+    }
+
+    #[test]
+    fn test_formatters() {
+        assert_eq!(
+            ScalarFormatter::new().format_ticks(&[0.0, 0.5, 1.0]),
+            ["0.0", "0.5", "1.0"]
+        );
+        assert_eq!(
+            PercentFormatter::new(1.0).format_ticks(&[0.0, 0.25, 1.0]),
+            ["0%", "25%", "100%"]
+        );
+        let eng = EngFormatter::new("Hz");
+        assert_eq!(eng.format(1500.0), "1.5 kHz");
+        assert_eq!(eng.format(0.002), "2 mHz");
+        assert_eq!(eng.format(999.9999), "1 kHz");
+        assert_eq!(EngFormatter::new("").format(12.0), "12");
+
+        let sci = ScientificFormatter::new();
+        let ticks = [1e6, 1.0005e6, 1.001e6];
+        assert_eq!(sci.format_ticks(&ticks), ["0.0", "0.5", "1.0"]);
+        assert_eq!(sci.offset_text(&ticks).as_deref(), Some("×10³ +10⁶"));
+        assert_eq!(sci.format_ticks(&[0.0, 2e6, 4e6]), ["0", "2", "4"]);
+        assert_eq!(sci.format(1.5e-3), "1.5×10⁻³");
+
+        let func = FuncFormatter::new(|v| format!("${}", v));
+        assert_eq!(func.format_ticks(&[1.0, 2.0]), ["$1", "$2"]);
+    }
+
+    #[test]
+    fn test_auto_minor_ticks() {
+        let minor = auto_minor_ticks(&Scale::Linear, &[0.0, 1.0, 2.0], 0.0, 2.0);
+        assert_eq!(minor.len(), 8);
+        assert!((minor[0] - 0.2).abs() < 1e-12);
+
+        let minor = auto_minor_ticks(&Scale::log(), &[1.0, 10.0, 100.0], 1.0, 100.0);
+        assert_eq!(minor.len(), 16);
+        assert_eq!(minor[..3], [2.0, 3.0, 4.0]);
+        assert!(!minor.contains(&10.0));
+
+        assert_eq!(TickDirection::InOut.extents(4.0), (2.0, 2.0));
+        let mut svg = SvgRenderer::new();
+        svg.line(
+            (0.0, 0.0),
+            (1.0, 0.0),
+            &GridStyle::minor().line_style(Color::BLACK),
+        );
+        assert!(svg.finish().contains("stroke-dasharray=\"1,2\""));
+    }
+}
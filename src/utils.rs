@@ -34,6 +34,31 @@ pub fn calculate_range<T: Float + Copy>(data: &[T]) -> (T, T) {
     (min_val, max_val)
 }
 
+/// Calculate the padded range of values without padding past sticky edges
+///
+/// Sticky edges are values the data should sit flush against, such as the
+/// baseline of a bar plot.
+pub fn calculate_range_sticky(data: &[f64], sticky: &[f64]) -> (f64, f64) {
+    let (mut min_val, mut max_val) = calculate_range(data);
+    if data.is_empty() {
+        return (min_val, max_val);
+    }
+
+    let data_min = data.iter().copied().fold(f64::INFINITY, f64::min);
+    let data_max = data.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    for &edge in sticky {
+        if edge <= data_min && edge > min_val {
+            min_val = edge;
+        }
+        if edge >= data_max && edge < max_val {
+            max_val = edge;
+        }
+    }
+
+    (min_val, max_val)
+}
+
 /// Generate nice tick values for an axis
 pub fn generate_ticks(min: f64, max: f64, target_count: usize) -> Vec<f64> {
     if min >= max || target_count == 0 {
@@ -115,6 +140,14 @@ mod tests {
         assert_eq!(max, 5.0);
     }
 
+    #[test]
+    fn test_calculate_range_sticky() {
+        let data = vec![0.0, 2.0, 4.0];
+        let (min, max) = calculate_range_sticky(&data, &[0.0]);
+        assert_eq!(min, 0.0);
+        assert!(max > 4.0);
+    }
+
     #[test]
     fn test_generate_ticks() {
         let ticks = generate_ticks(0.0, 10.0, 6);
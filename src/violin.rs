@@ -0,0 +1,269 @@
+//! Violin plots backed by kernel density estimation
+
+use crate::IntoVec;
+use crate::colors::Color;
+use crate::markers::Marker;
+use crate::plot::{Orientation, Plot, PlotType};
+use crate::render::{PathData, Renderer, Style};
+use crate::scale::PlotArea;
+use crate::stats::{Bandwidth, GaussianKde, percentile, sorted_finite};
+
+/// What is drawn inside a violin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViolinInner {
+    /// Only the density outline
+    None,
+    /// A thin box from Q1 to Q3 with a dot at the median
+    Box,
+    /// Dashed lines at the quartiles and the median
+    Quartiles,
+}
+
+/// Which half of the violin is drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViolinSide {
+    /// Mirrored density on both sides
+    Both,
+    /// Only the left (or lower, for horizontal violins) half
+    Low,
+    /// Only the right (or upper, for horizontal violins) half
+    High,
+}
+
+/// Violin plot specific settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViolinStyle {
+    pub position: f64,
+    /// Full width of the violin at its widest point
+    pub width: f64,
+    pub orientation: Orientation,
+    pub bandwidth: Bandwidth,
+    /// Number of points at which the density is evaluated
+    pub points: usize,
+    pub inner: ViolinInner,
+    pub side: ViolinSide,
+}
+
+impl ViolinStyle {
+    fn new() -> Self {
+        ViolinStyle {
+            position: 1.0,
+            width: 0.8,
+            orientation: Orientation::Vertical,
+            bandwidth: Bandwidth::Scott,
+            points: 100,
+            inner: ViolinInner::Box,
+            side: ViolinSide::Both,
+        }
+    }
+}
+
+/// Density outline of a violin: `(value, half_width)` pairs in data units
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViolinOutline {
+    pub values: Vec<f64>,
+    pub half_widths: Vec<f64>,
+}
+
+impl ViolinOutline {
+    /// Half width of the violin at `value`, linearly interpolated
+    pub fn half_width_at(&self, value: f64) -> f64 {
+        let i = self.values.partition_point(|&v| v < value);
+        if i == 0 {
+            return self.half_widths.first().copied().unwrap_or(0.0);
+        }
+        if i >= self.values.len() {
+            return self.half_widths.last().copied().unwrap_or(0.0);
+        }
+        let t = (value - self.values[i - 1]) / (self.values[i] - self.values[i - 1]);
+        self.half_widths[i - 1] + (self.half_widths[i] - self.half_widths[i - 1]) * t
+    }
+}
+
+impl Plot {
+    /// Create a new violin plot of the distribution of `data`
+    pub fn violin<D: IntoVec<f64>>(data: D) -> Self {
+        Plot {
+            x_data: Vec::new(),
+            y_data: data.into_vec(),
+            z_data: None,
+            plot_type: PlotType::Violin(ViolinStyle::new()),
+            color: None,
+            marker: Marker::None,
+            marker_size: 6.0,
+            line_width: 1.0,
+            label: None,
+            alpha: 0.7,
+            x_categories: None,
+            y_categories: None,
+        }
+    }
+
+    fn violin_style_mut(&mut self) -> Option<&mut ViolinStyle> {
+        match self.plot_type {
+            PlotType::Violin(ref mut style) => Some(style),
+            _ => None,
+        }
+    }
+
+    /// Set the full width of the violin
+    pub fn violin_width(mut self, width: f64) -> Self {
+        if let Some(style) = self.violin_style_mut() {
+            style.width = width;
+        }
+        self
+    }
+
+    /// Set the KDE bandwidth rule
+    pub fn bandwidth(mut self, bandwidth: Bandwidth) -> Self {
+        if let Some(style) = self.violin_style_mut() {
+            style.bandwidth = bandwidth;
+        }
+        self
+    }
+
+    /// Set what is drawn inside the violin
+    pub fn inner(mut self, inner: ViolinInner) -> Self {
+        if let Some(style) = self.violin_style_mut() {
+            style.inner = inner;
+        }
+        self
+    }
+
+    /// Draw only one half of the violin
+    pub fn side(mut self, side: ViolinSide) -> Self {
+        if let Some(style) = self.violin_style_mut() {
+            style.side = side;
+        }
+        self
+    }
+
+    /// Compute the density outline of a violin plot, `None` for other plots
+    ///
+    /// The density is evaluated between the smallest and largest sample and
+    /// scaled so that the widest point spans half of the violin width.
+    pub fn violin_outline(&self) -> Option<ViolinOutline> {
+        let PlotType::Violin(ref style) = self.plot_type else {
+            return None;
+        };
+        let kde = GaussianKde::new(&self.y_data, style.bandwidth)?;
+        let (low, high) = kde.data_range();
+        let points = style.points.max(2);
+
+        let values: Vec<f64> = if low < high {
+            (0..points)
+                .map(|i| low + (high - low) * i as f64 / (points - 1) as f64)
+                .collect()
+        } else {
+            vec![low, high]
+        };
+        let densities: Vec<f64> = values.iter().map(|&v| kde.evaluate(v)).collect();
+        let max = densities.iter().copied().fold(0.0, f64::max);
+        let scale = if max > 0.0 {
+            style.width / 2.0 / max
+        } else {
+            0.0
+        };
+
+        Some(ViolinOutline {
+            values,
+            half_widths: densities.iter().map(|d| d * scale).collect(),
+        })
+    }
+
+    pub(crate) fn draw_violin(
+        &self,
+        renderer: &mut dyn Renderer,
+        style: &ViolinStyle,
+        area: &PlotArea,
+    ) {
+        let Some(outline) = self.violin_outline() else {
+            return;
+        };
+
+        // Map (position, value) pairs to SVG coordinates based on orientation
+        let point = |pos: f64, value: f64| {
+            let (x, y) = match style.orientation {
+                Orientation::Vertical => (pos, value),
+                Orientation::Horizontal => (value, pos),
+            };
+            area.point(x, y)
+        };
+
+        let p = style.position;
+        let (low_factor, high_factor) = match style.side {
+            ViolinSide::Both => (1.0, 1.0),
+            ViolinSide::Low => (1.0, 0.0),
+            ViolinSide::High => (0.0, 1.0),
+        };
+
+        // Up the high side, then back down the low side
+        let mut points = Vec::with_capacity(outline.values.len() * 2);
+        for (&value, &half) in outline.values.iter().zip(&outline.half_widths) {
+            points.push(point(p + half * high_factor, value));
+        }
+        for (&value, &half) in outline.values.iter().zip(&outline.half_widths).rev() {
+            points.push(point(p - half * low_factor, value));
+        }
+        let color = self.plot_color();
+        renderer.path(
+            &PathData::polygon(&points),
+            &Style::filled(color.multiply_alpha(self.alpha)).stroke(color, self.line_width),
+        );
+
+        let sorted = sorted_finite(&self.y_data);
+        let quartiles = [25.0, 50.0, 75.0].map(|q| percentile(&sorted, q));
+        match style.inner {
+            ViolinInner::None => {}
+            ViolinInner::Box => {
+                // Box sits on the drawn side(s) of split violins
+                let box_half = style.width * 0.04;
+                let (x0, y0) = point(p - box_half * low_factor, quartiles[0]);
+                let (x1, y1) = point(p + box_half * high_factor, quartiles[2]);
+                renderer.rect(
+                    x0.min(x1),
+                    y0.min(y1),
+                    (x1 - x0).abs(),
+                    (y1 - y0).abs(),
+                    &Style::filled(Color::DARKGRAY),
+                );
+                let center = p + box_half * (high_factor - low_factor) / 2.0;
+                let (mx, my) = point(center, quartiles[1]);
+                renderer.circle(mx, my, self.marker_size / 2.0, &Style::filled(Color::WHITE));
+            }
+            ViolinInner::Quartiles => {
+                for (i, &q) in quartiles.iter().enumerate() {
+                    let half = outline.half_width_at(q);
+                    let (x0, y0) = point(p - half * low_factor, q);
+                    let (x1, y1) = point(p + half * high_factor, q);
+                    // The median is solid, the outer quartiles dashed
+                    let mut line = Style::stroked(Color::DARKGRAY, self.line_width);
+                    if i != 1 {
+                        line = line.dash(vec![4.0, 3.0]);
+                    }
+                    renderer.line((x0, y0), (x1, y1), &line);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_violin_outline() {
+        let data = vec![1.0, 2.0, 2.0, 3.0];
+        let plot = Plot::violin(data).violin_width(1.0);
+        let outline = plot.violin_outline().unwrap();
+        assert_eq!(outline.values.first(), Some(&1.0));
+        assert_eq!(outline.values.last(), Some(&3.0));
+
+        // Symmetric data peaks in the middle at half the violin width
+        let widest = outline.half_widths.iter().copied().fold(0.0, f64::max);
+        assert_eq!(widest, 0.5);
+        assert!((outline.half_width_at(2.0) - 0.5).abs() < 1e-3);
+        assert!(outline.half_width_at(1.0) < 0.5);
+    }
+}